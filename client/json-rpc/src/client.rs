@@ -114,6 +114,13 @@ impl JsonRpcBatch {
         );
     }
 
+    pub fn add_get_account_state_with_proof_latest_request(&mut self, account: AccountAddress) {
+        self.add_request(
+            "get_account_state_with_proof_latest".to_string(),
+            vec![json!(account.to_string())],
+        );
+    }
+
     pub fn add_get_network_status_request(&mut self) {
         self.add_request("get_network_status".to_string(), vec![]);
     }
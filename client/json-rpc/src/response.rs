@@ -61,7 +61,11 @@ impl TryFrom<(String, Value)> for JsonRpcResponse {
                 let info: Vec<CurrencyInfoView> = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::CurrenciesResponse(info))
             }
-            "get_account_state_with_proof" => {
+            // raw mode returns the LCS serialized account state with proof as a hex string
+            "get_account_state_with_proof" if value.is_string() => {
+                Ok(JsonRpcResponse::UnknownResponse(value))
+            }
+            "get_account_state_with_proof" | "get_account_state_with_proof_latest" => {
                 let account_with_proof: AccountStateWithProofView = serde_json::from_value(value)?;
                 Ok(JsonRpcResponse::AccountStateWithProofResponse(
                     account_with_proof,
@@ -22,6 +22,8 @@ pub use prometheus::{
     register_int_gauge, register_int_gauge_vec, Histogram, HistogramVec, IntCounter, IntCounterVec,
     IntGauge, IntGaugeVec,
 };
+// Re-export to read the labels of counter vectors
+pub use prometheus::core::Collector;
 
 use anyhow::Result;
 use libra_logger::prelude::*;
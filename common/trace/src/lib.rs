@@ -9,6 +9,7 @@ use serde::Deserialize;
 use serde_json::json;
 
 pub mod counters;
+pub mod request_id;
 pub mod trace;
 
 pub mod prelude {
@@ -0,0 +1,47 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Id of the client request being served, shared by the layers serving it so their traces can be
+//! correlated
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+thread_local! {
+    static REQUEST_ID: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Returns the id of the request being served by the current task, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.with(|request_id| request_id.borrow().clone())
+}
+
+/// Runs the given future with `request_id` as the current request id
+pub fn with_request_id<F: Future>(request_id: String, future: F) -> WithRequestId<F> {
+    WithRequestId {
+        request_id,
+        future: Box::pin(future),
+    }
+}
+
+/// Future setting the current request id whenever it is polled
+pub struct WithRequestId<F> {
+    request_id: String,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for WithRequestId<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let request_id = self.request_id.clone();
+        let previous = REQUEST_ID.with(|current| current.replace(Some(request_id)));
+        let result = self.future.as_mut().poll(cx);
+        REQUEST_ID.with(|current| *current.borrow_mut() = previous);
+        result
+    }
+}
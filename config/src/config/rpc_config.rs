@@ -3,12 +3,138 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::net::SocketAddr;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct RpcConfig {
     pub address: SocketAddr,
+    /// Number of consecutive storage failures after which read methods are short-circuited.
+    /// 0 disables the storage circuit breaker
+    pub storage_failure_threshold: u64,
+    /// Time in milliseconds read methods are short-circuited for before storage is probed again
+    pub storage_cooldown_ms: u64,
+    /// Path of a read replica of the DB that reads fail over to. None disables failover.
+    /// The read replica is opened read-only at startup and isn't refreshed afterwards: it serves
+    /// the ledger as of startup, falling further behind the primary DB as it advances
+    pub secondary_db_dir: Option<PathBuf>,
+    /// When reads fail over to the read replica
+    pub failover_policy: FailoverPolicy,
+    /// Log which DB served each read that failed over to the read replica
+    pub log_failover: bool,
+    /// Maximum number of versions the read replica can lag the primary DB by for failed reads to
+    /// fail over to it. Beyond it the error of the primary DB is returned. 0 disables the limit
+    pub max_secondary_lag: u64,
+    /// Maximum number of requests handled concurrently, further requests are rejected as
+    /// overloaded. 0 disables the limit
+    pub max_inflight_requests: u64,
+    /// Path of the PEM encoded TLS certificate chain. None serves plain HTTP
+    pub tls_cert_path: Option<PathBuf>,
+    /// Path of the PEM encoded TLS private key. None serves plain HTTP
+    pub tls_key_path: Option<PathBuf>,
+    /// Interval in milliseconds at which the TLS certificate and key are checked for changes and
+    /// reloaded in place, without restarting the listener. 0 disables reloading
+    pub tls_reload_interval_ms: u64,
+    /// Methods that can only be called with an API key allowing them
+    pub restricted_methods: Vec<String>,
+    /// Restricted methods each API key, presented in the `x-api-key` header, is allowed to call
+    pub api_keys: BTreeMap<String, Vec<String>>,
+    /// Whether transactions are returned with their events when the `include_events` param is
+    /// absent. An explicit `include_events` param always takes precedence
+    pub include_events_by_default: bool,
+    /// Maximum size in bytes of the serialized result of `get_transactions` and `get_events`.
+    /// Larger results fail, so the client retries with a smaller limit. 0 disables the cap
+    pub max_response_size: u64,
+    /// Maximum number of events returned with each transaction listed by `get_transactions`,
+    /// further events are left out of the transaction. 0 disables the cap
+    pub max_events_per_transaction: u64,
+    /// Maximum total number of events returned with the transactions listed by a
+    /// `get_transactions` call, events past it are left out of their transaction. 0 disables the
+    /// cap
+    pub max_transactions_events: u64,
+    /// Maximum total cost of the requests of a batch, larger batches are rejected before any of
+    /// their requests is executed. 0 disables the limit
+    pub batch_cost_budget: u64,
+    /// Cost of a request per method, overriding the default costs. The cost of `get_transactions`
+    /// and `get_events` requests is multiplied by their limit
+    pub method_costs: BTreeMap<String, u64>,
+    /// Time in milliseconds the results of a method are reused for identical calls at the same
+    /// ledger version, per method. Methods not listed aren't cached
+    pub cached_methods: BTreeMap<String, u64>,
+    /// Path of the file `submit` calls and calls to restricted methods are audited to, as JSON
+    /// lines. None disables the audit log
+    pub audit_log_path: Option<PathBuf>,
+    /// Whether `submit` calls are rejected while the audit log is unwritable, rather than
+    /// accepted without an audit entry
+    pub audit_log_strict: bool,
+    /// Time in milliseconds during which `submit` rejects a transaction reusing the sender and
+    /// sequence number of a different, accepted transaction. 0 disables replay protection
+    pub replay_protection_window_ms: u64,
+    /// Time in milliseconds during which transactions submitted concurrently are gathered and
+    /// sent to mempool together. 0 sends each transaction to mempool as soon as it's submitted
+    pub submit_batch_window_ms: u64,
+    /// Limits of the calls to each method on full nodes, which serve the public, by method name.
+    /// Validators only serve internal clients, so aren't bound by them
+    pub full_node_method_limits: BTreeMap<String, MethodLimits>,
+    /// What `get_network_status` and `get_network_status_by_network` return on public full nodes,
+    /// which have no VFN network configured. Validators and VFNs always return the full status
+    pub public_network_status: NetworkStatusPolicy,
+    /// Number of threads of the pool the methods reading storage run on. 0 runs them on the
+    /// threads of the JSON RPC runtime instead
+    pub read_pool_threads: u64,
+    /// Maximum number of reads waiting for a thread of the read pool, further reads are rejected
+    /// as overloaded
+    pub read_pool_queue_size: u64,
+    /// Maximum number of client connections open at once, further connections are closed right
+    /// away, TLS handshakes in progress included. 0 disables the limit
+    pub max_connections: u64,
+    /// Time in milliseconds after which connections without any activity are closed. 0 disables
+    /// the timeout
+    pub connection_idle_timeout_ms: u64,
+    /// Whether unsigned integers of results are serialized as strings for clients not asking for
+    /// a format in the `x-u64-format` header
+    pub u64_as_string_by_default: bool,
+    /// Whether the latest ledger info and the currencies info are read from storage before
+    /// requests are served, so the first requests don't pay for reading them from disk
+    pub warmup: bool,
+    /// Endpoint of the OpenTelemetry collector a span per JSON RPC call is exported to over
+    /// OTLP, e.g. `http://localhost:4317`. Only honored by nodes built with the `otlp` feature of
+    /// `libra-json-rpc`. None disables the export
+    pub otlp_endpoint: Option<String>,
+}
+
+/// Bounds on the calls to a method
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MethodLimits {
+    /// Maximum number of items a call can request, i.e. its limit or the number of items it
+    /// lists, for the methods whose cost grows with it. 0 if unlimited
+    pub max_items: u64,
+    /// Maximum number of calls handled at once, further calls are rejected as overloaded. 0 if
+    /// unlimited
+    pub max_concurrent: u64,
+}
+
+/// What `get_network_status` and `get_network_status_by_network` disclose on public full nodes
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkStatusPolicy {
+    /// The method isn't available, calls fail with method not found
+    Disabled,
+    /// Only the total number of connected peers, without the per network breakdown
+    Reduced,
+    /// The total number of connected peers and the number of peers on each network
+    Full,
+}
+
+/// When reads fail over from the primary DB to the read replica
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverPolicy {
+    /// Retry against the read replica whenever a read from the primary DB fails
+    OnError,
+    /// Read from the read replica when the requested state version is pruned from the primary DB
+    OnPruned,
 }
 
 pub const DEFAULT_JSON_RPC_PORT: u16 = 8080;
@@ -19,6 +145,38 @@ impl Default for RpcConfig {
             address: format!("0.0.0.0:{}", DEFAULT_JSON_RPC_PORT)
                 .parse()
                 .unwrap(),
+            storage_failure_threshold: 10,
+            storage_cooldown_ms: 5_000,
+            secondary_db_dir: None,
+            failover_policy: FailoverPolicy::OnError,
+            log_failover: false,
+            max_secondary_lag: 10_000,
+            max_inflight_requests: 10_000,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_reload_interval_ms: 60_000,
+            restricted_methods: vec![],
+            api_keys: BTreeMap::new(),
+            include_events_by_default: false,
+            max_response_size: 10 * 1024 * 1024,
+            max_events_per_transaction: 1_000,
+            max_transactions_events: 10_000,
+            batch_cost_budget: 10_000,
+            method_costs: BTreeMap::new(),
+            cached_methods: BTreeMap::new(),
+            audit_log_path: None,
+            audit_log_strict: false,
+            replay_protection_window_ms: 0,
+            submit_batch_window_ms: 0,
+            full_node_method_limits: BTreeMap::new(),
+            public_network_status: NetworkStatusPolicy::Disabled,
+            read_pool_threads: 0,
+            read_pool_queue_size: 1_000,
+            max_connections: 10_000,
+            connection_idle_timeout_ms: 60_000,
+            u64_as_string_by_default: false,
+            warmup: true,
+            otlp_endpoint: None,
         }
     }
 }
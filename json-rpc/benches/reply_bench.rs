@@ -0,0 +1,96 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measures the time and the heap allocations of serializing `get_transactions` replies
+//!
+//! Run with `cargo bench -p libra-json-rpc --features fuzzing`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use libra_json_rpc::json_reply;
+use serde_json::Value;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Counts the heap allocations of the benchmark
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns a `get_transactions` response listing the given number of user transactions
+fn get_transactions_response(limit: u64) -> Value {
+    let transactions: Vec<_> = (0..limit)
+        .map(|version| {
+            serde_json::json!({
+                "version": version,
+                "hash": "a".repeat(64),
+                "bytes": 300,
+                "transaction": {
+                    "type": "user",
+                    "sender": "0".repeat(32),
+                    "signature_scheme": "Scheme::Ed25519",
+                    "signature": "b".repeat(128),
+                    "public_key": "c".repeat(64),
+                    "sequence_number": version,
+                    "chain_id": 4,
+                    "max_gas_amount": 1_000_000,
+                    "gas_unit_price": 0,
+                    "gas_currency": "LBR",
+                    "expiration_timestamp_secs": 1_600_000_000,
+                    "script_hash": "d".repeat(64),
+                    "script_bytes": "e".repeat(400),
+                    "script": {"type": "unknown"},
+                },
+                "events": [],
+                "vm_status": {"type": "executed"},
+                "gas_used": 500,
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "id": 1,
+        "jsonrpc": "2.0",
+        "libra_chain_id": 4,
+        "libra_ledger_timestampusec": 1_600_000_000_000_000u64,
+        "libra_ledger_version": limit,
+        "result": transactions,
+    })
+}
+
+fn reply_bench(c: &mut Criterion) {
+    for limit in &[10, 100, 1000] {
+        let response = get_transactions_response(*limit);
+
+        // once the reply buffer has grown, replies only allocate their response and headers
+        drop(json_reply(&response, false));
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        drop(json_reply(&response, false));
+        println!(
+            "get_transactions reply of {} transactions: {} allocations",
+            limit,
+            ALLOCATIONS.load(Ordering::Relaxed) - allocations
+        );
+
+        c.bench_function(&format!("get_transactions_reply_{}", limit), |b| {
+            b.iter(|| json_reply(&response, false))
+        });
+    }
+}
+
+criterion_group!(benches, reply_bench);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    env,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Save revision and build time info to environment variables
+fn main() {
+    if env::var("GIT_REV").is_err() {
+        let output = Command::new("git")
+            .args(&["rev-parse", "--short", "HEAD"])
+            .output()
+            .unwrap();
+        let git_rev = String::from_utf8(output.stdout).unwrap();
+        println!("cargo:rustc-env=GIT_REV={}", git_rev);
+    }
+    if env::var("BUILD_TIMESTAMP").is_err() {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        println!("cargo:rustc-env=BUILD_TIMESTAMP={}", timestamp);
+    }
+}
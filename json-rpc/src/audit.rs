@@ -0,0 +1,165 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Audit log of transaction submissions and calls to restricted methods
+//!
+//! Entries are appended to a dedicated file as JSON lines, separately from the debug logs. Every
+//! entry carries the hash of the line preceding it, so that removing or editing an entry breaks
+//! the chain. Entries never contain API keys or other key material.
+//!
+//! A write failure makes the audit log unwritable until the node restarts. In strict mode,
+//! submissions are then rejected rather than accepted without an audit record.
+use crate::errors::JsonRpcError;
+use anyhow::Result;
+use libra_crypto::HashValue;
+use libra_logger::prelude::*;
+use serde::Serialize;
+use std::{
+    fs::{File, OpenOptions},
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Number of bytes read at a time from the end of the audit log, looking for its last line
+const TAIL_CHUNK_SIZE: u64 = 4096;
+
+/// Transaction audited by a `submit` entry
+#[derive(Debug, Serialize)]
+pub(crate) struct AuditedTransaction {
+    pub sender: String,
+    pub sequence_number: u64,
+    pub hash: String,
+}
+
+/// Entry of the audit log
+#[derive(Debug, Serialize)]
+pub(crate) struct AuditEntry<'a> {
+    pub method: &'a str,
+    pub request_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<AuditedTransaction>,
+    /// "success", or the error the call failed with
+    pub result: String,
+}
+
+#[derive(Serialize)]
+struct AuditLine<'a> {
+    timestamp_usecs: u64,
+    previous_hash: String,
+    #[serde(flatten)]
+    entry: &'a AuditEntry<'a>,
+}
+
+struct AuditFile {
+    file: File,
+    /// Hash of the last line of the file
+    last_hash: HashValue,
+}
+
+/// Append-only audit log file
+pub(crate) struct AuditLog {
+    /// None once the file is unwritable
+    file: Mutex<Option<AuditFile>>,
+    /// Whether submissions are rejected while the file is unwritable
+    strict: bool,
+}
+
+impl AuditLog {
+    pub fn new(path: &Path, strict: bool) -> Self {
+        let file = Self::open(path)
+            .map_err(|err| {
+                error!(
+                    "[json-rpc] failed to open audit log {}: {}",
+                    path.display(),
+                    err
+                )
+            })
+            .ok();
+        Self {
+            file: Mutex::new(file),
+            strict,
+        }
+    }
+
+    fn open(path: &Path) -> Result<AuditFile> {
+        // resume the hash chain from the last entry written before a restart
+        let last_hash = last_line_hash(path)?;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditFile { file, last_hash })
+    }
+
+    /// Fails if submissions must be rejected, because the audit log is unwritable in strict mode
+    pub fn ensure_writable(&self) -> Result<(), JsonRpcError> {
+        let file = self.file.lock().expect("failed to lock audit log");
+        if self.strict && file.is_none() {
+            Err(JsonRpcError::audit_log_unavailable())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn record(&self, entry: &AuditEntry) {
+        let mut file = self.file.lock().expect("failed to lock audit log");
+        let audit_file = match file.as_mut() {
+            Some(audit_file) => audit_file,
+            None => {
+                error!("[json-rpc] audit log unwritable, dropped entry {:?}", entry);
+                return;
+            }
+        };
+        if let Err(err) = Self::append(audit_file, entry) {
+            error!(
+                "[json-rpc] failed to write audit log, dropped entry {:?}: {}",
+                entry, err
+            );
+            *file = None;
+        }
+    }
+
+    fn append(audit_file: &mut AuditFile, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(&AuditLine {
+            timestamp_usecs: SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64,
+            previous_hash: audit_file.last_hash.to_hex(),
+            entry,
+        })?;
+        let hash = HashValue::sha3_256_of(&line);
+        line.push(b'\n');
+        audit_file.file.write_all(&line)?;
+        audit_file.file.flush()?;
+        audit_file.last_hash = hash;
+        Ok(())
+    }
+}
+
+/// Returns the hash of the last line of the given file, zero if it has none. Only the tail of the
+/// file is read, back to the start of its last line
+fn last_line_hash(path: &Path) -> Result<HashValue> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(HashValue::zero()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut start = file.metadata()?.len();
+    let mut tail = vec![];
+    loop {
+        let line_end = tail
+            .iter()
+            .rposition(|byte| *byte != b'\n')
+            .map_or(0, |position| position + 1);
+        match tail[..line_end].iter().rposition(|byte| *byte == b'\n') {
+            Some(position) => return Ok(HashValue::sha3_256_of(&tail[position + 1..line_end])),
+            None if start == 0 && line_end == 0 => return Ok(HashValue::zero()),
+            None if start == 0 => return Ok(HashValue::sha3_256_of(&tail[..line_end])),
+            None => (),
+        }
+        let chunk_start = start.saturating_sub(TAIL_CHUNK_SIZE);
+        let mut chunk = vec![0; (start - chunk_start) as usize];
+        file.seek(SeekFrom::Start(chunk_start))?;
+        file.read_exact(&mut chunk)?;
+        chunk.append(&mut tail);
+        tail = chunk;
+        start = chunk_start;
+    }
+}
@@ -0,0 +1,320 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Circuit breaker protecting a struggling storage layer from request amplification
+//!
+//! After `failure_threshold` consecutive storage failures the breaker opens and every read fails
+//! fast with a "storage unavailable" JSON RPC error. Once `cooldown` has elapsed, a single read is
+//! let through as a probe while the others keep failing fast: its success closes the breaker,
+//! its failure re-opens it.
+//!
+//! Only failures of storage itself, such as I/O or internal errors, count toward the threshold.
+//! Reads of versions or epochs ahead of the ledger are rejected before reaching storage, and
+//! neither errors caused by the read (a `LibraDbError` such as an item not found) nor failures of
+//! reads at versions pruned from storage are counted, so bursts of bad requests can't open the
+//! breaker.
+use crate::{counters, errors::JsonRpcError};
+use anyhow::{ensure, Error, Result};
+use libra_crypto::HashValue;
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{AccumulatorConsistencyProof, SparseMerkleProof},
+    transaction::{TransactionListWithProof, TransactionWithProof, Version},
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use storage_interface::{errors::LibraDbError, DbReader, StartupInfo, TreeState};
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u64,
+    opened_at: Option<Instant>,
+    /// Whether a read is let through as a probe of the open breaker
+    probing: bool,
+}
+
+/// Tracks consecutive storage failures and whether reads should be short-circuited
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u64,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u64, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Returns whether a read is let through: always while the breaker is closed, never during
+    /// its cooldown, and a single probe at a time afterwards. A read let through must be followed
+    /// by `record` or `release`
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.state.lock().expect("failed to lock breaker state");
+        match state.opened_at {
+            None => true,
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => false,
+            Some(_) if state.probing => false,
+            Some(_) => {
+                state.probing = true;
+                true
+            }
+        }
+    }
+
+    pub fn record(&self, success: bool) {
+        let mut state = self.state.lock().expect("failed to lock breaker state");
+        state.probing = false;
+        if success {
+            state.consecutive_failures = 0;
+            if state.opened_at.take().is_some() {
+                counters::STORAGE_CIRCUIT_BREAKER_OPEN.set(0);
+            }
+        } else {
+            state.consecutive_failures += 1;
+            if self.failure_threshold > 0 && state.consecutive_failures >= self.failure_threshold {
+                state.opened_at = Some(Instant::now());
+                counters::STORAGE_CIRCUIT_BREAKER_OPEN.set(1);
+            }
+        }
+    }
+
+    /// Ends a read whose outcome tells nothing about storage, letting another probe through
+    pub fn release(&self) {
+        self.state
+            .lock()
+            .expect("failed to lock breaker state")
+            .probing = false;
+    }
+}
+
+/// `DbReader` wrapper guarding every read of the wrapped reader with a `CircuitBreaker`
+pub(crate) struct CircuitBreakerDb {
+    db: Arc<dyn DbReader>,
+    breaker: CircuitBreaker,
+    /// Number of versions of state kept by storage, None if storage isn't pruned
+    prune_window: Option<u64>,
+}
+
+impl CircuitBreakerDb {
+    pub fn new(db: Arc<dyn DbReader>, breaker: CircuitBreaker, prune_window: Option<u64>) -> Self {
+        Self {
+            db,
+            breaker,
+            prune_window,
+        }
+    }
+
+    /// Runs the given storage read unless the breaker is open, and records its outcome
+    fn call<T>(&self, read: impl FnOnce(&dyn DbReader) -> Result<T>) -> Result<T> {
+        self.call_at(None, read)
+    }
+
+    /// Runs the given read of the state at the given version unless the breaker is open, and
+    /// records its outcome. Failures of reads at pruned versions aren't counted, as storage
+    /// doesn't hold their state
+    fn call_at<T>(
+        &self,
+        state_version: Option<Version>,
+        read: impl FnOnce(&dyn DbReader) -> Result<T>,
+    ) -> Result<T> {
+        if !self.breaker.try_acquire() {
+            return Err(Error::new(JsonRpcError::storage_unavailable()));
+        }
+        let result = read(self.db.as_ref());
+        match &result {
+            Ok(_) => self.breaker.record(true),
+            Err(error)
+                if is_storage_failure(error)
+                    && !state_version.map_or(false, |v| self.is_pruned(v)) =>
+            {
+                self.breaker.record(false)
+            }
+            Err(_) => self.breaker.release(),
+        }
+        result
+    }
+
+    /// Returns whether the state at the given version has been pruned from storage
+    fn is_pruned(&self, version: Version) -> bool {
+        match (self.prune_window, self.db.get_latest_ledger_info()) {
+            (Some(prune_window), Ok(ledger_info)) => {
+                version.saturating_add(prune_window) < ledger_info.ledger_info().version()
+            }
+            _ => false,
+        }
+    }
+
+    /// Fails reads of versions ahead of the latest ledger version, before they reach storage
+    fn ensure_version(&self, version: Version) -> Result<()> {
+        let latest_version = self.db.get_latest_ledger_info()?.ledger_info().version();
+        ensure!(
+            version <= latest_version,
+            "version {} is ahead of the latest version {}",
+            version,
+            latest_version
+        );
+        Ok(())
+    }
+}
+
+/// Whether the given storage error is a failure of storage itself rather than one caused by the
+/// read, which storage reports as a `LibraDbError`
+fn is_storage_failure(error: &Error) -> bool {
+    !error.chain().any(|cause| cause.is::<LibraDbError>())
+}
+
+impl DbReader for CircuitBreakerDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        let latest_epoch = self
+            .db
+            .get_latest_ledger_info()?
+            .ledger_info()
+            .next_block_epoch();
+        ensure!(
+            start_epoch <= end_epoch && end_epoch <= latest_epoch,
+            "invalid epoch range [{}, {}), the latest epoch is {}",
+            start_epoch,
+            end_epoch,
+            latest_epoch
+        );
+        self.call(|db| db.get_epoch_ending_ledger_infos(start_epoch, end_epoch))
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.ensure_version(ledger_version)?;
+        self.call(|db| db.get_transactions(start_version, batch_size, ledger_version, fetch_events))
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        ascending: bool,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        ensure!(limit > 0, "limit must be positive");
+        self.call(|db| db.get_events(event_key, start, ascending, limit))
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        self.ensure_version(version)?;
+        self.call(|db| db.get_block_timestamp(version))
+    }
+
+    fn get_latest_account_state(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<AccountStateBlob>> {
+        self.call(|db| db.get_latest_account_state(address))
+    }
+
+    /// The latest ledger info is served from memory and is needed to build the response envelope,
+    /// so it bypasses the breaker
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        self.db.get_latest_ledger_info()
+    }
+
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        self.call(|db| db.get_startup_info())
+    }
+
+    fn get_txn_by_account(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.ensure_version(ledger_version)?;
+        self.call(|db| db.get_txn_by_account(address, seq_num, ledger_version, fetch_events))
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(EpochChangeProof, AccumulatorConsistencyProof)> {
+        ensure!(
+            known_version <= ledger_info.ledger_info().version(),
+            "known version {} is ahead of the ledger info version {}",
+            known_version,
+            ledger_info.ledger_info().version()
+        );
+        self.call(|db| db.get_state_proof_with_ledger_info(known_version, ledger_info))
+    }
+
+    fn get_state_proof(
+        &self,
+        known_version: u64,
+    ) -> Result<(
+        LedgerInfoWithSignatures,
+        EpochChangeProof,
+        AccumulatorConsistencyProof,
+    )> {
+        self.ensure_version(known_version)?;
+        self.call(|db| db.get_state_proof(known_version))
+    }
+
+    fn get_account_state_with_proof(
+        &self,
+        address: AccountAddress,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<AccountStateWithProof> {
+        ensure!(
+            version <= ledger_version,
+            "version {} is ahead of the ledger version {}",
+            version,
+            ledger_version
+        );
+        self.ensure_version(ledger_version)?;
+        self.call_at(Some(version), |db| {
+            db.get_account_state_with_proof(address, version, ledger_version)
+        })
+    }
+
+    fn get_account_state_with_proof_by_version(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        self.ensure_version(version)?;
+        self.call_at(Some(version), |db| {
+            db.get_account_state_with_proof_by_version(address, version)
+        })
+    }
+
+    fn get_latest_state_root(&self) -> Result<(Version, HashValue)> {
+        self.call(|db| db.get_latest_state_root())
+    }
+
+    fn get_latest_tree_state(&self) -> Result<TreeState> {
+        self.call(|db| db.get_latest_tree_state())
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.ensure_version(known_version)?;
+        self.call(|db| db.get_epoch_ending_ledger_info(known_version))
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Transport level limits on client connections
+//!
+//! At most `max_connections` connections are served at once: connections accepted past the cap
+//! are closed right away. Connections without any read or write for `idle_timeout` are closed, so
+//! clients holding connections open without using them can't exhaust the cap. Since requests are
+//! read before they are handled, a request taking longer than `idle_timeout` to handle has its
+//! connection closed too.
+//!
+//! The limits apply to TLS as well, as connections are counted from their acceptance: a TLS
+//! connection still in its handshake holds a slot and is closed once idle for `idle_timeout`.
+use crate::counters;
+use futures::{ready, Future, Stream};
+use libra_logger::prelude::*;
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+    time::{self, Delay, Instant},
+};
+
+/// Time to wait before accepting connections again after failing to accept one, e.g. because the
+/// process ran out of file descriptors
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Connections accepted from a listener, up to `max_connections` at once
+pub(crate) struct LimitedIncoming {
+    listener: TcpListener,
+    connections: Arc<AtomicU64>,
+    /// 0 if unlimited
+    max_connections: u64,
+    /// None if connections are never closed for being idle
+    idle_timeout: Option<Duration>,
+    backoff: Option<Delay>,
+}
+
+impl LimitedIncoming {
+    pub fn new(
+        listener: TcpListener,
+        max_connections: u64,
+        idle_timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            listener,
+            connections: Arc::new(AtomicU64::new(0)),
+            max_connections,
+            idle_timeout,
+            backoff: None,
+        }
+    }
+}
+
+impl Stream for LimitedIncoming {
+    type Item = io::Result<LimitedConnection>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(backoff) = self.backoff.as_mut() {
+                ready!(Pin::new(backoff).poll(cx));
+                self.backoff = None;
+            }
+            // accept errors are never returned, as they would stop the server
+            let stream = match ready!(self.listener.poll_accept(cx)) {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    warn!("[rpc] failed to accept connection: {}", err);
+                    self.backoff = Some(time::delay_for(ACCEPT_ERROR_BACKOFF));
+                    continue;
+                }
+            };
+            let count = self.connections.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.max_connections > 0 && count > self.max_connections {
+                self.connections.fetch_sub(1, Ordering::SeqCst);
+                counters::REJECTED_CONNECTIONS.inc();
+                continue;
+            }
+            counters::CONNECTIONS.inc();
+            let idle = self
+                .idle_timeout
+                .map(|timeout| (timeout, time::delay_for(timeout)));
+            return Poll::Ready(Some(Ok(LimitedConnection {
+                stream,
+                idle,
+                connections: Arc::clone(&self.connections),
+            })));
+        }
+    }
+}
+
+/// Connection counted against the cap until dropped, failing with a timeout once idle
+pub(crate) struct LimitedConnection {
+    stream: TcpStream,
+    /// Idle timeout, and the delay until the connection times out
+    idle: Option<(Duration, Delay)>,
+    connections: Arc<AtomicU64>,
+}
+
+impl LimitedConnection {
+    /// Pushes the idle deadline back if the connection made progress, and fails it if it's been
+    /// idle for too long
+    fn check_idle<T>(
+        &mut self,
+        cx: &mut Context,
+        poll: Poll<io::Result<T>>,
+    ) -> Poll<io::Result<T>> {
+        if let Some((timeout, delay)) = self.idle.as_mut() {
+            if poll.is_ready() {
+                delay.reset(Instant::now() + *timeout);
+            } else if Pin::new(delay).poll(cx).is_ready() {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "connection idle for too long",
+                )));
+            }
+        }
+        poll
+    }
+}
+
+impl AsyncRead for LimitedConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_read(cx, buf);
+        self.check_idle(cx, poll)
+    }
+}
+
+impl AsyncWrite for LimitedConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.stream).poll_write(cx, buf);
+        self.check_idle(cx, poll)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let poll = Pin::new(&mut self.stream).poll_flush(cx);
+        self.check_idle(cx, poll)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+impl Drop for LimitedConnection {
+    fn drop(&mut self) {
+        self.connections.fetch_sub(1, Ordering::SeqCst);
+        counters::CONNECTIONS.dec();
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use libra_metrics::{register_int_gauge_vec, IntGaugeVec};
+use libra_metrics::{register_int_gauge, register_int_gauge_vec, IntGauge, IntGaugeVec};
 use once_cell::sync::Lazy;
 
 /// Cumulative number of valid requests that the JSON RPC client service receives
@@ -11,7 +11,7 @@ pub static REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
         "Cumulative number of requests that JSON RPC client service receives",
         &[
             "type",   // type of request, matches JSON RPC method name (e.g. "submit", "get_account")
-            "result", // result of request: "success", "fail"
+            "result", // result of request: "success", "fail" or why it was rejected (e.g. "overloaded")
         ]
     )
     .unwrap()
@@ -28,3 +28,109 @@ pub static INVALID_REQUESTS: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+/// Cumulative number of lookups of cached results, per method
+pub static RESPONSE_CACHE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "libra_client_service_response_cache_count",
+        "Cumulative number of lookups of cached JSON RPC results",
+        &[
+            "type",   // JSON RPC method name (e.g. "get_currencies")
+            "result", // "hit" or "miss"
+        ]
+    )
+    .unwrap()
+});
+
+/// Cumulative number of responses rejected for exceeding the maximum response size, per method
+pub static OVERSIZED_RESPONSES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "libra_client_service_oversized_responses_count",
+        "Cumulative number of JSON RPC responses rejected for exceeding the maximum response size",
+        &[
+            "type", // JSON RPC method name (e.g. "get_transactions")
+        ]
+    )
+    .unwrap()
+});
+
+/// Whether the storage circuit breaker is open (1) or closed (0)
+pub static STORAGE_CIRCUIT_BREAKER_OPEN: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_storage_circuit_breaker_open",
+        "Whether the JSON RPC storage circuit breaker is open"
+    )
+    .unwrap()
+});
+
+/// Number of requests that the JSON RPC client service is currently handling
+pub static INFLIGHT_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_inflight_requests",
+        "Number of requests that JSON RPC client service is currently handling"
+    )
+    .unwrap()
+});
+
+/// Number of storage reads waiting for a thread of the read pool
+pub static READ_POOL_QUEUE_DEPTH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_read_pool_queue_depth",
+        "Number of JSON RPC storage reads waiting for a thread of the read pool"
+    )
+    .unwrap()
+});
+
+/// Number of threads of the read pool currently running a storage read
+pub static READ_POOL_BUSY_THREADS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_read_pool_busy_threads",
+        "Number of threads of the JSON RPC read pool currently running a storage read"
+    )
+    .unwrap()
+});
+
+/// Number of client connections currently open
+pub static CONNECTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_connections",
+        "Number of client connections to the JSON RPC service currently open"
+    )
+    .unwrap()
+});
+
+/// Cumulative number of client connections closed right away, as the connection cap was reached
+pub static REJECTED_CONNECTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_rejected_connections_count",
+        "Cumulative number of client connections to the JSON RPC service rejected by the connection cap"
+    )
+    .unwrap()
+});
+
+/// Cumulative number of batches of submitted transactions sent to mempool
+pub static SUBMIT_BATCHES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_submit_batches_count",
+        "Cumulative number of batches of submitted transactions sent to mempool by the JSON RPC service"
+    )
+    .unwrap()
+});
+
+/// Cumulative number of submitted transactions sent to mempool in batches
+pub static SUBMIT_BATCHED_TRANSACTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_submit_batched_transactions_count",
+        "Cumulative number of submitted transactions sent to mempool in batches by the JSON RPC service"
+    )
+    .unwrap()
+});
+
+/// Latest version of the read replica reads fail over to, as of startup
+pub static SECONDARY_DB_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "libra_client_service_secondary_db_version",
+        "Latest version of the read replica the JSON RPC service fails over to"
+    )
+    .unwrap()
+});
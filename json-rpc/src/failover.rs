@@ -0,0 +1,271 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Failover of storage reads from the primary DB to a read replica
+//!
+//! With `FailoverPolicy::OnError`, any read failing on the primary DB is retried against the read
+//! replica. With `FailoverPolicy::OnPruned`, account state reads at a version older than the
+//! primary's prune window are served by the read replica directly.
+//!
+//! The read replica is a snapshot that isn't refreshed, so reads only fail over to it while it
+//! lags the primary DB by at most `max_secondary_lag` versions, and pruned state is only read
+//! from it at versions it holds. Its latest version is exported as a gauge.
+use crate::counters;
+use anyhow::Result;
+use libra_config::config::FailoverPolicy;
+use libra_crypto::HashValue;
+use libra_logger::prelude::*;
+use libra_types::{
+    account_address::AccountAddress,
+    account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{AccumulatorConsistencyProof, SparseMerkleProof},
+    transaction::{TransactionListWithProof, TransactionWithProof, Version},
+};
+use std::sync::Arc;
+use storage_interface::{DbReader, StartupInfo, TreeState};
+
+/// `DbReader` reading from a primary DB and failing over to a read replica
+pub(crate) struct FailoverDb {
+    primary: Arc<dyn DbReader>,
+    secondary: Arc<dyn DbReader>,
+    policy: FailoverPolicy,
+    prune_window: Option<u64>,
+    max_secondary_lag: u64,
+    log_failover: bool,
+}
+
+impl FailoverDb {
+    pub fn new(
+        primary: Arc<dyn DbReader>,
+        secondary: Arc<dyn DbReader>,
+        policy: FailoverPolicy,
+        prune_window: Option<u64>,
+        max_secondary_lag: u64,
+        log_failover: bool,
+    ) -> Self {
+        match secondary.get_latest_version() {
+            Ok(version) => counters::SECONDARY_DB_VERSION.set(version as i64),
+            Err(err) => warn!("[json-rpc] failed to read secondary DB version: {}", err),
+        }
+        Self {
+            primary,
+            secondary,
+            policy,
+            prune_window,
+            max_secondary_lag,
+            log_failover,
+        }
+    }
+
+    /// Returns whether the read replica is recent enough for failed reads to fail over to it.
+    /// When the version of either DB can't be read, failing over is allowed
+    fn is_secondary_recent(&self) -> bool {
+        if self.max_secondary_lag == 0 {
+            return true;
+        }
+        match (
+            self.primary.get_latest_version(),
+            self.secondary.get_latest_version(),
+        ) {
+            (Ok(primary_version), Ok(secondary_version)) => {
+                primary_version.saturating_sub(secondary_version) <= self.max_secondary_lag
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns whether the read replica holds the state at the given version
+    fn secondary_holds(&self, version: Version) -> bool {
+        self.secondary
+            .get_latest_version()
+            .map_or(false, |latest_version| version <= latest_version)
+    }
+
+    /// Returns whether the state at the given version has been pruned from the primary DB
+    fn is_pruned(&self, version: Version) -> bool {
+        match (self.prune_window, self.primary.get_latest_version()) {
+            (Some(prune_window), Ok(latest_version)) => {
+                version.saturating_add(prune_window) < latest_version
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs the given read against the DB selected by the failover policy.
+    /// `state_version` is the version of the account state read, if any
+    fn read<T>(
+        &self,
+        name: &str,
+        state_version: Option<Version>,
+        read: impl Fn(&dyn DbReader) -> Result<T>,
+    ) -> Result<T> {
+        match self.policy {
+            FailoverPolicy::OnError => read(self.primary.as_ref()).or_else(|err| {
+                if !self.is_secondary_recent() {
+                    if self.log_failover {
+                        debug!(
+                            "[json-rpc] {} failed on primary DB ({}), secondary DB lags too far behind",
+                            name, err
+                        );
+                    }
+                    return Err(err);
+                }
+                if self.log_failover {
+                    debug!(
+                        "[json-rpc] {} failed on primary DB ({}), reading from secondary DB",
+                        name, err
+                    );
+                }
+                read(self.secondary.as_ref())
+            }),
+            FailoverPolicy::OnPruned => match state_version {
+                Some(version) if self.is_pruned(version) && self.secondary_holds(version) => {
+                    if self.log_failover {
+                        debug!(
+                            "[json-rpc] {} at pruned version {}, reading from secondary DB",
+                            name, version
+                        );
+                    }
+                    read(self.secondary.as_ref())
+                }
+                _ => read(self.primary.as_ref()),
+            },
+        }
+    }
+}
+
+impl DbReader for FailoverDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        self.read("get_epoch_ending_ledger_infos", None, |db| {
+            db.get_epoch_ending_ledger_infos(start_epoch, end_epoch)
+        })
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        self.read("get_transactions", None, |db| {
+            db.get_transactions(start_version, batch_size, ledger_version, fetch_events)
+        })
+    }
+
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        ascending: bool,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        self.read("get_events", None, |db| {
+            db.get_events(event_key, start, ascending, limit)
+        })
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        self.read("get_block_timestamp", None, |db| {
+            db.get_block_timestamp(version)
+        })
+    }
+
+    fn get_latest_account_state(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<AccountStateBlob>> {
+        self.read("get_latest_account_state", None, |db| {
+            db.get_latest_account_state(address)
+        })
+    }
+
+    /// The primary DB is the source of truth for the latest ledger state
+    fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
+        self.primary.get_latest_ledger_info()
+    }
+
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        self.primary.get_startup_info()
+    }
+
+    fn get_txn_by_account(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        self.read("get_txn_by_account", None, |db| {
+            db.get_txn_by_account(address, seq_num, ledger_version, fetch_events)
+        })
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<(EpochChangeProof, AccumulatorConsistencyProof)> {
+        self.read("get_state_proof_with_ledger_info", None, |db| {
+            db.get_state_proof_with_ledger_info(known_version, ledger_info.clone())
+        })
+    }
+
+    fn get_state_proof(
+        &self,
+        known_version: u64,
+    ) -> Result<(
+        LedgerInfoWithSignatures,
+        EpochChangeProof,
+        AccumulatorConsistencyProof,
+    )> {
+        self.read("get_state_proof", None, |db| {
+            db.get_state_proof(known_version)
+        })
+    }
+
+    fn get_account_state_with_proof(
+        &self,
+        address: AccountAddress,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<AccountStateWithProof> {
+        self.read("get_account_state_with_proof", Some(version), |db| {
+            db.get_account_state_with_proof(address, version, ledger_version)
+        })
+    }
+
+    fn get_account_state_with_proof_by_version(
+        &self,
+        address: AccountAddress,
+        version: Version,
+    ) -> Result<(Option<AccountStateBlob>, SparseMerkleProof)> {
+        self.read(
+            "get_account_state_with_proof_by_version",
+            Some(version),
+            |db| db.get_account_state_with_proof_by_version(address, version),
+        )
+    }
+
+    fn get_latest_state_root(&self) -> Result<(Version, HashValue)> {
+        self.primary.get_latest_state_root()
+    }
+
+    fn get_latest_tree_state(&self) -> Result<TreeState> {
+        self.primary.get_latest_tree_state()
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        self.read("get_epoch_ending_ledger_info", None, |db| {
+            db.get_epoch_ending_ledger_info(known_version)
+        })
+    }
+}
@@ -9,16 +9,40 @@
 //! Protocol specification: https://www.jsonrpc.org/specification
 //!
 //! Module organization:
+//! ├── audit.rs          # audit log of submissions and calls to restricted methods
+//! ├── circuit_breaker.rs # protects storage from request amplification during outages
+//! ├── connections.rs    # limits on the number and idleness of client connections
+//! ├── failover.rs       # fails storage reads over to a read replica
 //! ├── methods.rs        # contains all available JSON RPC method handlers
+//! ├── openrpc.rs        # OpenRPC description of the available methods
+//! ├── otel.rs           # export of request spans to OpenTelemetry collectors
+//! ├── read_pool.rs      # dedicated thread pool storage reads run on
+//! ├── replay_protection.rs # rejects transactions reusing a sequence number in flight
+//! ├── response_cache.rs # short-lived cache of the results of read methods
 //! ├── runtime.rs        # implementation of JSON RPC protocol over HTTP
+//! ├── serialization.rs  # serialization of responses with fewer allocations
+//! ├── submit_batcher.rs # coalesces concurrent submissions into batches sent to mempool
 //! ├── tests.rs          # tests
+//! ├── tls.rs            # TLS termination with certificates reloaded in place
 
 #[macro_use]
 mod util;
 
+mod audit;
+mod circuit_breaker;
+mod connections;
 mod counters;
+mod failover;
 mod methods;
+mod openrpc;
+mod otel;
+mod read_pool;
+mod replay_protection;
+mod response_cache;
 mod runtime;
+mod serialization;
+mod submit_batcher;
+mod tls;
 
 pub use libra_json_rpc_types::{errors, response, views};
 
@@ -30,4 +54,6 @@ pub mod fuzzing;
 #[cfg(any(test, feature = "fuzzing"))]
 mod tests;
 #[cfg(any(test, feature = "fuzzing"))]
+pub use serialization::json_reply;
+#[cfg(any(test, feature = "fuzzing"))]
 pub use tests::test_bootstrap;
@@ -3,35 +3,132 @@
 
 //! Module contains RPC method handlers for Full Node JSON-RPC interface
 use crate::{
+    audit::{AuditEntry, AuditLog, AuditedTransaction},
     errors::{ErrorData, InvalidArguments, JsonRpcError},
+    openrpc,
+    read_pool::ReadPool,
+    replay_protection::ReplayProtection,
+    response_cache::ResponseCache,
+    runtime::MethodLimiter,
+    serialization,
+    submit_batcher::SubmitBatcher,
     views::{
-        AccountStateWithProofView, AccountView, BlockMetadata, CurrencyInfoView, EventView,
-        StateProofView, TransactionView,
+        AccountDiffView, AccountRoleView, AccountStateWithProofResultView,
+        AccountStateWithProofView, AccountStateWithSparseProofView, AccountView, AmountView,
+        BlockMetadata, BytesView, CapabilitiesView, CurrencyInfoView, EpochChangeProofView,
+        EventView, EventsPageView, FetchResultView, GasEstimateView, LedgerInfoView, ModuleView,
+        NetworkStatusView, NodeConfigView, PeerView, ServerVersionView, StateProofView,
+        TransactionInfoView, TransactionView, TreasuryComplianceView, VMPublishingOptionView,
+        VMStatusView,
     },
 };
 use anyhow::{ensure, format_err, Error, Result};
 use core::future::Future;
 use futures::{channel::oneshot, SinkExt};
-use libra_config::config::RoleType;
-use libra_crypto::hash::CryptoHash;
-use libra_mempool::MempoolClientSender;
-use libra_trace::prelude::*;
+use libra_config::config::{NetworkStatusPolicy, RoleType, RpcConfig};
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_mempool::{MempoolClientRequest, MempoolClientSender};
+use libra_metrics::Collector;
+use libra_trace::{prelude::*, request_id::current_request_id};
 use libra_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
-    account_config::{from_currency_code_string, CurrencyInfoResource},
+    account_config::{
+        from_currency_code_string, libra_root_address, treasury_compliance_account_address,
+        type_tag_for_currency_code, AccountResource, BalanceResource, ChildVASP, Credential,
+        CurrencyInfoResource, DesignatedDealer, FreezingBit, KeyRotationCapabilityResource,
+        ParentVASP, PreburnResource, RoleId, WithdrawCapabilityResource,
+    },
     account_state::AccountState,
     chain_id::ChainId,
-    event::EventKey,
+    contract_event::ContractEvent,
+    epoch_change::EpochChangeProof,
+    event::{EventHandle, EventKey},
     ledger_info::LedgerInfoWithSignatures,
     mempool_status::MempoolStatusCode,
     move_resource::MoveStorage,
-    on_chain_config::{OnChainConfig, RegisteredCurrencies},
-    transaction::SignedTransaction,
+    on_chain_config::{OnChainConfig, RegisteredCurrencies, VMConfig},
+    transaction::{
+        SignedTransaction, Transaction, TransactionArgument, TransactionListWithProof,
+        TransactionPayload, TransactionWithProof,
+    },
+    validator_config::ValidatorConfigResource,
+    vm_status::KeptVMStatus,
+};
+use move_core_types::{
+    gas_schedule::GasAlgebra,
+    identifier::Identifier,
+    language_storage::{StructTag, TypeTag, CORE_CODE_ADDRESS},
+    move_resource::MoveResource,
+    parser::parse_type_tags,
 };
 use network::counters;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
-use std::{collections::HashMap, convert::TryFrom, ops::Deref, pin::Pin, str::FromStr, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    convert::TryFrom,
+    ops::Deref,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 use storage_interface::DbReader;
+use transaction_builder::get_transaction_name;
+use vm::{access::ModuleAccess, file_format::CompiledModule};
+
+/// Maximum number of parent VASP transactions scanned by `list_child_vasps`
+const MAX_CHILD_VASP_SCAN: u64 = 1000;
+/// Name of the stdlib script creating a child VASP account, as returned by `get_transaction_name`
+const CREATE_CHILD_VASP_TRANSACTION: &str = "create_child_vasp_account_transaction";
+/// Names of the stdlib scripts creating an account, as returned by `get_transaction_name`
+const CREATE_ACCOUNT_TRANSACTIONS: &[&str] = &[
+    CREATE_CHILD_VASP_TRANSACTION,
+    "create_parent_vasp_account_transaction",
+    "create_designated_dealer_transaction",
+    "create_validator_account_transaction",
+    "create_validator_operator_account_transaction",
+];
+/// Maximum number of creator transactions scanned by `get_account_creation_transaction`
+const MAX_ACCOUNT_CREATION_SCAN: u64 = 1000;
+/// Role id of the treasury compliance account, as defined by the `Roles` module
+const TREASURY_COMPLIANCE_ROLE_ID: u64 = 1;
+/// Default number of recent transactions sampled by `get_gas_estimate`
+const DEFAULT_GAS_ESTIMATE_WINDOW: u64 = 100;
+/// Maximum number of recent transactions sampled by `get_gas_estimate`
+const MAX_GAS_ESTIMATE_WINDOW: u64 = 1000;
+/// Maximum number of epoch changes returned by a `get_epoch_change_proof` call
+const MAX_EPOCH_CHANGES: u64 = 20;
+/// Maximum number of versions scanned by a `get_events_by_type` call
+const MAX_EVENTS_BY_TYPE_SCAN: u64 = 1000;
+/// Maximum number of versions whose account state proof can be requested at once
+const MAX_ACCOUNT_STATE_PROOFS: u64 = 100;
+/// Maximum number of reads of a `batch_fetch` call
+const MAX_BATCH_FETCHES: u64 = 100;
+/// Maximum number of levels of dependencies resolved by `get_account_modules_with_deps`
+const MAX_MODULE_DEPENDENCY_DEPTH: u64 = 16;
+/// Maximum total size in bytes of the bytecode returned by `get_account_modules_with_deps`
+const MAX_MODULES_SIZE: usize = 1024 * 1024;
+/// Maximum limit of a `get_transactions` call
+const MAX_TRANSACTIONS_LIMIT: u64 = 1000;
+/// Maximum limit of a `get_events` call, as enforced by storage
+const MAX_EVENTS_LIMIT: u64 = 1000;
+/// Prefix of event cursors, telling them apart from sequence numbers given as strings
+const EVENT_CURSOR_PREFIX: &str = "cursor:";
+/// Maximum age of the cursors taken by `get_events_since_cursor`, one day
+const MAX_EVENT_CURSOR_AGE_USECS: u64 = 24 * 60 * 60 * 1_000_000;
+/// Maximum number of pending transactions returned by `get_mempool_transactions_for_account`
+const MAX_MEMPOOL_TRANSACTIONS: u64 = 100;
+/// Encodings requests and responses can be sent in
+const ENCODINGS: &[&str] = &["json"];
+/// Addresses no transaction can be sent from: the VM's and the one holding the core modules
+const RESERVED_SENDERS: &[AccountAddress] = &[
+    AccountAddress::new([0u8; AccountAddress::LENGTH]),
+    CORE_CODE_ADDRESS,
+];
+/// Time to wait for mempool to list the pending transactions of an account
+const MEMPOOL_REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
 
 #[derive(Clone)]
 pub(crate) struct JsonRpcService {
@@ -39,6 +136,40 @@ pub(crate) struct JsonRpcService {
     mempool_sender: MempoolClientSender,
     role: RoleType,
     chain_id: ChainId,
+    /// Number of versions of state kept by storage, None if storage isn't pruned
+    prune_window: Option<u64>,
+    /// Read replica the state pruned from storage is read from, None if it can't be read
+    pruned_state_db: Option<Arc<dyn DbReader>>,
+    /// Whether events are included when the `include_events` param is absent
+    include_events_by_default: bool,
+    /// Maximum size in bytes of the results of methods returning events or transactions, 0 if
+    /// unlimited
+    max_response_size: u64,
+    /// Maximum number of events listed with each transaction of `get_transactions`, 0 if unlimited
+    max_events_per_transaction: u64,
+    /// Maximum total number of events listed by a `get_transactions` call, 0 if unlimited
+    max_transactions_events: u64,
+    /// Maximum total cost of the requests of a batch, 0 if unlimited
+    batch_cost_budget: u64,
+    audit_log: Option<Arc<AuditLog>>,
+    replay_protection: Option<Arc<ReplayProtection>>,
+    /// Gathers concurrent submissions into batches, None if each is sent to mempool on its own
+    submit_batcher: Option<Arc<SubmitBatcher>>,
+    /// Whether this node is a public full node: a full node not serving as the full node of a
+    /// validator through the VFN network
+    public_full_node: bool,
+    /// What `get_network_status` discloses on public full nodes
+    public_network_status: NetworkStatusPolicy,
+    /// Pool the methods reading storage run on, None if they run on the runtime threads
+    read_pool: Option<Arc<ReadPool>>,
+    /// Cache of the results of the cacheable methods, None if no method is
+    response_cache: Option<Arc<ResponseCache>>,
+    /// Limits of the methods given the role of the node, None if no method is limited
+    method_limiter: Option<Arc<MethodLimiter>>,
+    /// Whether unsigned integers of results are strings when the client doesn't ask for a format
+    u64_as_string_by_default: bool,
+    /// Whether `submit` is a restricted method, only callable with an API key allowing it
+    submit_restricted: bool,
 }
 
 impl JsonRpcService {
@@ -47,15 +178,84 @@ impl JsonRpcService {
         mempool_sender: MempoolClientSender,
         role: RoleType,
         chain_id: ChainId,
+        prune_window: Option<u64>,
+        config: &RpcConfig,
     ) -> Self {
         Self {
             db,
             mempool_sender,
             role,
             chain_id,
+            prune_window,
+            pruned_state_db: None,
+            include_events_by_default: config.include_events_by_default,
+            max_response_size: config.max_response_size,
+            max_events_per_transaction: config.max_events_per_transaction,
+            max_transactions_events: config.max_transactions_events,
+            batch_cost_budget: config.batch_cost_budget,
+            audit_log: config
+                .audit_log_path
+                .as_ref()
+                .map(|path| Arc::new(AuditLog::new(path, config.audit_log_strict))),
+            replay_protection: if config.replay_protection_window_ms > 0 {
+                Some(Arc::new(ReplayProtection::new(Duration::from_millis(
+                    config.replay_protection_window_ms,
+                ))))
+            } else {
+                None
+            },
+            submit_batcher: if config.submit_batch_window_ms > 0 {
+                Some(Arc::new(SubmitBatcher::new(Duration::from_millis(
+                    config.submit_batch_window_ms,
+                ))))
+            } else {
+                None
+            },
+            public_full_node: !role.is_validator(),
+            public_network_status: config.public_network_status,
+            read_pool: if config.read_pool_threads > 0 {
+                Some(Arc::new(ReadPool::new(
+                    config.read_pool_threads as usize,
+                    config.read_pool_queue_size as usize,
+                )))
+            } else {
+                None
+            },
+            response_cache: if config.cached_methods.is_empty() {
+                None
+            } else {
+                Some(Arc::new(ResponseCache::new(&config.cached_methods)))
+            },
+            method_limiter: MethodLimiter::new(config, role).map(Arc::new),
+            u64_as_string_by_default: config.u64_as_string_by_default,
+            submit_restricted: config
+                .restricted_methods
+                .iter()
+                .any(|method| unversioned_name(method) == "submit"),
         }
     }
 
+    /// Sets whether this node is a public full node, which all full nodes are by default
+    pub fn with_public_full_node(mut self, public_full_node: bool) -> Self {
+        self.public_full_node = public_full_node;
+        self
+    }
+
+    /// Serves the state pruned from storage, up to its latest version, from the given read replica
+    pub fn with_pruned_state_db(mut self, pruned_state_db: Arc<dyn DbReader>) -> Self {
+        self.pruned_state_db = Some(pruned_state_db);
+        self
+    }
+
+    /// Returns whether the state at the given version, pruned from storage, is held by the read
+    /// replica
+    fn is_pruned_state_available(&self, version: u64) -> bool {
+        self.pruned_state_db.as_ref().map_or(false, |db| {
+            db.get_latest_version()
+                .map_or(false, |latest_version| version <= latest_version)
+        })
+    }
+
     pub fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures> {
         self.db.get_latest_ledger_info()
     }
@@ -63,12 +263,87 @@ impl JsonRpcService {
     pub fn chain_id(&self) -> ChainId {
         self.chain_id
     }
+
+    pub fn audit_log(&self) -> Option<Arc<AuditLog>> {
+        self.audit_log.clone()
+    }
+
+    pub fn read_pool(&self) -> Option<Arc<ReadPool>> {
+        self.read_pool.clone()
+    }
+
+    pub fn response_cache(&self) -> Option<Arc<ResponseCache>> {
+        self.response_cache.clone()
+    }
+
+    pub fn method_limiter(&self) -> Option<Arc<MethodLimiter>> {
+        self.method_limiter.clone()
+    }
+
+    pub fn max_response_size(&self) -> u64 {
+        self.max_response_size
+    }
+
+    pub fn u64_as_string_by_default(&self) -> bool {
+        self.u64_as_string_by_default
+    }
+
+    /// Reads the latest ledger info and the currencies info, as the first requests would, so
+    /// that they find them in the storage caches
+    pub async fn warmup(&self) -> Result<()> {
+        let request = JsonRpcRequest {
+            params: vec![],
+            ledger_info: self.get_latest_ledger_info()?,
+        };
+        currencies_info(self.clone(), request).await?;
+        Ok(())
+    }
 }
 
 type RpcHandler =
     Box<fn(JsonRpcService, JsonRpcRequest) -> Pin<Box<dyn Future<Output = Result<Value>> + Send>>>;
 
-pub(crate) type RpcRegistry = HashMap<String, RpcHandler>;
+/// Registered RPC method
+pub(crate) struct RpcMethod {
+    pub handler: RpcHandler,
+    /// Set for methods clients should migrate off, returned in the envelope of their responses
+    pub deprecation: Option<Deprecation>,
+}
+
+/// Deprecation notice of a RPC method
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Deprecation {
+    /// Method replacing the deprecated one
+    pub replacement: &'static str,
+    /// Date after which the method may be removed, formatted as YYYY-MM-DD
+    pub sunset_date: &'static str,
+}
+
+pub(crate) type RpcRegistry = HashMap<String, RpcMethod>;
+
+/// Methods waiting on mempool rather than reading storage, which never run on the read pool
+pub(crate) const MEMPOOL_METHODS: &[&str] = &["submit", "get_mempool_transactions_for_account"];
+
+/// Deprecated methods, and their deprecation notice
+pub(crate) const DEPRECATED_METHODS: &[(&str, Deprecation)] = &[];
+
+/// Prefix of the names of the methods of version 2 of the API. Methods whose behavior changed are
+/// registered under their prefixed name, the others are served by their version 1 handler
+pub(crate) const V2_PREFIX: &str = "v2.";
+
+/// Returns the method serving the given name: the method registered under that name, or for a
+/// version 2 name, the version 1 method it falls back to
+pub(crate) fn resolve_method<'a>(registry: &'a RpcRegistry, name: &str) -> Option<&'a RpcMethod> {
+    registry
+        .get(name)
+        .or_else(|| registry.get(name.strip_prefix(V2_PREFIX)?))
+}
+
+/// Returns the name of the method without its version prefix. All versions of a method share
+/// their access restrictions and cost
+pub(crate) fn unversioned_name(name: &str) -> &str {
+    name.strip_prefix(V2_PREFIX).unwrap_or(name)
+}
 
 pub(crate) struct JsonRpcRequest {
     pub params: Vec<Value>,
@@ -91,27 +366,220 @@ impl JsonRpcRequest {
         default
     }
 
+    /// Returns whether the request parameter at the given index is given explicitly as null,
+    /// as opposed to being absent
+    fn is_null_param(&self, index: usize) -> bool {
+        self.params.get(index) == Some(&Value::Null)
+    }
+
+    /// Deserializes the request parameter at the given index, absent parameters being null.
+    /// Fails with an invalid param error giving the index and name of the param
+    pub(crate) fn deserialize_param<T: DeserializeOwned>(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> Result<T> {
+        serde_json::from_value(self.get_param(index)).map_err(|err| {
+            // only required params fail to deserialize from null
+            if self.is_null_param(index) {
+                return null_param_error(index, name);
+            }
+            Error::new(JsonRpcError::invalid_param(
+                index,
+                name,
+                format!("is invalid: {}", err),
+            ))
+        })
+    }
+
+    /// Returns the u64 request parameter at the given index, given either as a number or as a
+    /// numeric string, as SDKs commonly serialize u64s as strings to avoid precision loss.
+    /// Other values, including negative, fractional and out of range numbers, fail with an
+    /// invalid param error naming the param
+    pub(crate) fn get_u64_param(&self, index: usize, name: &str) -> Result<u64> {
+        if self.is_null_param(index) {
+            return Err(null_param_error(index, name));
+        }
+        let param = self.get_param(index);
+        parse_u64(&param).ok_or_else(|| {
+            Error::new(JsonRpcError::invalid_param(
+                index,
+                name,
+                format!(
+                    "must be an unsigned integer from 0 to {}, given {}",
+                    u64::MAX,
+                    param
+                ),
+            ))
+        })
+    }
+
+    /// Returns the optional u64 request parameter at the given index, None if null or absent
+    pub(crate) fn get_optional_u64_param(&self, index: usize, name: &str) -> Result<Option<u64>> {
+        match self.get_param(index) {
+            Value::Null => Ok(None),
+            _ => self.get_u64_param(index, name).map(Some),
+        }
+    }
+
+    /// Returns the array of u64s request parameter at the given index, whose items are given
+    /// either as numbers or as numeric strings
+    pub(crate) fn get_u64_array_param(&self, index: usize, name: &str) -> Result<Vec<u64>> {
+        if self.is_null_param(index) {
+            return Err(null_param_error(index, name));
+        }
+        let param = self.get_param(index);
+        let items = param.as_array().ok_or_else(|| {
+            Error::new(JsonRpcError::invalid_param(
+                index,
+                name,
+                format!("must be an array, given {}", param),
+            ))
+        })?;
+        items
+            .iter()
+            .map(|item| {
+                parse_u64(item).ok_or_else(|| {
+                    Error::new(JsonRpcError::invalid_param(
+                        index,
+                        name,
+                        format!(
+                            "must only contain unsigned integers from 0 to {}, given {}",
+                            u64::MAX,
+                            item
+                        ),
+                    ))
+                })
+            })
+            .collect()
+    }
+
     fn version(&self) -> u64 {
         self.ledger_info.ledger_info().version()
     }
 }
 
-/// Submits transaction to full node
-async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<()> {
-    let txn_payload: String = serde_json::from_value(request.get_param(0))?;
-    let transaction: SignedTransaction = lcs::from_bytes(&hex::decode(txn_payload)?)?;
+/// Returns the invalid param error of a required param given as null
+fn null_param_error(index: usize, name: &str) -> Error {
+    Error::new(JsonRpcError::invalid_param(
+        index,
+        name,
+        "is required, given null".to_string(),
+    ))
+}
+
+/// Parses a u64 given either as a JSON number or as a string of decimal digits
+pub(crate) fn parse_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(string) => string.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Submits transaction to full node, and records the submission in the audit log if enabled
+async fn submit(service: JsonRpcService, request: JsonRpcRequest) -> Result<()> {
+    let audit_log = match service.audit_log.clone() {
+        Some(audit_log) => audit_log,
+        None => return submit_to_mempool(service, submitted_transaction(&request)?).await,
+    };
+    audit_log.ensure_writable()?;
+
+    // submissions are audited whether their transaction is valid or not
+    let transaction = submitted_transaction(&request);
+    let audited_transaction = transaction
+        .as_ref()
+        .ok()
+        .map(|transaction| AuditedTransaction {
+            sender: transaction.sender().to_string(),
+            sequence_number: transaction.sequence_number(),
+            hash: Transaction::UserTransaction(transaction.clone())
+                .hash()
+                .to_hex(),
+        });
+    let result = match transaction {
+        Ok(transaction) => submit_to_mempool(service, transaction).await,
+        Err(err) => Err(err),
+    };
+    audit_log.record(&AuditEntry {
+        method: "submit",
+        request_id: &current_request_id().unwrap_or_default(),
+        transaction: audited_transaction,
+        result: match &result {
+            Ok(()) => "success".to_string(),
+            Err(err) => err.to_string(),
+        },
+    });
+    result
+}
+
+/// Returns the transaction of a `submit` call, which can't be sent by a reserved sender
+fn submitted_transaction(request: &JsonRpcRequest) -> Result<SignedTransaction> {
+    let txn_payload = request
+        .params
+        .get(0)
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            JsonRpcError::invalid_param(0, "data", "should be a hex encoded string".to_string())
+        })?;
+    let transaction: SignedTransaction = serialization::decode_hex_lcs(txn_payload)?;
+    // senders which don't exist yet are let through, as accounts can be created by then
+    ensure!(
+        !RESERVED_SENDERS.contains(&transaction.sender()),
+        JsonRpcError::invalid_param(
+            0,
+            "data",
+            format!(
+                "has reserved sender {}, which can't send transactions",
+                transaction.sender()
+            )
+        )
+    );
+    Ok(transaction)
+}
+
+async fn submit_to_mempool(
+    mut service: JsonRpcService,
+    transaction: SignedTransaction,
+) -> Result<()> {
     trace_code_block!("json-rpc::submit", {"txn", transaction.sender(), transaction.sequence_number()});
+    let replay_protection = service.replay_protection.clone();
+    if let Some(replay_protection) = &replay_protection {
+        replay_protection.check(&transaction)?;
+    }
 
-    let (req_sender, callback) = oneshot::channel();
-    service
-        .mempool_sender
-        .send((transaction, req_sender))
-        .await?;
-    let (mempool_status, vm_status_opt) = callback.await??;
+    // mempool failing to receive the transaction or to answer isn't the transaction's fault
+    let callback = match &service.submit_batcher {
+        Some(submit_batcher) => submit_batcher.submit(&service.mempool_sender, transaction.clone()),
+        None => {
+            let (req_sender, callback) = oneshot::channel();
+            service
+                .mempool_sender
+                .send(MempoolClientRequest::SubmitTransaction(
+                    transaction.clone(),
+                    req_sender,
+                ))
+                .await
+                .map_err(|_| JsonRpcError::mempool_unavailable())?;
+            callback
+        }
+    };
+    // a dropped callback leaves the submission's fate unknown: mempool may have accepted the
+    // transaction before dropping it, e.g. while shutting down
+    let (mempool_status, vm_status_opt) = callback.await.map_err(|_| {
+        JsonRpcError::submission_status_unknown(
+            &Transaction::UserTransaction(transaction.clone())
+                .hash()
+                .to_hex(),
+        )
+    })??;
 
     if let Some(vm_status) = vm_status_opt {
         Err(Error::new(JsonRpcError::vm_status(vm_status)))
     } else if mempool_status.code == MempoolStatusCode::Accepted {
+        if let Some(replay_protection) = replay_protection {
+            replay_protection.record(&transaction);
+        }
         Ok(())
     } else {
         Err(Error::new(JsonRpcError::mempool_error(mempool_status)?))
@@ -119,27 +587,89 @@ async fn submit(mut service: JsonRpcService, request: JsonRpcRequest) -> Result<
 }
 
 /// Returns account state (AccountView) by given address
+/// If a version is specified, returns the account state and balances as of that version
 async fn get_account(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Option<AccountView>> {
-    let address: String = serde_json::from_value(request.get_param(0))?;
+    account_view(&service, &request, false)
+}
+
+/// Version 2 of `get_account`: fails rather than returning null when the account exists but its
+/// role or freezing bit can't be found
+async fn get_account_v2(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<AccountView>> {
+    account_view(&service, &request, true)
+}
+
+/// Returns the view of the account given as first param, at the version given as second param
+/// if any, with its balances in the currencies given as third param if any. If the fourth param
+/// is true, the account state is verified against the ledger info of the response before use.
+/// An account missing its role or freezing bit fails if `require_complete`, or is returned as
+/// None otherwise
+fn account_view(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    require_complete: bool,
+) -> Result<Option<AccountView>> {
+    let address: String = request.deserialize_param(0, "account")?;
     let account_address = AccountAddress::from_str(&address)?;
-    let response = service
-        .db
-        .get_account_state_with_proof_by_version(account_address, request.version())?
-        .0;
-    let currency_info = currencies_info(service, request).await?;
-    let currencies: Vec<_> = currency_info
-        .into_iter()
-        .map(|info| from_currency_code_string(&info.code))
-        .collect::<Result<_, _>>()?;
+    let version = get_version_param(request, 1, "version")?;
+    let balance_currencies: Option<Vec<String>> = request.deserialize_param(2, "currencies")?;
+    let verify: Option<bool> = request.deserialize_param(3, "verify")?;
+    ensure_not_pruned(service, request, version)?;
+
+    let response = if verify.unwrap_or(false) {
+        // the full proof is read so that it can be checked up to the ledger info of the response
+        let ledger_version = request.version();
+        let state =
+            service
+                .db
+                .get_account_state_with_proof(account_address, version, ledger_version)?;
+        state
+            .verify(request.ledger_info.ledger_info(), version, account_address)
+            .map_err(|err| format_err!("account state failed verification: {}", err))?;
+        state.blob
+    } else {
+        service
+            .db
+            .get_account_state_with_proof_by_version(account_address, version)?
+            .0
+    };
+    let currencies = get_on_chain_config::<RegisteredCurrencies>(service, version)?
+        .currency_codes()
+        .to_vec();
+    // balances are only read in the requested currencies, all registered ones by default
+    let balance_currencies = match balance_currencies {
+        Some(codes) => {
+            for code in &codes {
+                ensure!(
+                    currencies.iter().any(|currency| currency.as_str() == code),
+                    JsonRpcError::invalid_param(
+                        2,
+                        "currencies",
+                        format!("contains unregistered currency {}", code)
+                    )
+                );
+            }
+            currencies
+                .iter()
+                .filter(|currency| codes.iter().any(|code| code == currency.as_str()))
+                .cloned()
+                .collect()
+        }
+        None => currencies.clone(),
+    };
     if let Some(blob) = response {
         let account_state = AccountState::try_from(&blob)?;
         if let Some(account) = account_state.get_account_resource()? {
-            let balances = account_state.get_balance_resources(&currencies)?;
-            if let Some(account_role) = account_state.get_account_role(&currencies)? {
-                if let Some(freezing_bit) = account_state.get_freezing_bit()? {
+            let balances = account_state.get_balance_resources(&balance_currencies)?;
+            let account_role = account_state.get_account_role(&currencies)?;
+            let freezing_bit = account_state.get_freezing_bit()?;
+            match (account_role, freezing_bit) {
+                (Some(account_role), Some(freezing_bit)) => {
                     return Ok(Some(AccountView::new(
                         &account,
                         balances,
@@ -147,156 +677,1225 @@ async fn get_account(
                         freezing_bit,
                     )));
                 }
+                _ => ensure!(
+                    !require_complete,
+                    "account {} has no role or freezing bit",
+                    account_address
+                ),
             }
         }
     }
     Ok(None)
 }
 
+/// Returns the hex encoded LCS serialized resource of the given type held by the given account,
+/// None if the account or the resource doesn't exist.
+/// The resource type is given as a struct tag, e.g. `0x1::LibraAccount::Balance<0x1::LBR::LBR>`
+async fn get_resource(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<BytesView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let resource_type: String = request.deserialize_param(1, "resource_type")?;
+    let struct_tag = parse_struct_tag(&resource_type)?;
+
+    let account_state = match get_account_state(&service, account_address, request.version())? {
+        Some(account_state) => account_state,
+        None => return Ok(None),
+    };
+    Ok(account_state
+        .get(&AccessPath::resource_access_vec(&struct_tag))
+        .map(BytesView::from))
+}
+
+/// Returns the modules published by the given account, or only the one with the given name,
+/// followed by all the modules they depend on, directly or not, each module once.
+/// Dependencies are resolved at most `MAX_MODULE_DEPENDENCY_DEPTH` levels deep, and at most
+/// `MAX_MODULES_SIZE` bytes of bytecode are returned. Modules already resolved aren't resolved
+/// again, so a dependency cycle can't make the resolution loop
+async fn get_account_modules_with_deps(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<ModuleView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let module_name: Option<String> = request.deserialize_param(1, "module")?;
+    let version = request.version();
+
+    let mut account_states = HashMap::new();
+    account_states.insert(
+        account_address,
+        get_account_state(&service, account_address, version)?,
+    );
+    let mut queue = VecDeque::new();
+    if let Some(account_state) = &account_states[&account_address] {
+        for (path, bytecode) in account_state.iter() {
+            if path.first() != Some(&AccessPath::CODE_TAG) {
+                continue;
+            }
+            let module = deserialize_module(bytecode)?;
+            if module_name
+                .as_ref()
+                .map_or(true, |name| module.self_id().name().as_str() == name)
+            {
+                queue.push_back((module, bytecode.clone(), 0));
+            }
+        }
+    }
+    if let Some(name) = &module_name {
+        ensure!(
+            !queue.is_empty(),
+            "module {} not found under account {}",
+            name,
+            account_address
+        );
+    }
+
+    let mut resolved: HashSet<_> = queue
+        .iter()
+        .map(|(module, _, _)| module.self_id())
+        .collect();
+    let mut modules = vec![];
+    let mut size = 0;
+    while let Some((module, bytecode, depth)) = queue.pop_front() {
+        let id = module.self_id();
+        size += bytecode.len();
+        ensure!(
+            size <= MAX_MODULES_SIZE,
+            "modules exceed {} bytes, request a single module",
+            MAX_MODULES_SIZE
+        );
+        for handle in module.module_handles() {
+            let dependency = module.module_id_for_handle(handle);
+            if !resolved.insert(dependency.clone()) {
+                continue;
+            }
+            ensure!(
+                depth < MAX_MODULE_DEPENDENCY_DEPTH,
+                "dependencies of {} are nested more than {} levels deep",
+                id,
+                MAX_MODULE_DEPENDENCY_DEPTH
+            );
+            let address = *dependency.address();
+            if !account_states.contains_key(&address) {
+                account_states.insert(address, get_account_state(&service, address, version)?);
+            }
+            let bytecode = account_states[&address]
+                .as_ref()
+                .and_then(|account_state| account_state.get(&dependency.access_vector()))
+                .ok_or_else(|| format_err!("dependency {} of {} not found", dependency, id))?
+                .clone();
+            queue.push_back((deserialize_module(&bytecode)?, bytecode, depth + 1));
+        }
+        modules.push(ModuleView {
+            address: id.address().to_string(),
+            name: id.name().to_string(),
+            bytecode: BytesView::from(&bytecode),
+        });
+    }
+    Ok(modules)
+}
+
+fn deserialize_module(bytecode: &[u8]) -> Result<CompiledModule> {
+    CompiledModule::deserialize(bytecode)
+        .map_err(|err| format_err!("invalid module bytecode: {:?}", err))
+}
+
+/// Parses a struct tag such as `0x1::LibraAccount::Balance<0x1::LBR::LBR>`
+fn parse_struct_tag(struct_type: &str) -> Result<StructTag> {
+    let mut type_tags = parse_type_tags(struct_type)
+        .map_err(|err| format_err!("invalid struct type {}: {}", struct_type, err))?;
+    match (type_tags.pop(), type_tags.is_empty()) {
+        (Some(TypeTag::Struct(struct_tag)), true) => Ok(struct_tag),
+        _ => Err(format_err!(
+            "invalid struct type {}: expected a single struct type",
+            struct_type
+        )),
+    }
+}
+
+/// Returns the resources of the given account that were added, removed or changed between the two
+/// given versions. Resources are identified by their type when known, by their hex encoded access
+/// path otherwise
+async fn get_account_diff(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<AccountDiffView> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let from_version = request.get_u64_param(1, "from_version")?;
+    let to_version = request.get_u64_param(2, "to_version")?;
+    for version in &[from_version, to_version] {
+        ensure!(
+            *version <= request.version(),
+            "version {} is ahead of the latest ledger version {}",
+            version,
+            request.version()
+        );
+        ensure_not_pruned(&service, &request, *version)?;
+    }
+
+    let from_state =
+        get_account_state(&service, account_address, from_version)?.unwrap_or_default();
+    let to_state = get_account_state(&service, account_address, to_version)?.unwrap_or_default();
+    let added: Vec<_> = to_state
+        .iter()
+        .filter(|(path, _)| from_state.get(path).is_none())
+        .map(|(path, _)| path)
+        .collect();
+    let removed: Vec<_> = from_state
+        .iter()
+        .filter(|(path, _)| to_state.get(path).is_none())
+        .map(|(path, _)| path)
+        .collect();
+    let changed: Vec<_> = to_state
+        .iter()
+        .filter(|(path, value)| {
+            from_state
+                .get(path)
+                .map_or(false, |from_value| from_value != *value)
+        })
+        .map(|(path, _)| path)
+        .collect();
+
+    let mut diff = AccountDiffView::default();
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        return Ok(diff);
+    }
+    let mut currencies = get_on_chain_config::<RegisteredCurrencies>(&service, from_version)?
+        .currency_codes()
+        .to_vec();
+    currencies.extend_from_slice(
+        get_on_chain_config::<RegisteredCurrencies>(&service, to_version)?.currency_codes(),
+    );
+    let resource_names = get_resource_names(&currencies);
+    let name = |path: &Vec<u8>| {
+        resource_names
+            .get(path)
+            .cloned()
+            .unwrap_or_else(|| hex::encode(path))
+    };
+    diff.added = added.into_iter().map(name).collect();
+    diff.removed = removed.into_iter().map(name).collect();
+    diff.changed = changed.into_iter().map(name).collect();
+    Ok(diff)
+}
+
+/// Returns the names of the account resources known to the JSON RPC service, by access path
+fn get_resource_names(currencies: &[Identifier]) -> HashMap<Vec<u8>, String> {
+    fn entry<T: MoveResource>() -> (Vec<u8>, String) {
+        (
+            T::resource_path(),
+            format!("{}::{}", T::MODULE_NAME, T::STRUCT_NAME),
+        )
+    }
+
+    let mut names: HashMap<_, _> = vec![
+        entry::<AccountResource>(),
+        entry::<ChildVASP>(),
+        entry::<Credential>(),
+        entry::<DesignatedDealer>(),
+        entry::<FreezingBit>(),
+        entry::<KeyRotationCapabilityResource>(),
+        entry::<ParentVASP>(),
+        entry::<RoleId>(),
+        entry::<ValidatorConfigResource>(),
+        entry::<WithdrawCapabilityResource>(),
+    ]
+    .into_iter()
+    .collect();
+    for currency in currencies {
+        let currency_type_tag = type_tag_for_currency_code(currency.clone());
+        names.insert(
+            BalanceResource::access_path_for(currency_type_tag.clone()),
+            format!(
+                "{}::{}<{}>",
+                BalanceResource::MODULE_NAME,
+                BalanceResource::STRUCT_NAME,
+                currency
+            ),
+        );
+        names.insert(
+            PreburnResource::access_path_for(currency_type_tag),
+            format!(
+                "{}::{}<{}>",
+                PreburnResource::MODULE_NAME,
+                PreburnResource::STRUCT_NAME,
+                currency
+            ),
+        );
+    }
+    names
+}
+
+/// Returns whether the account with the given address is frozen, without fetching its balances
+/// Returns None if the account does not exist
+async fn is_account_frozen(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<bool>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    match get_account_state(&service, account_address, request.version())? {
+        Some(account_state) => Ok(account_state
+            .get_freezing_bit()?
+            .map(|freezing_bit| freezing_bit.is_frozen())),
+        None => Ok(None),
+    }
+}
+
+/// Returns the role of the account with the given address, without its balances and freezing info
+/// Returns None if the account does not exist
+async fn get_account_role(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<AccountRoleView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let account_state = match get_account_state(&service, account_address, request.version())? {
+        Some(account_state) => account_state,
+        None => return Ok(None),
+    };
+    let currencies: Vec<_> = currencies_info(service, request)
+        .await?
+        .into_iter()
+        .map(|info| from_currency_code_string(&info.code))
+        .collect::<Result<_, _>>()?;
+    Ok(account_state
+        .get_account_role(&currencies)?
+        .map(AccountRoleView::from))
+}
+
+/// Returns the state of the treasury compliance account, which admin transactions are sent from
+/// Fails if the account doesn't exist or doesn't have the treasury compliance role, which means
+/// the chain is misconfigured
+async fn get_treasury_compliance_info(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<TreasuryComplianceView> {
+    let address = treasury_compliance_account_address();
+    let account_state = get_account_state(&service, address, request.version())?
+        .ok_or_else(|| format_err!("treasury compliance account {} not found", address))?;
+    let role_id = account_state.get_resource::<RoleId>(&RoleId::resource_path())?;
+    ensure!(
+        role_id.map(|role| role.role_id()) == Some(TREASURY_COMPLIANCE_ROLE_ID),
+        "account {} doesn't have the treasury compliance role",
+        address
+    );
+    let account = account_state
+        .get_account_resource()?
+        .ok_or_else(|| format_err!("treasury compliance account {} not found", address))?;
+    let is_frozen = account_state
+        .get_freezing_bit()?
+        .map_or(false, |freezing_bit| freezing_bit.is_frozen());
+
+    Ok(TreasuryComplianceView {
+        address: address.to_string(),
+        sequence_number: account.sequence_number(),
+        authentication_key: BytesView::from(account.authentication_key()),
+        is_frozen,
+    })
+}
+
+/// Returns the addresses of the child VASP accounts created by the given parent VASP
+/// There is no on-chain index of child accounts, so this scans the parent's sent transactions for
+/// successfully executed `create_child_vasp_account` scripts. The cost grows linearly with the
+/// parent's sequence number: at most `MAX_CHILD_VASP_SCAN` transactions are scanned, and the scan
+/// stops as soon as the `num_children` tracked by the parent's role resource have been found
+async fn list_child_vasps(service: JsonRpcService, request: JsonRpcRequest) -> Result<Vec<String>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let parent_address = AccountAddress::from_str(&address)?;
+    let account_state = get_account_state(&service, parent_address, request.version())?
+        .ok_or_else(|| format_err!("account {} does not exist", parent_address))?;
+    let parent_vasp = account_state
+        .get_resource::<ParentVASP>(&ParentVASP::resource_path())?
+        .ok_or_else(|| format_err!("account {} is not a parent VASP", parent_address))?;
+    let sequence_number = account_state
+        .get_account_resource()?
+        .map_or(0, |account| account.sequence_number());
+
+    let mut children = vec![];
+    for seq in 0..std::cmp::min(sequence_number, MAX_CHILD_VASP_SCAN) {
+        if children.len() as u64 >= parent_vasp.num_children() {
+            break;
+        }
+        let txn =
+            match service
+                .db
+                .get_txn_by_account(parent_address, seq, request.version(), false)?
+            {
+                Some(txn) => txn,
+                None => continue,
+            };
+        if txn.proof.transaction_info().status() != &KeptVMStatus::Executed {
+            continue;
+        }
+        if let Ok(signed_txn) = txn.transaction.as_signed_user_txn() {
+            if let TransactionPayload::Script(script) = signed_txn.payload() {
+                if get_transaction_name(script.code()) == CREATE_CHILD_VASP_TRANSACTION {
+                    if let Some(TransactionArgument::Address(child)) = script.args().first() {
+                        children.push(child.to_string());
+                    }
+                }
+            }
+        }
+    }
+    Ok(children)
+}
+
 /// Returns the blockchain metadata for a specified version. If no version is specified, default to
 /// returning the current blockchain metadata
 /// Can be used to verify that target Full Node is up-to-date
 async fn get_metadata(service: JsonRpcService, request: JsonRpcRequest) -> Result<BlockMetadata> {
-    match serde_json::from_value::<u64>(request.get_param(0)) {
-        Ok(version) => Ok(BlockMetadata {
+    match request.get_optional_u64_param(0, "version")? {
+        Some(version) => Ok(BlockMetadata {
             version,
             timestamp: service.db.get_block_timestamp(version)?,
         }),
-        _ => Ok(BlockMetadata {
+        None => Ok(BlockMetadata {
             version: request.version(),
             timestamp: request.ledger_info.ledger_info().timestamp_usecs(),
         }),
     }
 }
 
+/// Returns the latest ledger version, the cheapest way for clients to poll for new transactions
+async fn get_latest_version(_service: JsonRpcService, request: JsonRpcRequest) -> Result<u64> {
+    Ok(request.version())
+}
+
+/// Execution outcome used to filter the result of `get_transactions`
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+enum TransactionStatusFilter {
+    #[serde(rename = "executed")]
+    Executed,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+impl TransactionStatusFilter {
+    fn matches(self, status: &KeptVMStatus) -> bool {
+        match self {
+            TransactionStatusFilter::Executed => status == &KeptVMStatus::Executed,
+            TransactionStatusFilter::Failed => status != &KeptVMStatus::Executed,
+        }
+    }
+}
+
 /// Returns transactions by range
+/// The optional status filter is a convenience: the whole range of `limit` transactions is still
+/// scanned, so fewer than `limit` transactions may be returned
 async fn get_transactions(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<TransactionView>> {
-    let start_version: u64 = serde_json::from_value(request.get_param(0))?;
-    let limit: u64 = serde_json::from_value(request.get_param(1))?;
-    let include_events: bool = serde_json::from_value(request.get_param(2))?;
+    let result = transaction_views(&service, &request)?.collect::<Result<Vec<_>>>()?;
+    ensure_response_size(&service, &result)?;
+    Ok(result)
+}
 
-    ensure!(
-        limit > 0 && limit <= 1000,
-        "limit must be smaller than 1000"
-    );
+/// Iterator over the views of the transactions of a `get_transactions` request, built as they are
+/// consumed
+pub(crate) type TransactionViews = Box<dyn Iterator<Item = Result<TransactionView>> + Send>;
+
+/// Reads the transactions of a `get_transactions` request from storage, returning their views
+pub(crate) fn transaction_views(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+) -> Result<TransactionViews> {
+    let start_version = request.get_u64_param(0, "start_version")?;
+    let limit = request.get_u64_param(1, "limit")?;
+    let include_events = get_include_events_param(service, request, 2)?;
+    let status_filter: Option<TransactionStatusFilter> = request.deserialize_param(3, "status")?;
+
+    ensure_limit(limit, MAX_TRANSACTIONS_LIMIT)?;
 
     let txs =
         service
             .db
             .get_transactions(start_version, limit, request.version(), include_events)?;
+    ensure_transaction_list(&txs, start_version, limit, request.version())?;
 
-    let mut result = vec![];
-
-    let all_events = if include_events {
+    let mut all_events = if include_events {
         txs.events
             .ok_or_else(|| format_err!("Storage layer didn't return events when requested!"))?
     } else {
         vec![]
-    };
+    }
+    .into_iter();
 
     let txs_with_info = txs
         .transactions
         .into_iter()
-        .zip(txs.proof.transaction_infos().iter());
+        .zip(txs.proof.transaction_infos().to_vec());
 
-    for (v, (tx, info)) in txs_with_info.enumerate() {
-        let events = if include_events {
-            all_events
-                .get(v)
-                .ok_or_else(|| format_err!("Missing events for version: {}", v))?
-                .iter()
-                .cloned()
-                .map(|x| (start_version + v as u64, x).into())
-                .collect()
-        } else {
-            vec![]
-        };
+    // events past the caps are left out of their transaction, which is flagged as truncated
+    let unlimited_if_zero = |cap: u64| if cap > 0 { cap } else { u64::max_value() };
+    let max_events_per_transaction = unlimited_if_zero(service.max_events_per_transaction);
+    let mut events_left = unlimited_if_zero(service.max_transactions_events);
 
-        result.push(TransactionView {
-            version: start_version + v as u64,
-            hash: tx.hash().to_hex(),
-            transaction: tx.into(),
-            events,
-            vm_status: info.status().into(),
-            gas_used: info.gas_used(),
+    let views = txs_with_info
+        .enumerate()
+        .filter_map(move |(v, (tx, info))| {
+            // events are listed for every version, whether its transaction is filtered out or not
+            let mut events = all_events.next();
+            if let Some(filter) = status_filter {
+                if !filter.matches(info.status()) {
+                    return None;
+                }
+            }
+            let mut events_truncated = false;
+            if let Some(events) = events.as_mut() {
+                let max_events = std::cmp::min(max_events_per_transaction, events_left);
+                if events.len() as u64 > max_events {
+                    events.truncate(max_events as usize);
+                    events_truncated = true;
+                }
+                events_left -= events.len() as u64;
+            }
+            let version = start_version + v as u64;
+            let view = || -> Result<TransactionView> {
+                let hash = tx.hash();
+                let events = if include_events {
+                    events
+                        .ok_or_else(|| format_err!("Missing events for version: {}", v))?
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, x)| indexed_event_view(version, &hash, index, x))
+                        .collect()
+                } else {
+                    vec![]
+                };
+                Ok(TransactionView {
+                    version,
+                    hash: hash.to_hex(),
+                    bytes: lcs::serialized_size(&tx)? as u64,
+                    gas_currency: gas_currency(&tx),
+                    transaction: tx.into(),
+                    events,
+                    vm_status: info.status().into(),
+                    gas_used: info.gas_used(),
+                    proof: None,
+                    events_truncated,
+                })
+            };
+            Some(view())
         });
+    Ok(Box::new(views))
+}
+
+/// Returns the transaction infos of a range of versions, without their transactions or events,
+/// which is much lighter than `get_transactions` for clients only verifying the ledger
+async fn get_transaction_infos(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<TransactionInfoView>> {
+    let start_version = request.get_u64_param(0, "start_version")?;
+    let limit = request.get_u64_param(1, "limit")?;
+    ensure_limit(limit, MAX_TRANSACTIONS_LIMIT)?;
+
+    let txs = service
+        .db
+        .get_transactions(start_version, limit, request.version(), false)?;
+    ensure_transaction_list(&txs, start_version, limit, request.version())?;
+    let result = txs
+        .proof
+        .transaction_infos()
+        .iter()
+        .enumerate()
+        .map(|(index, info)| TransactionInfoView {
+            version: start_version + index as u64,
+            transaction_hash: info.transaction_hash().to_hex(),
+            state_root_hash: info.state_root_hash().to_hex(),
+            event_root_hash: info.event_root_hash().to_hex(),
+            gas_used: info.gas_used(),
+            vm_status: info.status().into(),
+        })
+        .collect::<Vec<_>>();
+    ensure_response_size(&service, &result)?;
+    Ok(result)
+}
+
+/// Returns the transactions committed in blocks whose timestamp, in microseconds, is within the
+/// given window: from `start_time` included to `end_time` excluded, up to `limit` transactions
+/// from the start of the window.
+/// Timestamps have block granularity, all the transactions of a block sharing its timestamp. The
+/// window is located by binary search over block timestamps, which never decrease with versions
+async fn get_transactions_by_time(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<TransactionView>> {
+    let start_time = request.get_u64_param(0, "start_time")?;
+    let end_time = request.get_u64_param(1, "end_time")?;
+    let limit = request.get_u64_param(2, "limit")?;
+    ensure_limit(limit, MAX_TRANSACTIONS_LIMIT)?;
+    ensure!(
+        start_time <= end_time,
+        JsonRpcError::invalid_param(
+            1,
+            "end_time",
+            format!("must be >= start_time {}, got {}", start_time, end_time)
+        )
+    );
+
+    let start_version = first_version_at(&service, start_time, request.version())?;
+    let end_version = first_version_at(&service, end_time, request.version())?;
+    if start_version == end_version {
+        return Ok(vec![]);
     }
+    let range_request = JsonRpcRequest {
+        ledger_info: request.ledger_info.clone(),
+        params: vec![
+            start_version.into(),
+            limit.min(end_version - start_version).into(),
+            request.get_param(3),
+        ],
+    };
+    let result = transaction_views(&service, &range_request)?.collect::<Result<Vec<_>>>()?;
+    ensure_response_size(&service, &result)?;
     Ok(result)
 }
 
+/// Returns the first version up to the given ledger version committed in a block with a timestamp
+/// at or after the given one, or the version following the ledger version if there is none
+fn first_version_at(service: &JsonRpcService, timestamp: u64, ledger_version: u64) -> Result<u64> {
+    let (mut low, mut high) = (0, ledger_version.saturating_add(1));
+    while low < high {
+        let middle = low + (high - low) / 2;
+        if service.db.get_block_timestamp(middle)? < timestamp {
+            low = middle + 1;
+        } else {
+            high = middle;
+        }
+    }
+    Ok(low)
+}
+
 /// Returns account transaction by account and sequence_number
+/// The proof of its transaction info is only attached if requested
 async fn get_account_transaction(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Option<TransactionView>> {
-    let p_account: String = serde_json::from_value(request.get_param(0))?;
-    let sequence: u64 = serde_json::from_value(request.get_param(1))?;
-    let include_events: bool = serde_json::from_value(request.get_param(2))?;
+    let p_account: String = request.deserialize_param(0, "account")?;
+    let sequence = request.get_u64_param(1, "sequence_number")?;
+    let include_events = get_include_events_param(&service, &request, 2)?;
+    let include_proof: Option<bool> = request.deserialize_param(3, "include_proof")?;
 
     let account = AccountAddress::try_from(p_account)?;
 
-    let tx = service
+    service
         .db
-        .get_txn_by_account(account, sequence, request.version(), include_events)?;
-
-    if let Some(tx) = tx {
-        if include_events {
-            ensure!(
-                tx.events.is_some(),
-                "Storage layer didn't return events when requested!"
-            );
-        }
-        let tx_version = tx.version;
-
-        let events = tx
-            .events
-            .unwrap_or_default()
-            .into_iter()
-            .map(|x| ((tx_version, x).into()))
-            .collect();
-
-        Ok(Some(TransactionView {
-            version: tx_version,
-            hash: tx.transaction.hash().to_hex(),
-            transaction: tx.transaction.into(),
-            events,
-            vm_status: tx.proof.transaction_info().status().into(),
-            gas_used: tx.proof.transaction_info().gas_used(),
-        }))
-    } else {
-        Ok(None)
-    }
+        .get_txn_by_account(account, sequence, request.version(), include_events)?
+        .map(|tx| transaction_view(tx, include_events, include_proof.unwrap_or(false)))
+        .transpose()
 }
 
-/// Returns events by given access path
-async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<Vec<EventView>> {
-    let raw_event_key: String = serde_json::from_value(request.get_param(0))?;
-    let start: u64 = serde_json::from_value(request.get_param(1))?;
-    let limit: u64 = serde_json::from_value(request.get_param(2))?;
+/// Returns the transactions of the given account pending in this node's mempool, ordered by
+/// sequence number. They aren't executed yet: their vm_status is "pending", and their version and
+/// gas used are 0
+async fn get_mempool_transactions_for_account(
+    mut service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<TransactionView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account = AccountAddress::from_str(&address)?;
+    let limit = request
+        .get_optional_u64_param(1, "limit")?
+        .unwrap_or(MAX_MEMPOOL_TRANSACTIONS);
+    ensure_limit(limit, MAX_MEMPOOL_TRANSACTIONS)?;
 
-    let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
-    let events_with_proof = service.db.get_events(&event_key, start, true, limit)?;
+    let (req_sender, callback) = oneshot::channel();
+    service
+        .mempool_sender
+        .send(MempoolClientRequest::GetAccountTransactions(
+            account,
+            limit as usize,
+            req_sender,
+        ))
+        .await
+        .map_err(|_| JsonRpcError::mempool_unavailable())?;
+    let txns = tokio::time::timeout(MEMPOOL_REQUEST_TIMEOUT, callback)
+        .await
+        .map_err(|_| JsonRpcError::mempool_unavailable())?
+        .map_err(|_| JsonRpcError::mempool_unavailable())?;
 
-    let req_version = request.version();
-    let events = events_with_proof
+    txns.into_iter()
+        .map(|txn| {
+            let transaction = Transaction::UserTransaction(txn);
+            Ok(TransactionView {
+                version: 0,
+                hash: transaction.hash().to_hex(),
+                bytes: lcs::serialized_size(&transaction)? as u64,
+                gas_currency: gas_currency(&transaction),
+                transaction: transaction.into(),
+                events: vec![],
+                vm_status: VMStatusView::Pending,
+                gas_used: 0,
+                proof: None,
+                events_truncated: false,
+            })
+        })
+        .collect()
+}
+
+/// Returns the transaction that created the given account, None if the account doesn't exist or
+/// was created at genesis.
+/// There is no on-chain record of account creation, so this scans the sent transactions of the
+/// account's creator (the parent VASP of a child VASP, the treasury compliance account for parent
+/// VASPs and designated dealers, the libra root account otherwise) for a successfully executed
+/// account creation script taking the account as argument. At most `MAX_ACCOUNT_CREATION_SCAN`
+/// transactions are scanned, so accounts created later in the creator's history aren't found
+async fn get_account_creation_transaction(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Option<TransactionView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account = AccountAddress::from_str(&address)?;
+    let include_events = get_include_events_param(&service, &request, 1)?;
+
+    let account_state = match get_account_state(&service, account, request.version())? {
+        Some(account_state) => account_state,
+        None => return Ok(None),
+    };
+    let creator = if let Some(child_vasp) =
+        account_state.get_resource::<ChildVASP>(&ChildVASP::resource_path())?
+    {
+        child_vasp.parent_vasp_addr()
+    } else if account_state
+        .get_resource::<ParentVASP>(&ParentVASP::resource_path())?
+        .is_some()
+        || account_state
+            .get_resource::<DesignatedDealer>(&DesignatedDealer::resource_path())?
+            .is_some()
+    {
+        treasury_compliance_account_address()
+    } else {
+        libra_root_address()
+    };
+    let sequence_number = match get_account_state(&service, creator, request.version())? {
+        Some(creator_state) => creator_state
+            .get_account_resource()?
+            .map_or(0, |account| account.sequence_number()),
+        None => return Ok(None),
+    };
+
+    for seq in 0..std::cmp::min(sequence_number, MAX_ACCOUNT_CREATION_SCAN) {
+        let txn =
+            match service
+                .db
+                .get_txn_by_account(creator, seq, request.version(), include_events)?
+            {
+                Some(txn) => txn,
+                None => continue,
+            };
+        if txn.proof.transaction_info().status() != &KeptVMStatus::Executed {
+            continue;
+        }
+        let creates_account = match txn.transaction.as_signed_user_txn() {
+            Ok(signed_txn) => match signed_txn.payload() {
+                TransactionPayload::Script(script) => {
+                    CREATE_ACCOUNT_TRANSACTIONS
+                        .contains(&get_transaction_name(script.code()).as_str())
+                        && script
+                            .args()
+                            .contains(&TransactionArgument::Address(account))
+                }
+                _ => false,
+            },
+            Err(_) => false,
+        };
+        if creates_account {
+            return Ok(Some(transaction_view(txn, include_events, false)?));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns the view of the given transaction, with the proof of its transaction info if requested
+fn transaction_view(
+    tx: TransactionWithProof,
+    include_events: bool,
+    include_proof: bool,
+) -> Result<TransactionView> {
+    if include_events {
+        ensure!(
+            tx.events.is_some(),
+            "Storage layer didn't return events when requested!"
+        );
+    }
+    let proof = if include_proof {
+        Some(BytesView::from(&lcs::to_bytes(&tx.proof)?))
+    } else {
+        None
+    };
+    let tx_version = tx.version;
+    let hash = tx.transaction.hash();
+
+    let events = tx
+        .events
+        .unwrap_or_default()
         .into_iter()
-        .filter(|(version, _event)| version <= &req_version)
-        .map(|event| event.into())
+        .enumerate()
+        .map(|(index, x)| indexed_event_view(tx_version, &hash, index, x))
         .collect();
+
+    Ok(TransactionView {
+        version: tx_version,
+        hash: hash.to_hex(),
+        bytes: lcs::serialized_size(&tx.transaction)? as u64,
+        gas_currency: gas_currency(&tx.transaction),
+        transaction: tx.transaction.into(),
+        events,
+        vm_status: tx.proof.transaction_info().status().into(),
+        gas_used: tx.proof.transaction_info().gas_used(),
+        proof,
+        events_truncated: false,
+    })
+}
+
+/// Returns the view of the event at the given index among the events emitted by the transaction
+/// at the given version
+fn indexed_event_view(
+    version: u64,
+    transaction_hash: &HashValue,
+    index: usize,
+    event: ContractEvent,
+) -> EventView {
+    EventView {
+        transaction_hash: Some(transaction_hash.to_hex()),
+        event_index: Some(index as u64),
+        ..EventView::from((version, event))
+    }
+}
+
+/// Returns the hashes of the transactions at the given versions. Only the transactions at these
+/// versions are read, once each: the events of a page may be emitted far apart
+fn transaction_hashes(
+    service: &JsonRpcService,
+    versions: &[u64],
+    ledger_version: u64,
+) -> Result<HashMap<u64, HashValue>> {
+    let mut hashes = HashMap::new();
+    for &version in versions {
+        if hashes.contains_key(&version) {
+            continue;
+        }
+        let txs = service
+            .db
+            .get_transactions(version, 1, ledger_version, false)?;
+        ensure_transaction_list(&txs, version, 1, ledger_version)?;
+        let txn = txs
+            .transactions
+            .first()
+            .ok_or_else(|| format_err!("transaction at version {} not found", version))?;
+        hashes.insert(version, txn.hash());
+    }
+    Ok(hashes)
+}
+
+/// Returns the currency the gas of the given transaction is paid in, if sent by a user
+fn gas_currency(transaction: &Transaction) -> Option<String> {
+    transaction
+        .as_signed_user_txn()
+        .ok()
+        .map(|txn| txn.gas_currency_code().to_string())
+}
+
+/// Returns events by given access path
+async fn get_events(service: JsonRpcService, request: JsonRpcRequest) -> Result<Vec<EventView>> {
+    let raw_event_key: String = request.deserialize_param(0, "key")?;
+    let start = request.get_u64_param(1, "start")?;
+    let limit = request.get_u64_param(2, "limit")?;
+    ensure_limit(limit, MAX_EVENTS_LIMIT)?;
+
+    let events = event_views(&service, &raw_event_key, start, limit, request.version())?;
+    ensure_response_size(&service, &events)?;
     Ok(events)
 }
 
-/// Returns meta information about supported currencies
+/// Version 2 of `get_events`: returns the events alongside a cursor to the following events, and
+/// takes either a sequence number or such a cursor as start
+async fn get_events_v2(service: JsonRpcService, request: JsonRpcRequest) -> Result<EventsPageView> {
+    let raw_event_key: String = request.deserialize_param(0, "key")?;
+    let event_key = EventKey::try_from(&hex::decode(&raw_event_key)?[..])?;
+    let start = match request.get_param(1) {
+        Value::String(cursor) if cursor.starts_with(EVENT_CURSOR_PREFIX) => {
+            let cursor = EventCursor::decode(&cursor)
+                .map_err(|err| JsonRpcError::invalid_param(1, "start", err.to_string()))?;
+            ensure!(
+                cursor.key == event_key,
+                JsonRpcError::invalid_param(
+                    1,
+                    "start",
+                    "is a cursor of another event stream".to_string()
+                )
+            );
+            cursor.start
+        }
+        _ => request.get_u64_param(1, "start")?,
+    };
+    let limit = request.get_u64_param(2, "limit")?;
+    ensure_limit(limit, MAX_EVENTS_LIMIT)?;
+
+    events_page(&service, &request, event_key, start, limit)
+}
+
+/// Returns the events of the stream of the cursor given as first param committed after it, up to
+/// the limit given as second param, alongside the cursor to the following events.
+/// Cursors older than `MAX_EVENT_CURSOR_AGE_USECS` fail, so a client resuming from a cursor it
+/// persisted long ago starts over from `get_events` rather than replaying an unbounded backlog
+async fn get_events_since_cursor(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<EventsPageView> {
+    let cursor: String = request.deserialize_param(0, "cursor")?;
+    let limit = request.get_u64_param(1, "limit")?;
+    ensure_limit(limit, MAX_EVENTS_LIMIT)?;
+
+    ensure!(
+        cursor.starts_with(EVENT_CURSOR_PREFIX),
+        JsonRpcError::invalid_param(0, "cursor", "is an invalid cursor".to_string())
+    );
+    let cursor = EventCursor::decode(&cursor)
+        .map_err(|err| JsonRpcError::invalid_param(0, "cursor", err.to_string()))?;
+    let age = request
+        .ledger_info
+        .ledger_info()
+        .timestamp_usecs()
+        .saturating_sub(cursor.issued_at_usecs);
+    ensure!(
+        age <= MAX_EVENT_CURSOR_AGE_USECS,
+        JsonRpcError::invalid_param(
+            0,
+            "cursor",
+            "has expired, read the events with get_events instead".to_string()
+        )
+    );
+
+    events_page(&service, &request, cursor.key, cursor.start, limit)
+}
+
+/// Returns the page of events of the given stream starting at `start`, alongside the cursor to
+/// the following events
+fn events_page(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    event_key: EventKey,
+    start: u64,
+    limit: u64,
+) -> Result<EventsPageView> {
+    let raw_event_key = hex::encode(event_key.as_bytes());
+    let events = event_views(service, &raw_event_key, start, limit, request.version())?;
+    ensure_response_size(service, &events)?;
+    // the stream may grow, so there always is a cursor to poll for the following events
+    let next_cursor = EventCursor {
+        key: event_key,
+        start: events
+            .last()
+            .map_or(start, |event| event.sequence_number + 1),
+        issued_at_usecs: request.ledger_info.ledger_info().timestamp_usecs(),
+    };
+    Ok(EventsPageView {
+        events,
+        next_cursor: next_cursor.encode()?,
+    })
+}
+
+/// Position in an event stream, handed to clients as an opaque string: the hex encoded LCS
+/// serialized cursor after `EVENT_CURSOR_PREFIX`
+#[derive(Deserialize, Serialize)]
+struct EventCursor {
+    key: EventKey,
+    start: u64,
+    /// Timestamp of the ledger info of the response the cursor was returned in
+    issued_at_usecs: u64,
+}
+
+impl EventCursor {
+    fn encode(&self) -> Result<String> {
+        Ok(format!(
+            "{}{}",
+            EVENT_CURSOR_PREFIX,
+            hex::encode(lcs::to_bytes(self)?)
+        ))
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let bytes = hex::decode(&cursor[EVENT_CURSOR_PREFIX.len()..])
+            .map_err(|_| format_err!("is an invalid cursor"))?;
+        lcs::from_bytes(&bytes).map_err(|_| format_err!("is an invalid cursor"))
+    }
+}
+
+/// Returns the page of events of the stream with the given hex encoded key starting at `start`,
+/// leaving out the events emitted after the given version.
+/// Pages starting past the last event of the stream are empty
+fn event_views(
+    service: &JsonRpcService,
+    raw_event_key: &str,
+    start: u64,
+    limit: u64,
+    ledger_version: u64,
+) -> Result<Vec<EventView>> {
+    let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
+    let account_state =
+        get_account_state(service, event_key.get_creator_address(), ledger_version)?;
+    let count = match account_state {
+        Some(account_state) => event_count(&account_state, &event_key)?,
+        None => None,
+    };
+    let mut limit = limit;
+    if let Some(count) = count {
+        if start >= count {
+            return Ok(vec![]);
+        }
+        limit = std::cmp::min(limit, count - start);
+    }
+    let events_with_proof: Vec<_> = service
+        .db
+        .get_events(&event_key, start, true, limit)?
+        .into_iter()
+        .filter(|(version, _)| *version <= ledger_version)
+        .collect();
+
+    let versions: Vec<_> = events_with_proof
+        .iter()
+        .map(|(version, _)| *version)
+        .collect();
+    let hashes = transaction_hashes(service, &versions, ledger_version)?;
+    Ok(events_with_proof
+        .into_iter()
+        .map(|(version, event)| EventView {
+            transaction_hash: Some(hashes[&version].to_hex()),
+            ..EventView::from((version, event))
+        })
+        .collect())
+}
+
+/// Returns the number of events emitted to the stream with the given key, read from the count of
+/// its event handle in the given state of the account that created it: its sent or received
+/// payment events, or the new block events. None for the keys of other streams, whose handles
+/// can't be located
+fn event_count(account_state: &AccountState, event_key: &EventKey) -> Result<Option<u64>> {
+    let mut handles = vec![];
+    if let Some(account_resource) = account_state.get_account_resource()? {
+        handles.push(account_resource.sent_events().clone());
+        handles.push(account_resource.received_events().clone());
+    }
+    if let Some(block_resource) = account_state.get_libra_block_resource()? {
+        handles.push(block_resource.new_block_events().clone());
+    }
+    Ok(handles
+        .iter()
+        .find(|handle| handle.key() == event_key)
+        .map(EventHandle::count))
+}
+
+/// Read of a `batch_fetch` call
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FetchRequest {
+    /// Resource of the given struct type held by the given account
+    Resource {
+        address: String,
+        resource_type: String,
+    },
+    /// Balance of the given account in the given currency
+    Balance { address: String, currency: String },
+    /// Page of events of the stream with the given key, as returned by `get_events`
+    Events { key: String, start: u64, limit: u64 },
+}
+
+/// Returns the results of the given resource, balance and event page reads, all at the same
+/// version, in the order they are requested: the optional version param, or the latest ledger
+/// version. Resources and balances are read from storage with a single read per account
+async fn batch_fetch(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<FetchResultView>> {
+    let fetches: Vec<FetchRequest> = request.deserialize_param(0, "fetches")?;
+    ensure!(
+        fetches.len() as u64 <= MAX_BATCH_FETCHES,
+        "at most {} reads can be batched, got {}",
+        MAX_BATCH_FETCHES,
+        fetches.len()
+    );
+    let version = get_version_param(&request, 1, "version")?;
+    ensure_not_pruned(&service, &request, version)?;
+
+    let mut access_paths = vec![];
+    for fetch in &fetches {
+        match fetch {
+            FetchRequest::Resource {
+                address,
+                resource_type,
+            } => access_paths.push(AccessPath::new(
+                AccountAddress::from_str(address)?,
+                AccessPath::resource_access_vec(&parse_struct_tag(resource_type)?),
+            )),
+            FetchRequest::Balance { address, currency } => access_paths.push(AccessPath::new(
+                AccountAddress::from_str(address)?,
+                BalanceResource::access_path_for(type_tag_for_currency_code(
+                    from_currency_code_string(currency)?,
+                )),
+            )),
+            FetchRequest::Events { limit, .. } => ensure_limit(*limit, MAX_EVENTS_LIMIT)?,
+        }
+    }
+    let mut resources = fetch_resources(&service, access_paths, version)?.into_iter();
+
+    let mut results = vec![];
+    for fetch in fetches {
+        results.push(match fetch {
+            FetchRequest::Resource { .. } => {
+                FetchResultView::Resource(resources.next().flatten().map(BytesView::from))
+            }
+            FetchRequest::Balance { currency, .. } => {
+                let balance = resources
+                    .next()
+                    .flatten()
+                    .map(|bytes| lcs::from_bytes::<BalanceResource>(&bytes))
+                    .transpose()?;
+                FetchResultView::Balance(balance.map(|balance| AmountView {
+                    amount: balance.coin(),
+                    currency,
+                }))
+            }
+            FetchRequest::Events { key, start, limit } => {
+                FetchResultView::Events(event_views(&service, &key, start, limit, version)?)
+            }
+        });
+    }
+    ensure_response_size(&service, &results)?;
+    Ok(results)
+}
+
+/// Returns the resources at the given access paths as of the given version, None for those that
+/// don't exist, reading each account once
+fn fetch_resources(
+    service: &JsonRpcService,
+    access_paths: Vec<AccessPath>,
+    version: u64,
+) -> Result<Vec<Option<Vec<u8>>>> {
+    let mut account_states = HashMap::new();
+    let mut resources = vec![];
+    for path in access_paths {
+        if !account_states.contains_key(&path.address) {
+            account_states.insert(
+                path.address,
+                get_account_state(service, path.address, version)?,
+            );
+        }
+        resources.push(
+            account_states[&path.address]
+                .as_ref()
+                .and_then(|account_state| account_state.get(&path.path))
+                .cloned(),
+        );
+    }
+    Ok(resources)
+}
+
+/// Returns the events of the given Move struct type emitted between the two given versions
+/// included, by any account, up to `limit` events.
+/// There is no index of events by type, so the version range is scanned: this is best-effort, and
+/// the range is capped at `MAX_EVENTS_BY_TYPE_SCAN` versions
+async fn get_events_by_type(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<EventView>> {
+    let event_type: String = request.deserialize_param(0, "event_type")?;
+    let start_version = request.get_u64_param(1, "start_version")?;
+    let end_version = request.get_u64_param(2, "end_version")?;
+    let limit = request.get_u64_param(3, "limit")?;
+
+    let type_tag = TypeTag::Struct(parse_struct_tag(&event_type)?);
+    ensure!(
+        start_version <= end_version && end_version - start_version < MAX_EVENTS_BY_TYPE_SCAN,
+        "at most {} versions can be scanned",
+        MAX_EVENTS_BY_TYPE_SCAN
+    );
+    ensure_limit(limit, MAX_EVENTS_LIMIT)?;
+
+    let end_version = std::cmp::min(end_version, request.version());
+    if start_version > end_version {
+        return Ok(vec![]);
+    }
+    let batch_size = end_version - start_version + 1;
+    let txs = service
+        .db
+        .get_transactions(start_version, batch_size, request.version(), true)?;
+    ensure_transaction_list(&txs, start_version, batch_size, request.version())?;
+    let all_events = txs
+        .events
+        .ok_or_else(|| format_err!("Storage layer didn't return events when requested!"))?;
+    let hashes: Vec<_> = txs.transactions.iter().map(CryptoHash::hash).collect();
+
+    let events: Vec<EventView> = all_events
+        .into_iter()
+        .enumerate()
+        .flat_map(|(offset, events)| {
+            events
+                .into_iter()
+                .enumerate()
+                .map(move |(index, event)| (offset, index, event))
+        })
+        .filter(|(_, _, event)| event.type_tag() == &type_tag)
+        .take(limit as usize)
+        .map(|(offset, index, event)| {
+            indexed_event_view(start_version + offset as u64, &hashes[offset], index, event)
+        })
+        .collect();
+    ensure_response_size(&service, &events)?;
+    Ok(events)
+}
+
+/// Returns the number of events emitted to the given sent or received payment event stream, or to
+/// the new block event stream
+async fn get_events_count(service: JsonRpcService, request: JsonRpcRequest) -> Result<u64> {
+    let raw_event_key: String = request.deserialize_param(0, "key")?;
+    let event_key = EventKey::try_from(&hex::decode(raw_event_key)?[..])?;
+
+    let account_state =
+        get_account_state(&service, event_key.get_creator_address(), request.version())?;
+    match account_state {
+        Some(account_state) => event_count(&account_state, &event_key)?.ok_or_else(|| {
+            Error::new(JsonRpcError::invalid_param(
+                0,
+                "key",
+                "is not the key of a sent or received payment or new block event stream"
+                    .to_string(),
+            ))
+        }),
+        // no events can have been emitted by an account that doesn't exist yet
+        None => Ok(0),
+    }
+}
+
+/// Returns meta information about supported currencies, sorted by currency code.
+/// The order doesn't depend on the order currencies were registered in, so clients can rely on it
 async fn currencies_info(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<Vec<CurrencyInfoView>> {
+    let mut currencies: Vec<_> = currency_info_resources(&service, request.version())?
+        .into_iter()
+        .map(CurrencyInfoView::from)
+        .collect();
+    currencies.sort_by(|a, b| a.code.cmp(&b.code));
+    Ok(currencies)
+}
+
+/// Returns the `CurrencyInfo` resources of the registered currencies as of the given version
+fn currency_info_resources(
+    service: &JsonRpcService,
+    version: u64,
+) -> Result<Vec<CurrencyInfoResource>> {
     let raw_data = service.db.deref().batch_fetch_resources_by_version(
         vec![RegisteredCurrencies::CONFIG_ID.access_path()],
-        request.version(),
+        version,
     )?;
     ensure!(raw_data.len() == 1, "invalid storage result");
     let currencies = RegisteredCurrencies::from_bytes(&raw_data[0])?;
@@ -306,60 +1905,722 @@ async fn currencies_info(
         .map(|code| CurrencyInfoResource::resource_path_for(code.clone()))
         .collect();
 
-    let mut currencies = vec![];
-    for raw_data in service
+    service
         .db
         .deref()
-        .batch_fetch_resources_by_version(access_paths, request.version())?
-    {
-        let currency_info = CurrencyInfoResource::try_from_bytes(&raw_data)?;
-        currencies.push(CurrencyInfoView::from(currency_info));
+        .batch_fetch_resources_by_version(access_paths, version)?
+        .iter()
+        .map(|raw_data| CurrencyInfoResource::try_from_bytes(raw_data))
+        .collect()
+}
+
+/// Returns meta information about supported currencies, sorted by currency code.
+/// Currencies retired by disabling their minting are left out unless `include_inactive`, which
+/// defaults to true
+async fn get_currencies(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<CurrencyInfoView>> {
+    let include_inactive: bool = request
+        .deserialize_param::<Option<bool>>(0, "include_inactive")?
+        .unwrap_or(true);
+    let mut currencies = currencies_info(service, request).await?;
+    if !include_inactive {
+        currencies.retain(|info| info.can_mint);
     }
     Ok(currencies)
 }
 
+/// Converts the given amount from one registered currency to another, using the on-chain exchange
+/// rates to LBR of both currencies as of the requested version.
+/// Rates are the exact on-chain 32|32 fixed-point numbers and the conversion is done in integer
+/// arithmetic, rounding down to the smallest unit of the target currency
+async fn convert_amount(service: JsonRpcService, request: JsonRpcRequest) -> Result<u64> {
+    let amount = request.get_u64_param(0, "amount")?;
+    let from_currency: String = request.deserialize_param(1, "from_currency")?;
+    let to_currency: String = request.deserialize_param(2, "to_currency")?;
+
+    let currencies = currency_info_resources(&service, request.version())?;
+    let find_currency = |index: usize, name: &str, code: &str| {
+        currencies
+            .iter()
+            .find(|info| info.currency_code().as_str() == code)
+            .ok_or_else(|| {
+                Error::new(JsonRpcError::invalid_param(
+                    index,
+                    name,
+                    format!("unknown currency {}", code),
+                ))
+            })
+    };
+    let from = find_currency(1, "from_currency", &from_currency)?;
+    let to = find_currency(2, "to_currency", &to_currency)?;
+    ensure!(
+        to.to_lbr_exchange_rate() > 0,
+        "currency {} has no exchange rate to LBR",
+        to_currency
+    );
+    ensure!(
+        from.scaling_factor() > 0,
+        "currency {} has no scaling factor",
+        from_currency
+    );
+
+    // amount / from_scaling * from_rate / to_rate * to_scaling, with both rates scaled by 2^32
+    let converted = u128::from(amount)
+        .checked_mul(u128::from(from.to_lbr_exchange_rate()))
+        .and_then(|value| value.checked_mul(u128::from(to.scaling_factor())))
+        .map(|value| {
+            value / u128::from(from.scaling_factor()) / u128::from(to.to_lbr_exchange_rate())
+        });
+    converted
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| {
+            Error::new(JsonRpcError::invalid_param(
+                0,
+                "amount",
+                format!("converted amount overflows u64: {}", amount),
+            ))
+        })
+}
+
+/// Returns advisory gas unit prices per registered currency
+/// This is a heuristic, not a guarantee of inclusion: it samples the gas unit prices of the user
+/// transactions among the last `window` committed transactions (defaults to
+/// `DEFAULT_GAS_ESTIMATE_WINDOW`) and reports their median and 90th percentile, never below the
+/// on-chain minimum gas price. Currencies without samples report the on-chain minimum
+async fn get_gas_estimate(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<GasEstimateView>> {
+    let window = request
+        .get_optional_u64_param(0, "window")?
+        .unwrap_or(DEFAULT_GAS_ESTIMATE_WINDOW);
+    ensure_limit(window, MAX_GAS_ESTIMATE_WINDOW)?;
+
+    let min_gas_unit_price = get_on_chain_config::<VMConfig>(&service, request.version())?
+        .gas_schedule
+        .gas_constants
+        .min_price_per_gas_unit
+        .get();
+
+    let start_version = (request.version() + 1).saturating_sub(window);
+    let txs = service.db.get_transactions(
+        start_version,
+        request.version() + 1 - start_version,
+        request.version(),
+        false,
+    )?;
+    let mut samples: HashMap<String, Vec<u64>> = HashMap::new();
+    for tx in txs.transactions {
+        if let Ok(signed_txn) = tx.as_signed_user_txn() {
+            samples
+                .entry(signed_txn.gas_currency_code().to_string())
+                .or_default()
+                .push(signed_txn.gas_unit_price());
+        }
+    }
+
+    let currencies = currencies_info(service, request).await?;
+    Ok(currencies
+        .into_iter()
+        .map(|info| {
+            let mut prices = samples.remove(&info.code).unwrap_or_default();
+            prices.sort_unstable();
+            let percentile = |p: usize| {
+                if prices.is_empty() {
+                    min_gas_unit_price
+                } else {
+                    let rank = (p * prices.len() + 99) / 100;
+                    std::cmp::max(prices[rank.saturating_sub(1)], min_gas_unit_price)
+                }
+            };
+            GasEstimateView {
+                currency: info.code,
+                sample_size: prices.len() as u64,
+                min_gas_unit_price,
+                median_gas_unit_price: percentile(50),
+                p90_gas_unit_price: percentile(90),
+            }
+        })
+        .collect())
+}
+
+/// Returns the VM publishing policy: the allowed script hashes and whether module publishing is
+/// open. Uses the policy at the given version if specified, at the latest version otherwise
+async fn get_vm_publishing_option(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<VMPublishingOptionView> {
+    let version = get_version_param(&request, 0, "version")?;
+    let vm_config = get_on_chain_config::<VMConfig>(&service, version)?;
+    Ok(VMPublishingOptionView::from(vm_config.publishing_option))
+}
+
 /// Returns proof of new state relative to version known to client
+/// With `epoch_change_only` set, the ledger consistency proof is neither computed nor returned,
+/// for clients only advancing their trusted epoch: the epoch change proof is read from the epoch
+/// of the known version
 async fn get_state_proof(
     service: JsonRpcService,
     request: JsonRpcRequest,
 ) -> Result<StateProofView> {
-    let known_version: u64 = serde_json::from_value(request.get_param(0))?;
+    let known_version = request.get_u64_param(0, "version")?;
+    let epoch_change_only = request
+        .deserialize_param::<Option<bool>>(1, "epoch_change_only")?
+        .unwrap_or(false);
+    if epoch_change_only {
+        ensure!(
+            known_version <= request.version(),
+            "version {} is ahead of the latest version {}",
+            known_version,
+            request.version()
+        );
+        let latest_epoch = request.ledger_info.ledger_info().next_block_epoch();
+        let (known_epoch, _) = epoch_of_version(&service, known_version, latest_epoch)?;
+        let proof = if known_epoch < latest_epoch {
+            service
+                .db
+                .get_epoch_ending_ledger_infos(known_epoch, latest_epoch)?
+        } else {
+            EpochChangeProof::new(vec![], false)
+        };
+        let view = EpochChangeProofView::try_from((request.ledger_info, proof))?;
+        return Ok(StateProofView {
+            ledger_info_with_signatures: view.ledger_info_with_signatures,
+            epoch_change_proof: view.epoch_change_proof,
+            ledger_consistency_proof: None,
+        });
+    }
     let proofs = service
         .db
         .get_state_proof_with_ledger_info(known_version, request.ledger_info.clone())?;
     StateProofView::try_from((request.ledger_info, proofs.0, proofs.1))
 }
 
+/// Returns the epoch changes from the epoch trusted by the client, alongside the latest ledger
+/// info. At most `MAX_EPOCH_CHANGES` epoch changes are returned, the client advancing its trusted
+/// state and calling again while `more` is set
+async fn get_epoch_change_proof(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<EpochChangeProofView> {
+    let known_epoch = request.get_u64_param(0, "known_epoch")?;
+    let latest_epoch = request.ledger_info.ledger_info().next_block_epoch();
+    ensure!(
+        known_epoch <= latest_epoch,
+        "known epoch {} is ahead of the latest epoch {}",
+        known_epoch,
+        latest_epoch
+    );
+
+    let end_epoch = std::cmp::min(latest_epoch, known_epoch.saturating_add(MAX_EPOCH_CHANGES));
+    let mut proof = service
+        .db
+        .get_epoch_ending_ledger_infos(known_epoch, end_epoch)?;
+    proof.more |= end_epoch < latest_epoch;
+    EpochChangeProofView::try_from((request.ledger_info, proof))
+}
+
+/// Returns the ledger info signing the given version, or the nearest one after it: storage only
+/// keeps the ledger infos ending epochs, and the latest one. That is the ledger info ending the
+/// epoch of the version, or the latest ledger info if the version is in the current epoch
+async fn get_ledger_info_at_version(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<LedgerInfoView> {
+    let version = request.get_u64_param(0, "version")?;
+    ensure!(
+        version <= request.version(),
+        "version {} is ahead of the latest version {}",
+        version,
+        request.version()
+    );
+    ensure_not_pruned(&service, &request, version)?;
+
+    let latest_epoch = request.ledger_info.ledger_info().next_block_epoch();
+    let (_, ledger_info) = epoch_of_version(&service, version, latest_epoch)?;
+    LedgerInfoView::try_from(ledger_info.as_ref().unwrap_or(&request.ledger_info))
+}
+
+/// Returns the epoch of the given version, alongside the ledger info ending that epoch if it
+/// ended before the given latest epoch. A version ending an epoch belongs to that epoch.
+/// The epoch is found by binary search over the versions of the epoch ending ledger infos, which
+/// increase with epochs
+fn epoch_of_version(
+    service: &JsonRpcService,
+    version: u64,
+    latest_epoch: u64,
+) -> Result<(u64, Option<LedgerInfoWithSignatures>)> {
+    let epoch_ending_ledger_info = |epoch: u64| -> Result<LedgerInfoWithSignatures> {
+        service
+            .db
+            .get_epoch_ending_ledger_infos(epoch, epoch + 1)?
+            .ledger_info_with_sigs
+            .pop()
+            .ok_or_else(|| format_err!("ledger info ending epoch {} not found", epoch))
+    };
+    // epochs before `low` end before the version, epochs from `high` end at or after it
+    let mut low = 0;
+    let mut high = latest_epoch;
+    let mut ledger_info = None;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        let mid_ledger_info = epoch_ending_ledger_info(mid)?;
+        if mid_ledger_info.ledger_info().version() >= version {
+            high = mid;
+            ledger_info = Some(mid_ledger_info);
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok((low, ledger_info))
+}
+
 /// Returns the account state to the client, alongside a proof relative to the version and
 /// ledger_version specified by the client. If version or ledger_version are not specified,
 /// the latest known versions will be used.
+/// In raw mode, the LCS serialized `AccountStateWithProof` is returned as hex encoded bytes
+/// instead of the decoded `AccountStateWithProofView`
 async fn get_account_state_with_proof(
     service: JsonRpcService,
     request: JsonRpcRequest,
-) -> Result<AccountStateWithProofView> {
-    let address: String = serde_json::from_value(request.get_param(0))?;
+) -> Result<Value> {
+    let address: String = request.deserialize_param(0, "account")?;
     let account_address = AccountAddress::from_str(&address)?;
 
     // If versions are specified by the request parameters, use them, otherwise use the defaults
-    let version =
-        serde_json::from_value::<u64>(request.get_param(1)).unwrap_or_else(|_| request.version());
-    let ledger_version =
-        serde_json::from_value::<u64>(request.get_param(2)).unwrap_or_else(|_| request.version());
+    let version = request
+        .get_optional_u64_param(1, "version")?
+        .unwrap_or_else(|| request.version());
+    let ledger_version = request
+        .get_optional_u64_param(2, "ledger_version")?
+        .unwrap_or_else(|| request.version());
+
+    let raw: bool = request
+        .deserialize_param::<Option<bool>>(3, "raw")?
+        .unwrap_or(false);
 
     let account_state_with_proof =
         service
             .db
             .get_account_state_with_proof(account_address, version, ledger_version)?;
-    Ok(AccountStateWithProofView::try_from(
+    if raw {
+        return Ok(serde_json::to_value(BytesView::from(&lcs::to_bytes(
+            &account_state_with_proof,
+        )?))?);
+    }
+    Ok(serde_json::to_value(AccountStateWithProofView::try_from(
         account_state_with_proof,
+    )?)?)
+}
+
+/// Returns the state of the given account at the latest ledger version, alongside its proof
+/// relative to that version. Unlike `get_account_state_with_proof`, it takes no version params
+async fn get_account_state_with_proof_latest(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<AccountStateWithProofView> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let version = request.version();
+    AccountStateWithProofView::try_from(service.db.get_account_state_with_proof(
+        account_address,
+        version,
+        version,
     )?)
 }
 
-/// Returns the number of peers this node is connected to
+/// Returns the state of the given account at the given version, or the latest ledger version,
+/// alongside its sparse merkle proof relative to the state root of that version.
+/// Unlike `get_account_state_with_proof`, the proof doesn't include the transaction info and
+/// accumulator proofs linking that state root to a ledger info
+async fn get_account_state_with_proof_by_version(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<AccountStateWithSparseProofView> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let version = get_version_param(&request, 1, "version")?;
+    ensure_not_pruned(&service, &request, version)?;
+
+    let (blob, proof) = service
+        .db
+        .get_account_state_with_proof_by_version(account_address, version)?;
+    let blob = match blob {
+        Some(blob) => Some(BytesView::from(&lcs::to_bytes(&blob)?)),
+        None => None,
+    };
+    Ok(AccountStateWithSparseProofView {
+        version,
+        blob,
+        proof: BytesView::from(&lcs::to_bytes(&proof)?),
+    })
+}
+
+/// Returns the state of the given account alongside its proof at each of the given versions, all
+/// relative to the same ledger version, in the order of the versions.
+/// A version which can't be served, because it is ahead of the ledger version or pruned, gets an
+/// error entry rather than failing the whole call
+async fn get_account_state_with_proofs(
+    service: JsonRpcService,
+    request: JsonRpcRequest,
+) -> Result<Vec<AccountStateWithProofResultView>> {
+    let address: String = request.deserialize_param(0, "account")?;
+    let account_address = AccountAddress::from_str(&address)?;
+    let versions = request.get_u64_array_param(1, "versions")?;
+    ensure!(
+        versions.len() as u64 <= MAX_ACCOUNT_STATE_PROOFS,
+        "at most {} versions can be requested, got {}",
+        MAX_ACCOUNT_STATE_PROOFS,
+        versions.len()
+    );
+    let ledger_version = get_version_param(&request, 2, "ledger_version")?;
+
+    let get_proof = |version: u64| -> Result<AccountStateWithProofView> {
+        ensure!(
+            version <= ledger_version,
+            "version {} is ahead of the ledger version {}",
+            version,
+            ledger_version
+        );
+        ensure_not_pruned(&service, &request, version)?;
+        AccountStateWithProofView::try_from(service.db.get_account_state_with_proof(
+            account_address,
+            version,
+            ledger_version,
+        )?)
+    };
+    // each distinct version is read once, in ascending order, against the same ledger version
+    let proofs: BTreeMap<u64, AccountStateWithProofResultView> = versions
+        .iter()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|version| {
+            let result = match get_proof(version) {
+                Ok(proof) => AccountStateWithProofResultView::Ok(proof),
+                Err(err) => AccountStateWithProofResultView::Err {
+                    error: err
+                        .downcast::<JsonRpcError>()
+                        .unwrap_or_else(|err| JsonRpcError::internal_error(err.to_string())),
+                },
+            };
+            (version, result)
+        })
+        .collect();
+    Ok(versions
+        .iter()
+        .map(|version| proofs[version].clone())
+        .collect())
+}
+
+/// Returns the number of peers this node is connected to.
+/// On public full nodes, the method is disabled unless configured otherwise by
+/// `public_network_status`
 async fn get_network_status(service: JsonRpcService, _request: JsonRpcRequest) -> Result<u64> {
-    let blah = counters::LIBRA_NETWORK_PEERS
-        .get_metric_with_label_values(&[service.role.as_str(), "connected"])?;
-    Ok(blah.get() as u64)
+    Ok(network_status(&service)?.connected_peers)
+}
+
+/// Returns the number of peers this node is connected to, in total and on each network.
+/// On public full nodes, the method is disabled or the per network breakdown omitted unless
+/// configured otherwise by `public_network_status`
+async fn get_network_status_by_network(
+    service: JsonRpcService,
+    _request: JsonRpcRequest,
+) -> Result<NetworkStatusView> {
+    network_status(&service)
+}
+
+/// Returns the number of connected peers, in total and on each network as disclosed by the
+/// `public_network_status` policy
+fn network_status(service: &JsonRpcService) -> Result<NetworkStatusView> {
+    let mut peers = connected_peers_metrics(service.role);
+    let policy = if service.public_full_node {
+        service.public_network_status
+    } else {
+        NetworkStatusPolicy::Full
+    };
+    if policy == NetworkStatusPolicy::Disabled {
+        return Err(Error::new(JsonRpcError::method_not_found()));
+    }
+
+    let connected_peers = counters::LIBRA_NETWORK_PEERS
+        .get_metric_with_label_values(&[service.role.as_str(), "connected"])?
+        .get() as u64;
+
+    let mut networks = BTreeMap::new();
+    if policy == NetworkStatusPolicy::Full {
+        // a peer is briefly connected in both directions while a simultaneous dial is resolved
+        peers.sort_by(|a, b| (&a.network, &a.peer_id).cmp(&(&b.network, &b.peer_id)));
+        peers.dedup_by(|a, b| a.network == b.network && a.peer_id == b.peer_id);
+        for peer in peers {
+            *networks.entry(peer.network).or_insert(0) += 1;
+        }
+    }
+
+    Ok(NetworkStatusView {
+        connected_peers,
+        networks,
+    })
+}
+
+/// Returns the peers this node is connected to.
+/// Public full nodes don't disclose their peers and return an empty list
+async fn get_peers(service: JsonRpcService, _request: JsonRpcRequest) -> Result<Vec<PeerView>> {
+    if service.public_full_node {
+        return Ok(vec![]);
+    }
+    Ok(connected_peers_metrics(service.role))
+}
+
+/// Returns the connected peers of the networks with the given role, as tracked by the network
+/// layer metrics
+fn connected_peers_metrics(role: RoleType) -> Vec<PeerView> {
+    let mut peers = vec![];
+    for metric_family in counters::LIBRA_NETWORK_PEER_CONNECTED.collect() {
+        for metric in metric_family.get_metric() {
+            if metric.get_gauge().get_value() <= 0.0 {
+                continue;
+            }
+            let labels: HashMap<_, _> = metric
+                .get_label()
+                .iter()
+                .map(|label| (label.get_name(), label.get_value()))
+                .collect();
+            if labels.get("role_type") != Some(&role.as_str()) {
+                continue;
+            }
+            peers.push(PeerView {
+                peer_id: labels["peer_id"].to_string(),
+                network: labels["network_id"].to_string(),
+                direction: labels["origin"].to_string(),
+            });
+        }
+    }
+    peers
+}
+
+/// Returns the version of this server and the build it comes from, embedded at compile time
+async fn get_server_version(
+    _service: JsonRpcService,
+    _request: JsonRpcRequest,
+) -> Result<ServerVersionView> {
+    Ok(ServerVersionView {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: env!("GIT_REV").to_string(),
+        build_timestamp: env!("BUILD_TIMESTAMP").parse()?,
+    })
+}
+
+/// Returns the features this server supports and their limits, as configured
+async fn get_capabilities(
+    service: JsonRpcService,
+    _request: JsonRpcRequest,
+) -> Result<CapabilitiesView> {
+    let non_zero = |limit: u64| if limit > 0 { Some(limit) } else { None };
+    let mut methods: Vec<_> = build_registry().into_iter().map(|(name, _)| name).collect();
+    methods.sort();
+
+    Ok(CapabilitiesView {
+        methods,
+        deprecated_methods: DEPRECATED_METHODS
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        batch: true,
+        max_batch_cost: non_zero(service.batch_cost_budget),
+        max_transactions_limit: MAX_TRANSACTIONS_LIMIT,
+        max_events_limit: MAX_EVENTS_LIMIT,
+        max_response_size: non_zero(service.max_response_size),
+        encodings: ENCODINGS
+            .iter()
+            .map(|encoding| encoding.to_string())
+            .collect(),
+        subscriptions: false,
+        historical_reads: service.prune_window.is_none(),
+        prune_window: service.prune_window,
+        include_events_by_default: service.include_events_by_default,
+    })
+}
+
+/// Returns the settings of this node affecting how its API behaves.
+/// Each field is copied from the configuration explicitly, rather than the configuration being
+/// serialized and filtered, so that settings added to it later are never disclosed by mistake
+async fn get_node_config(
+    service: JsonRpcService,
+    _request: JsonRpcRequest,
+) -> Result<NodeConfigView> {
+    let non_zero = |limit: u64| if limit > 0 { Some(limit) } else { None };
+    Ok(NodeConfigView {
+        role: service.role.as_str().to_string(),
+        chain_id: service.chain_id.id(),
+        max_transactions_limit: MAX_TRANSACTIONS_LIMIT,
+        max_events_limit: MAX_EVENTS_LIMIT,
+        max_response_size: non_zero(service.max_response_size),
+        max_events_per_transaction: non_zero(service.max_events_per_transaction),
+        max_transactions_events: non_zero(service.max_transactions_events),
+        max_batch_cost: non_zero(service.batch_cost_budget),
+        submit_restricted: service.submit_restricted,
+        prune_window: service.prune_window,
+        encodings: ENCODINGS
+            .iter()
+            .map(|encoding| encoding.to_string())
+            .collect(),
+        include_events_by_default: service.include_events_by_default,
+        u64_as_string_by_default: service.u64_as_string_by_default,
+    })
+}
+
+/// Returns the OpenRPC description of all available methods
+async fn rpc_discover(_service: JsonRpcService, _request: JsonRpcRequest) -> Result<Value> {
+    Ok(openrpc::openrpc_document())
+}
+
+/// Returns the decoded state of the given account at the given version, if the account exists
+fn get_account_state(
+    service: &JsonRpcService,
+    address: AccountAddress,
+    version: u64,
+) -> Result<Option<AccountState>> {
+    let (blob, _proof) = service
+        .db
+        .get_account_state_with_proof_by_version(address, version)?;
+    blob.map(|blob| AccountState::try_from(&blob)).transpose()
+}
+
+/// Returns the optional version param of the given name at the given index, defaulting to the
+/// latest ledger version
+fn get_version_param(request: &JsonRpcRequest, index: usize, name: &str) -> Result<u64> {
+    let version = request
+        .get_optional_u64_param(index, name)?
+        .unwrap_or_else(|| request.version());
+    ensure!(
+        version <= request.version(),
+        "{} {} is ahead of the latest ledger version {}",
+        name,
+        version,
+        request.version()
+    );
+    Ok(version)
+}
+
+/// Returns the optional `include_events` param at the given index.
+/// Absent or null, it defaults to the server's `include_events_by_default` config
+fn get_include_events_param(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    index: usize,
+) -> Result<bool> {
+    let include_events: Option<bool> = request.deserialize_param(index, "include_events")?;
+    Ok(include_events.unwrap_or(service.include_events_by_default))
+}
+
+/// Fails with an `InvalidLimit` error if the given limit is 0 or exceeds the maximum limit
+fn ensure_limit(limit: u64, max_limit: u64) -> Result<()> {
+    ensure!(
+        limit > 0,
+        JsonRpcError::invalid_limit(limit, max_limit, "limit must be greater than 0".to_string())
+    );
+    ensure!(
+        limit <= max_limit,
+        JsonRpcError::invalid_limit(limit, max_limit, format!("limit must be <= {}", max_limit))
+    );
+    Ok(())
+}
+
+/// Fails with a `ResponseTooLarge` error if the serialized result exceeds the maximum response size
+fn ensure_response_size<T: Serialize>(service: &JsonRpcService, result: &T) -> Result<()> {
+    if service.max_response_size == 0 {
+        return Ok(());
+    }
+    let size = serialization::serialized_size(result)?;
+    let max_size = service.max_response_size as usize;
+    if size > max_size {
+        return Err(Error::new(JsonRpcError::response_too_large(size, max_size)));
+    }
+    Ok(())
+}
+
+/// Fails with a `StatePruned` error if the state at the given version was pruned from storage and
+/// isn't held by the read replica either
+fn ensure_not_pruned(
+    service: &JsonRpcService,
+    request: &JsonRpcRequest,
+    version: u64,
+) -> Result<()> {
+    match service.prune_window {
+        Some(prune_window)
+            if version.saturating_add(prune_window) < request.version()
+                && !service.is_pruned_state_available(version) =>
+        {
+            Err(Error::new(JsonRpcError::state_pruned(version)))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fails with an internal error if the transaction list storage returned for the given range is
+/// inconsistent: starting at another version, longer than requested or past the ledger version, or
+/// with more or fewer transaction infos or events than transactions. A list shorter than requested
+/// is valid, as reads past the end of the ledger are cut short
+fn ensure_transaction_list(
+    txs: &TransactionListWithProof,
+    start_version: u64,
+    limit: u64,
+    ledger_version: u64,
+) -> Result<()> {
+    let count = txs.transactions.len() as u64;
+    if count == 0 {
+        ensure!(
+            txs.proof.transaction_infos().is_empty(),
+            "storage returned {} transaction infos without transactions from version {}",
+            txs.proof.transaction_infos().len(),
+            start_version
+        );
+        return Ok(());
+    }
+    ensure!(
+        txs.first_transaction_version == Some(start_version),
+        "storage returned transactions from version {:?}, requested from version {}",
+        txs.first_transaction_version,
+        start_version
+    );
+    ensure!(
+        count <= limit && start_version + count - 1 <= ledger_version,
+        "storage returned {} transactions from version {}, past limit {} or ledger version {}",
+        count,
+        start_version,
+        limit,
+        ledger_version
+    );
+    ensure!(
+        txs.proof.transaction_infos().len() as u64 == count,
+        "storage returned {} transaction infos for {} transactions from version {}",
+        txs.proof.transaction_infos().len(),
+        count,
+        start_version
+    );
+    if let Some(events) = txs.events.as_ref() {
+        ensure!(
+            events.len() as u64 == count,
+            "storage returned events of {} transactions for {} transactions from version {}",
+            events.len(),
+            count,
+            start_version
+        );
+    }
+    Ok(())
+}
+
+/// Returns the on-chain config `T` at the given version
+fn get_on_chain_config<T: OnChainConfig>(service: &JsonRpcService, version: u64) -> Result<T> {
+    let raw_data = service
+        .db
+        .deref()
+        .batch_fetch_resources_by_version(vec![T::CONFIG_ID.access_path()], version)?;
+    ensure!(raw_data.len() == 1, "invalid storage result");
+    T::deserialize_into_config(&raw_data[0])
 }
 
 /// Builds registry of all available RPC methods
@@ -370,27 +2631,149 @@ pub(crate) fn build_registry() -> RpcRegistry {
     let mut registry = RpcRegistry::new();
     register_rpc_method!(registry, "submit", submit, 1, 0);
     register_rpc_method!(registry, "get_metadata", get_metadata, 0, 1);
-    register_rpc_method!(registry, "get_account", get_account, 1, 0);
-    register_rpc_method!(registry, "get_transactions", get_transactions, 3, 0);
+    register_rpc_method!(registry, "get_latest_version", get_latest_version, 0, 0);
+    register_rpc_method!(registry, "get_account", get_account, 1, 3);
+    register_rpc_method!(registry, "v2.get_account", get_account_v2, 1, 3);
+    register_rpc_method!(registry, "get_account_diff", get_account_diff, 3, 0);
+    register_rpc_method!(registry, "get_resource", get_resource, 2, 0);
+    register_rpc_method!(
+        registry,
+        "get_account_modules_with_deps",
+        get_account_modules_with_deps,
+        1,
+        1
+    );
+    register_rpc_method!(registry, "is_account_frozen", is_account_frozen, 1, 0);
+    register_rpc_method!(registry, "get_account_role", get_account_role, 1, 0);
+    register_rpc_method!(registry, "list_child_vasps", list_child_vasps, 1, 0);
+    register_rpc_method!(
+        registry,
+        "get_treasury_compliance_info",
+        get_treasury_compliance_info,
+        0,
+        0
+    );
+    register_rpc_method!(
+        registry,
+        "get_account_creation_transaction",
+        get_account_creation_transaction,
+        1,
+        1
+    );
+    register_rpc_method!(registry, "get_transactions", get_transactions, 2, 2);
+    register_rpc_method!(
+        registry,
+        "get_transaction_infos",
+        get_transaction_infos,
+        2,
+        0
+    );
     register_rpc_method!(
         registry,
         "get_account_transaction",
         get_account_transaction,
+        2,
+        2
+    );
+    register_rpc_method!(
+        registry,
+        "get_mempool_transactions_for_account",
+        get_mempool_transactions_for_account,
+        1,
+        1
+    );
+    register_rpc_method!(
+        registry,
+        "get_transactions_by_time",
+        get_transactions_by_time,
         3,
-        0
+        1
     );
     register_rpc_method!(registry, "get_events", get_events, 3, 0);
-    register_rpc_method!(registry, "get_currencies", currencies_info, 0, 0);
+    register_rpc_method!(registry, "v2.get_events", get_events_v2, 3, 0);
+    register_rpc_method!(
+        registry,
+        "get_events_since_cursor",
+        get_events_since_cursor,
+        2,
+        0
+    );
+    register_rpc_method!(registry, "get_events_count", get_events_count, 1, 0);
+    register_rpc_method!(registry, "get_events_by_type", get_events_by_type, 4, 0);
+    register_rpc_method!(registry, "batch_fetch", batch_fetch, 1, 1);
+    register_rpc_method!(registry, "get_currencies", get_currencies, 0, 1);
+    register_rpc_method!(registry, "convert_amount", convert_amount, 3, 0);
+    register_rpc_method!(registry, "get_gas_estimate", get_gas_estimate, 0, 1);
+    register_rpc_method!(
+        registry,
+        "get_vm_publishing_option",
+        get_vm_publishing_option,
+        0,
+        1
+    );
 
-    register_rpc_method!(registry, "get_state_proof", get_state_proof, 1, 0);
+    register_rpc_method!(registry, "get_state_proof", get_state_proof, 1, 1);
+    register_rpc_method!(
+        registry,
+        "get_epoch_change_proof",
+        get_epoch_change_proof,
+        1,
+        0
+    );
+    register_rpc_method!(
+        registry,
+        "get_ledger_info_at_version",
+        get_ledger_info_at_version,
+        1,
+        0
+    );
     register_rpc_method!(
         registry,
         "get_account_state_with_proof",
         get_account_state_with_proof,
         3,
+        1
+    );
+    register_rpc_method!(
+        registry,
+        "get_account_state_with_proof_latest",
+        get_account_state_with_proof_latest,
+        1,
+        0
+    );
+    register_rpc_method!(
+        registry,
+        "get_account_state_with_proofs",
+        get_account_state_with_proofs,
+        3,
         0
     );
+    register_rpc_method!(
+        registry,
+        "get_account_state_with_proof_by_version",
+        get_account_state_with_proof_by_version,
+        1,
+        1
+    );
     register_rpc_method!(registry, "get_network_status", get_network_status, 0, 0);
+    register_rpc_method!(
+        registry,
+        "get_network_status_by_network",
+        get_network_status_by_network,
+        0,
+        0
+    );
+    register_rpc_method!(registry, "get_peers", get_peers, 0, 0);
+    register_rpc_method!(registry, "get_server_version", get_server_version, 0, 0);
+    register_rpc_method!(registry, "get_capabilities", get_capabilities, 0, 0);
+    register_rpc_method!(registry, "get_node_config", get_node_config, 0, 0);
+    register_rpc_method!(registry, "rpc.discover", rpc_discover, 0, 0);
 
+    for (name, deprecation) in DEPRECATED_METHODS {
+        registry
+            .get_mut(*name)
+            .unwrap_or_else(|| panic!("[rpc] unknown deprecated method {}", name))
+            .deprecation = Some(deprecation.clone());
+    }
     registry
 }
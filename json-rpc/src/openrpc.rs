@@ -0,0 +1,465 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenRPC description of the JSON RPC API, served by the `rpc.discover` method
+//!
+//! Specification: https://spec.open-rpc.org
+//!
+//! Every method of the registry must be described in `METHODS`, in the order of its parameters.
+use crate::methods::DEPRECATED_METHODS;
+use serde_json::{json, Value};
+
+const OPENRPC_VERSION: &str = "1.2.6";
+const API_VERSION: &str = "1.0.0";
+
+/// Description of a JSON RPC method parameter
+struct ParamSpec {
+    name: &'static str,
+    /// JSON schema type of the parameter
+    schema_type: &'static str,
+    required: bool,
+}
+
+/// Description of a JSON RPC method
+struct MethodSpec {
+    name: &'static str,
+    summary: &'static str,
+    params: &'static [ParamSpec],
+    /// Name of the view returned by the method, as defined in `libra_json_rpc_types::views`
+    result: &'static str,
+    /// JSON schema of the result
+    result_schema: &'static str,
+}
+
+const fn required(name: &'static str, schema_type: &'static str) -> ParamSpec {
+    ParamSpec {
+        name,
+        schema_type,
+        required: true,
+    }
+}
+
+const fn optional(name: &'static str, schema_type: &'static str) -> ParamSpec {
+    ParamSpec {
+        name,
+        schema_type,
+        required: false,
+    }
+}
+
+const METHODS: &[MethodSpec] = &[
+    MethodSpec {
+        name: "submit",
+        summary: "Submits a LCS serialized, hex encoded signed transaction to mempool",
+        params: &[required("data", "string")],
+        result: "null",
+        result_schema: "null",
+    },
+    MethodSpec {
+        name: "get_metadata",
+        summary: "Returns the blockchain metadata at the given version",
+        params: &[optional("version", "integer")],
+        result: "BlockMetadata",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_latest_version",
+        summary: "Returns the latest ledger version",
+        params: &[],
+        result: "u64",
+        result_schema: "integer",
+    },
+    MethodSpec {
+        name: "get_account",
+        summary: "Returns the state of the given account, at the given version if specified",
+        params: &[
+            required("account", "string"),
+            optional("version", "integer"),
+            optional("currencies", "array"),
+            optional("verify", "boolean"),
+        ],
+        result: "AccountView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "v2.get_account",
+        summary: "Returns the state of the given account, at the given version if specified. \
+                  Fails if the account exists without a role or freezing bit",
+        params: &[
+            required("account", "string"),
+            optional("version", "integer"),
+            optional("currencies", "array"),
+            optional("verify", "boolean"),
+        ],
+        result: "AccountView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_account_diff",
+        summary:
+            "Returns the resources of the given account changed between the two given versions",
+        params: &[
+            required("account", "string"),
+            required("from_version", "integer"),
+            required("to_version", "integer"),
+        ],
+        result: "AccountDiffView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_resource",
+        summary:
+            "Returns the LCS serialized resource of the given struct type held by the given account",
+        params: &[
+            required("account", "string"),
+            required("resource_type", "string"),
+        ],
+        result: "BytesView",
+        result_schema: "string",
+    },
+    MethodSpec {
+        name: "get_account_modules_with_deps",
+        summary: "Returns the modules of the given account, or the one with the given name, \
+                  followed by all the modules they depend on",
+        params: &[required("account", "string"), optional("module", "string")],
+        result: "Vec<ModuleView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "is_account_frozen",
+        summary: "Returns whether the given account is frozen",
+        params: &[required("account", "string")],
+        result: "bool",
+        result_schema: "boolean",
+    },
+    MethodSpec {
+        name: "get_account_role",
+        summary: "Returns the role of the given account",
+        params: &[required("account", "string")],
+        result: "AccountRoleView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "list_child_vasps",
+        summary: "Returns the child VASP accounts of the given parent VASP account",
+        params: &[required("account", "string")],
+        result: "Vec<String>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_treasury_compliance_info",
+        summary: "Returns the state of the treasury compliance account",
+        params: &[],
+        result: "TreasuryComplianceView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_account_creation_transaction",
+        summary: "Returns the transaction that created the given account, if found",
+        params: &[
+            required("account", "string"),
+            optional("include_events", "boolean"),
+        ],
+        result: "TransactionView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_transactions",
+        summary: "Returns the transactions in the given version range",
+        params: &[
+            required("start_version", "integer"),
+            required("limit", "integer"),
+            optional("include_events", "boolean"),
+            optional("status", "string"),
+        ],
+        result: "Vec<TransactionView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_transaction_infos",
+        summary: "Returns the transaction infos of the given version range, without transactions",
+        params: &[
+            required("start_version", "integer"),
+            required("limit", "integer"),
+        ],
+        result: "Vec<TransactionInfoView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_transactions_by_time",
+        summary:
+            "Returns the transactions committed in blocks with a timestamp in the given window",
+        params: &[
+            required("start_time", "integer"),
+            required("end_time", "integer"),
+            required("limit", "integer"),
+            optional("include_events", "boolean"),
+        ],
+        result: "Vec<TransactionView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_account_transaction",
+        summary: "Returns the transaction sent by the given account with the given sequence number",
+        params: &[
+            required("account", "string"),
+            required("sequence_number", "integer"),
+            optional("include_events", "boolean"),
+            optional("include_proof", "boolean"),
+        ],
+        result: "TransactionView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_mempool_transactions_for_account",
+        summary: "Returns the transactions of the given account pending in mempool",
+        params: &[required("account", "string"), optional("limit", "integer")],
+        result: "Vec<TransactionView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_events",
+        summary: "Returns the events of the given event stream",
+        params: &[
+            required("key", "string"),
+            required("start", "integer"),
+            required("limit", "integer"),
+        ],
+        result: "Vec<EventView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "v2.get_events",
+        summary: "Returns the events of the given event stream, from a sequence number or a \
+                  cursor, with the cursor to the following events",
+        params: &[
+            required("key", "string"),
+            required("start", "integer"),
+            required("limit", "integer"),
+        ],
+        result: "EventsPageView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_events_since_cursor",
+        summary: "Returns the events of the stream of the given cursor committed after it, with \
+                  the cursor to the following events",
+        params: &[required("cursor", "string"), required("limit", "integer")],
+        result: "EventsPageView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_events_by_type",
+        summary: "Returns the events of the given type emitted by any account in a version range",
+        params: &[
+            required("event_type", "string"),
+            required("start_version", "integer"),
+            required("end_version", "integer"),
+            required("limit", "integer"),
+        ],
+        result: "Vec<EventView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "batch_fetch",
+        summary: "Returns the results of the given resource, balance and event page reads, \
+                  all at the same version",
+        params: &[required("fetches", "array"), optional("version", "integer")],
+        result: "Vec<FetchResultView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_events_count",
+        summary: "Returns the number of events of the given payment or new block event stream",
+        params: &[required("key", "string")],
+        result: "u64",
+        result_schema: "integer",
+    },
+    MethodSpec {
+        name: "get_currencies",
+        summary: "Returns the currencies supported by the blockchain",
+        params: &[optional("include_inactive", "boolean")],
+        result: "Vec<CurrencyInfoView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "convert_amount",
+        summary: "Converts an amount between two currencies using their on-chain exchange rates",
+        params: &[
+            required("amount", "integer"),
+            required("from_currency", "string"),
+            required("to_currency", "string"),
+        ],
+        result: "u64",
+        result_schema: "integer",
+    },
+    MethodSpec {
+        name: "get_gas_estimate",
+        summary: "Returns gas unit price estimates based on recent transactions",
+        params: &[optional("window", "integer")],
+        result: "Vec<GasEstimateView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_vm_publishing_option",
+        summary: "Returns the VM publishing option at the given version",
+        params: &[optional("version", "integer")],
+        result: "VMPublishingOptionView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_state_proof",
+        summary: "Returns the proof of the latest state relative to the given version",
+        params: &[
+            required("version", "integer"),
+            optional("epoch_change_only", "boolean"),
+        ],
+        result: "StateProofView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_epoch_change_proof",
+        summary:
+            "Returns the epoch changes since the given trusted epoch and the latest ledger info",
+        params: &[required("known_epoch", "integer")],
+        result: "EpochChangeProofView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_ledger_info_at_version",
+        summary: "Returns the signed ledger info at a version, or the nearest one after it",
+        params: &[required("version", "integer")],
+        result: "LedgerInfoView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_account_state_with_proof",
+        summary: "Returns the state of the given account alongside its proof",
+        params: &[
+            required("account", "string"),
+            required("version", "integer"),
+            required("ledger_version", "integer"),
+            optional("raw", "boolean"),
+        ],
+        result: "AccountStateWithProofView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_account_state_with_proof_latest",
+        summary: "Returns the state of the given account alongside its proof at the latest version",
+        params: &[required("account", "string")],
+        result: "AccountStateWithProofView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_account_state_with_proofs",
+        summary: "Returns the state of the given account alongside its proof at each given version",
+        params: &[
+            required("account", "string"),
+            required("versions", "array"),
+            required("ledger_version", "integer"),
+        ],
+        result: "Vec<AccountStateWithProofResultView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_account_state_with_proof_by_version",
+        summary: "Returns the state of the given account at a version alongside its sparse merkle \
+                  proof",
+        params: &[
+            required("account", "string"),
+            optional("version", "integer"),
+        ],
+        result: "AccountStateWithSparseProofView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_network_status",
+        summary: "Returns the number of connected peers",
+        params: &[],
+        result: "u64",
+        result_schema: "integer",
+    },
+    MethodSpec {
+        name: "get_network_status_by_network",
+        summary: "Returns the number of connected peers, in total and per network",
+        params: &[],
+        result: "NetworkStatusView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_peers",
+        summary: "Returns the peers this node is connected to",
+        params: &[],
+        result: "Vec<PeerView>",
+        result_schema: "array",
+    },
+    MethodSpec {
+        name: "get_server_version",
+        summary: "Returns the version, git commit and build time of the server",
+        params: &[],
+        result: "ServerVersionView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_capabilities",
+        summary: "Returns the features this server supports and their limits",
+        params: &[],
+        result: "CapabilitiesView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "get_node_config",
+        summary: "Returns the settings of this node affecting how its API behaves",
+        params: &[],
+        result: "NodeConfigView",
+        result_schema: "object",
+    },
+    MethodSpec {
+        name: "rpc.discover",
+        summary: "Returns the OpenRPC description of this API",
+        params: &[],
+        result: "OpenRPC document",
+        result_schema: "object",
+    },
+];
+
+/// Returns the OpenRPC document describing all JSON RPC methods
+pub(crate) fn openrpc_document() -> Value {
+    let methods: Vec<_> = METHODS
+        .iter()
+        .map(|method| {
+            let params: Vec<_> = method
+                .params
+                .iter()
+                .map(|param| {
+                    json!({
+                        "name": param.name,
+                        "required": param.required,
+                        "schema": { "type": param.schema_type },
+                    })
+                })
+                .collect();
+            json!({
+                "name": method.name,
+                "summary": method.summary,
+                "deprecated": DEPRECATED_METHODS.iter().any(|(name, _)| *name == method.name),
+                "paramStructure": "by-position",
+                "params": params,
+                "result": {
+                    "name": method.result,
+                    "schema": { "type": method.result_schema },
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Libra JSON RPC",
+            "version": API_VERSION,
+        },
+        "methods": methods,
+    })
+}
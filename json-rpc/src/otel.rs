@@ -0,0 +1,212 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export of a span per JSON RPC call to an OpenTelemetry collector over OTLP
+//!
+//! Spans are only built by nodes compiled with the `otlp` feature and configured with an
+//! `otlp_endpoint`: other nodes neither parse trace contexts nor start spans. The trace context of
+//! a request is taken from its W3C `traceparent` header, so the spans of its calls join the trace
+//! of the client. The trace id is also the request id of requests without one, under which
+//! storage traces the reads serving them.
+
+use libra_config::config::RpcConfig;
+use libra_logger::prelude::*;
+use std::future::Future;
+#[cfg(feature = "otlp")]
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
+    task::{self, Poll},
+};
+
+#[cfg(feature = "otlp")]
+use opentelemetry::{
+    global::{self, BoxedSpan},
+    propagation::TextMapPropagator,
+    sdk::propagation::TraceContextPropagator,
+    trace::{Span, Tracer},
+    Context, KeyValue,
+};
+
+/// Header through which clients propagate their trace context, in the W3C Trace Context format
+pub(crate) const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Name of the tracer the spans of calls are started by
+#[cfg(feature = "otlp")]
+const TRACER_NAME: &str = "libra-json-rpc";
+
+/// Whether spans are exported, set once the exporter is installed
+#[cfg(feature = "otlp")]
+static EXPORTING: AtomicBool = AtomicBool::new(false);
+
+/// Installs the exporter of spans to the configured collector, if any. Must run within the
+/// runtime serving requests, which the exporter sends spans from
+pub(crate) fn init(config: &RpcConfig) {
+    let endpoint = match &config.otlp_endpoint {
+        Some(endpoint) => endpoint,
+        None => return,
+    };
+    #[cfg(feature = "otlp")]
+    match install(endpoint) {
+        Ok(()) => {
+            EXPORTING.store(true, Ordering::Relaxed);
+            info!("[rpc] exporting request spans to {}", endpoint);
+        }
+        Err(err) => error!(
+            "[rpc] failed to install the OTLP exporter, request spans aren't exported: {}",
+            err
+        ),
+    }
+    #[cfg(not(feature = "otlp"))]
+    warn!(
+        "[rpc] otlp_endpoint {} ignored, the node isn't built with the otlp feature",
+        endpoint
+    );
+}
+
+/// Installs the OTLP exporter as the global tracer provider
+#[cfg(feature = "otlp")]
+fn install(endpoint: &str) -> anyhow::Result<()> {
+    let (_tracer, uninstall) = opentelemetry_otlp::new_pipeline()
+        .with_endpoint(endpoint)
+        .install()
+        .map_err(|err| anyhow::format_err!("{}", err))?;
+    // spans are exported for as long as the node runs
+    std::mem::forget(uninstall);
+    Ok(())
+}
+
+#[cfg(feature = "otlp")]
+fn is_exporting() -> bool {
+    EXPORTING.load(Ordering::Relaxed)
+}
+
+#[cfg(not(feature = "otlp"))]
+fn is_exporting() -> bool {
+    false
+}
+
+/// Returns the trace id of the given `traceparent` header value, when spans are exported and the
+/// value is valid, to serve as the id of the request
+pub(crate) fn request_id(traceparent: Option<&str>) -> Option<String> {
+    traceparent
+        .filter(|_| is_exporting())
+        .and_then(trace_id)
+        .map(str::to_string)
+}
+
+/// Returns the trace id of the given `traceparent` header value, if valid:
+/// `version-trace_id-parent_id-flags`, in lowercase hex, with a non zero trace id and parent id.
+/// Versions after `00` may append fields
+pub(crate) fn trace_id(traceparent: &str) -> Option<&str> {
+    let is_hex = |field: &str, len: usize| {
+        field.len() == len
+            && field
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    let is_zero = |field: &str| field.bytes().all(|b| b == b'0');
+    let mut fields = traceparent.split('-');
+    let version = fields.next()?;
+    let trace_id = fields.next()?;
+    let parent_id = fields.next()?;
+    let flags = fields.next()?;
+    let valid = is_hex(version, 2)
+        && version != "ff"
+        && is_hex(trace_id, 32)
+        && !is_zero(trace_id)
+        && is_hex(parent_id, 16)
+        && !is_zero(parent_id)
+        && is_hex(flags, 2)
+        && (version != "00" || fields.next().is_none());
+    if valid {
+        Some(trace_id)
+    } else {
+        None
+    }
+}
+
+/// Runs the given future with the trace context of the given `traceparent` header value as the
+/// current context, so the spans started by the future join the trace of the client
+#[cfg(feature = "otlp")]
+pub(crate) fn with_trace_context<F: Future>(
+    traceparent: Option<&str>,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    let context = traceparent
+        .filter(|_| is_exporting())
+        .filter(|traceparent| trace_id(traceparent).is_some())
+        .map(|traceparent| {
+            let mut carrier = HashMap::new();
+            carrier.insert(TRACEPARENT_HEADER.to_string(), traceparent.to_string());
+            TraceContextPropagator::new().extract(&carrier)
+        });
+    WithTraceContext {
+        context,
+        future: Box::pin(future),
+    }
+}
+
+#[cfg(not(feature = "otlp"))]
+pub(crate) fn with_trace_context<F: Future>(
+    _traceparent: Option<&str>,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    future
+}
+
+/// Future attaching its trace context, if any, whenever it is polled
+#[cfg(feature = "otlp")]
+struct WithTraceContext<F> {
+    context: Option<Context>,
+    future: Pin<Box<F>>,
+}
+
+#[cfg(feature = "otlp")]
+impl<F: Future> Future for WithTraceContext<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        let _guard = self.context.clone().map(Context::attach);
+        self.future.as_mut().poll(cx)
+    }
+}
+
+/// Span of a JSON RPC call, child of the current trace context, whose duration is the latency
+/// of the call. Exported when ended
+pub(crate) struct CallSpan {
+    #[cfg(feature = "otlp")]
+    span: Option<BoxedSpan>,
+}
+
+#[cfg(feature = "otlp")]
+impl CallSpan {
+    /// Starts the span of a call to the given method, made by the request of the given id
+    pub(crate) fn start(method: &str, request_id: &str) -> Self {
+        if !is_exporting() {
+            return Self { span: None };
+        }
+        let span = global::tracer(TRACER_NAME).start("json-rpc::call");
+        span.set_attribute(KeyValue::new("rpc.method", method.to_string()));
+        span.set_attribute(KeyValue::new("libra.request_id", request_id.to_string()));
+        Self { span: Some(span) }
+    }
+
+    /// Ends the span with the result of the call, the label it is counted under
+    pub(crate) fn end(self, result: &str) {
+        if let Some(span) = self.span {
+            span.set_attribute(KeyValue::new("rpc.result", result.to_string()));
+            span.end();
+        }
+    }
+}
+
+#[cfg(not(feature = "otlp"))]
+impl CallSpan {
+    pub(crate) fn start(_method: &str, _request_id: &str) -> Self {
+        Self {}
+    }
+
+    pub(crate) fn end(self, _result: &str) {}
+}
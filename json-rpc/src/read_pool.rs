@@ -0,0 +1,97 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dedicated thread pool storage reads run on
+//!
+//! `DbReader` calls block the calling thread on disk. Handlers of methods reading storage are run
+//! to completion on the threads of this pool rather than on the threads of the JSON RPC runtime,
+//! so read concurrency can be matched to the storage backend independently of the rest of the
+//! node. Reads wait for a thread in a bounded queue: once it is full, further reads are rejected
+//! as overloaded.
+use crate::{counters, errors::JsonRpcError};
+use futures::{channel::oneshot, executor::block_on, Future};
+use libra_logger::prelude::*;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Arc, Mutex,
+    },
+    thread,
+};
+use tokio::runtime::Handle;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Fixed size pool of threads running storage reads, fed through a bounded queue
+pub(crate) struct ReadPool {
+    sender: Mutex<SyncSender<Job>>,
+}
+
+impl ReadPool {
+    pub fn new(num_threads: usize, queue_size: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..num_threads {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("rpc-read-{}", index))
+                .spawn(move || run_worker(receiver))
+                .expect("[rpc] failed to spawn read pool thread");
+        }
+        Self {
+            sender: Mutex::new(sender),
+        }
+    }
+
+    /// Runs the given future to completion on a thread of the pool, within the context of the
+    /// calling runtime so it can still use its timers and IO.
+    /// Fails with an overloaded error if the queue is full
+    pub async fn run<F>(&self, future: F) -> Result<F::Output, JsonRpcError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let handle = Handle::current();
+        let (result_sender, result_receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            // the receiver is gone if the request was dropped in the meantime
+            let _ = result_sender.send(handle.enter(|| block_on(future)));
+        });
+
+        counters::READ_POOL_QUEUE_DEPTH.inc();
+        let sent = self
+            .sender
+            .lock()
+            .expect("failed to lock read pool sender")
+            .try_send(job);
+        if sent.is_err() {
+            counters::READ_POOL_QUEUE_DEPTH.dec();
+            return Err(JsonRpcError::server_overloaded());
+        }
+        result_receiver
+            .await
+            .map_err(|_| JsonRpcError::internal_error("storage read panicked".to_string()))
+    }
+}
+
+/// Runs the queued jobs until the pool is dropped
+fn run_worker(receiver: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        let job = match receiver
+            .lock()
+            .expect("failed to lock read pool receiver")
+            .recv()
+        {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        counters::READ_POOL_QUEUE_DEPTH.dec();
+        counters::READ_POOL_BUSY_THREADS.inc();
+        // a panicking read fails its own request only, the thread keeps serving the queue
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            error!("[rpc] storage read panicked");
+        }
+        counters::READ_POOL_BUSY_THREADS.dec();
+    }
+}
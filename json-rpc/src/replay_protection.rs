@@ -0,0 +1,74 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Short-term protection against transactions reusing a sequence number already in flight
+//!
+//! A client recovering from a crash may sign a fresh transaction for a sequence number it already
+//! used. Once a transaction is accepted by mempool, any other transaction from the same sender
+//! with the same sequence number is rejected by `submit` until the window elapses, before it
+//! reaches mempool. Resubmitting the very same transaction is still let through.
+//!
+//! Note that this also rejects gas price bumps of a pending transaction within the window.
+use crate::errors::JsonRpcError;
+use libra_crypto::{hash::CryptoHash, HashValue};
+use libra_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, Transaction},
+};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Tracks the (sender, sequence number) pairs of recently accepted transactions
+pub(crate) struct ReplayProtection {
+    window: Duration,
+    accepted: Mutex<HashMap<(AccountAddress, u64), (HashValue, Instant)>>,
+}
+
+impl ReplayProtection {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            accepted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fails if another transaction with the same sender and sequence number was accepted within
+    /// the window
+    pub fn check(&self, transaction: &SignedTransaction) -> Result<(), JsonRpcError> {
+        let accepted = self
+            .accepted
+            .lock()
+            .expect("failed to lock accepted transactions");
+        match accepted.get(&(transaction.sender(), transaction.sequence_number())) {
+            Some((hash, accepted_at))
+                if accepted_at.elapsed() < self.window && *hash != hash_of(transaction) =>
+            {
+                Err(JsonRpcError::sequence_in_flight(
+                    transaction.sender(),
+                    transaction.sequence_number(),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn record(&self, transaction: &SignedTransaction) {
+        let mut accepted = self
+            .accepted
+            .lock()
+            .expect("failed to lock accepted transactions");
+        let window = self.window;
+        accepted.retain(|_, (_, accepted_at)| accepted_at.elapsed() < window);
+        accepted.insert(
+            (transaction.sender(), transaction.sequence_number()),
+            (hash_of(transaction), Instant::now()),
+        );
+    }
+}
+
+fn hash_of(transaction: &SignedTransaction) -> HashValue {
+    Transaction::UserTransaction(transaction.clone()).hash()
+}
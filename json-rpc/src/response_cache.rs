@@ -0,0 +1,88 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Short-lived cache of the results of read methods
+//!
+//! Methods reading slowly changing data, such as `get_currencies`, can be configured with a time to
+//! live. Their successful results are then reused for identical calls within it, sparing storage
+//! the repeated reads of bursts of identical calls. Results are keyed by method, params and ledger
+//! version, so a client is never served a result read at a version other than the one of its
+//! response. Mempool methods are never cached, as their results don't depend on the ledger
+//! version.
+use crate::{
+    counters,
+    methods::{unversioned_name, MEMPOOL_METHODS},
+};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Maximum number of results cached at once, further results aren't cached until some expire
+const MAX_CACHED_RESULTS: usize = 10_000;
+
+/// Method name, ledger version and serialized params of a call
+pub(crate) type CacheKey = (String, u64, String);
+
+/// Results of recent calls to the cacheable methods, with the time they were cached at
+pub(crate) struct ResponseCache {
+    ttls: HashMap<String, Duration>,
+    results: Mutex<HashMap<CacheKey, (Value, Instant)>>,
+}
+
+impl ResponseCache {
+    /// Caches the methods with the given time to live in milliseconds, by unversioned name
+    pub fn new(ttls: &BTreeMap<String, u64>) -> Self {
+        Self {
+            ttls: ttls
+                .iter()
+                .filter(|(method, ttl)| **ttl > 0 && !MEMPOOL_METHODS.contains(&method.as_str()))
+                .map(|(method, ttl)| (method.clone(), Duration::from_millis(*ttl)))
+                .collect(),
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the key of the given call, None if the method isn't cacheable
+    pub fn key(&self, method: &str, version: u64, params: &[Value]) -> Option<CacheKey> {
+        if !self.ttls.contains_key(unversioned_name(method)) {
+            return None;
+        }
+        let params = serde_json::to_string(params).ok()?;
+        Some((method.to_string(), version, params))
+    }
+
+    /// Returns the result cached for the given call, if it hasn't expired
+    pub fn get(&self, key: &CacheKey) -> Option<Value> {
+        let results = self.results.lock().expect("failed to lock cached results");
+        let result = results
+            .get(key)
+            .filter(|(_, cached_at)| cached_at.elapsed() < self.ttl(&key.0))
+            .map(|(result, _)| result.clone());
+        let label = if result.is_some() { "hit" } else { "miss" };
+        counters::RESPONSE_CACHE
+            .with_label_values(&[key.0.as_str(), label])
+            .inc();
+        result
+    }
+
+    pub fn insert(&self, key: CacheKey, result: Value) {
+        let mut results = self.results.lock().expect("failed to lock cached results");
+        if results.len() >= MAX_CACHED_RESULTS {
+            results.retain(|(method, _, _), (_, cached_at)| cached_at.elapsed() < self.ttl(method));
+            if results.len() >= MAX_CACHED_RESULTS {
+                return;
+            }
+        }
+        results.insert(key, (result, Instant::now()));
+    }
+
+    fn ttl(&self, method: &str) -> Duration {
+        self.ttls
+            .get(unversioned_name(method))
+            .copied()
+            .unwrap_or_default()
+    }
+}
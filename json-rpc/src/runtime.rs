@@ -2,21 +2,55 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit::AuditEntry,
+    circuit_breaker::{CircuitBreaker, CircuitBreakerDb},
+    connections::LimitedIncoming,
     counters,
     errors::JsonRpcError,
-    methods::{build_registry, JsonRpcRequest, JsonRpcService, RpcRegistry},
+    failover::FailoverDb,
+    methods::{
+        build_registry, parse_u64, resolve_method, transaction_views, unversioned_name,
+        JsonRpcRequest, JsonRpcService, RpcRegistry, MEMPOOL_METHODS,
+    },
+    otel,
+    serialization::{json_reply, json_stream_reply, stringify_u64s, stringify_unsigned},
+    tls::{tls_acceptor, tls_incoming, CertificateResolver},
+};
+use futures::{
+    future::{self, join_all},
+    stream::{self, StreamExt},
+};
+use libra_config::{
+    config::{FailoverPolicy, MethodLimits, NodeConfig, RoleType, RpcConfig},
+    network_id::NetworkId,
 };
-use futures::future::join_all;
-use libra_config::config::{NodeConfig, RoleType};
 use libra_json_rpc_types::views::{
-    JSONRPC_LIBRA_CHAIN_ID, JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS, JSONRPC_LIBRA_LEDGER_VERSION,
+    JSONRPC_LIBRA_CHAIN_ID, JSONRPC_LIBRA_DEPRECATION, JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS,
+    JSONRPC_LIBRA_LEDGER_VERSION, JSONRPC_LIBRA_REQUEST_ID,
 };
+use libra_logger::prelude::*;
 use libra_mempool::MempoolClientSender;
+use libra_trace::{
+    prelude::*,
+    request_id::{current_request_id, with_request_id},
+};
 use libra_types::{chain_id::ChainId, ledger_info::LedgerInfoWithSignatures};
 use serde_json::{map::Map, Value};
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use storage_interface::DbReader;
-use tokio::runtime::{Builder, Runtime};
+use tokio::{
+    net::TcpListener,
+    runtime::{Builder, Runtime},
+};
 use warp::{
     reject::{self, Reject},
     Filter,
@@ -28,16 +62,41 @@ const LABEL_INVALID_FORMAT: &str = "invalid_format";
 const LABEL_INVALID_METHOD: &str = "invalid_method";
 const LABEL_INVALID_PARAMS: &str = "invalid_params";
 const LABEL_MISSING_METHOD: &str = "method_not_found";
+const LABEL_NODE_TOO_STALE: &str = "node_too_stale";
+const LABEL_OVERLOADED: &str = "overloaded";
 const LABEL_SUCCESS: &str = "success";
+const LABEL_UNAUTHORIZED: &str = "unauthorized";
+
+/// Header through which clients present their API key
+const API_KEY_HEADER: &str = "x-api-key";
+/// Header through which clients can provide the id correlating the traces of their request
+const REQUEST_ID_HEADER: &str = "x-request-id";
+/// Header through which clients can ask for the result of a `get_transactions` request to be
+/// streamed, with the value `true`
+const STREAM_HEADER: &str = "x-stream-response";
+/// Header through which clients choose how unsigned integers of responses are serialized: as
+/// numbers with the value `number`, or as strings with the value `string`
+const U64_FORMAT_HEADER: &str = "x-u64-format";
+/// Header through which clients choose how responses are laid out: indented with the value
+/// `pretty`, or without whitespace with the value `compact`, the default
+const JSON_FORMAT_HEADER: &str = "x-json-format";
+/// Longest request id accepted from clients, longer ones are replaced by a generated id
+const MAX_REQUEST_ID_LEN: usize = 64;
+/// Maximum number of characters of the params logged for a rejected oversized response
+const MAX_LOGGED_PARAMS_LEN: usize = 256;
 
 /// Creates HTTP server (warp-based) that serves JSON RPC requests
 /// Returns handle to corresponding Tokio runtime
+#[allow(clippy::too_many_arguments)]
 pub fn bootstrap(
-    address: SocketAddr,
+    config: &RpcConfig,
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     role: RoleType,
     chain_id: ChainId,
+    prune_window: Option<u64>,
+    pruned_state_db: Option<Arc<dyn DbReader>>,
+    public_full_node: bool,
 ) -> Runtime {
     let runtime = Builder::new()
         .thread_name("rpc-")
@@ -47,14 +106,46 @@ pub fn bootstrap(
         .expect("[rpc] failed to create runtime");
 
     let registry = Arc::new(build_registry());
-    let service = JsonRpcService::new(libra_db, mp_sender, role, chain_id);
+    let libra_db = Arc::new(CircuitBreakerDb::new(
+        libra_db,
+        CircuitBreaker::new(
+            config.storage_failure_threshold,
+            Duration::from_millis(config.storage_cooldown_ms),
+        ),
+        prune_window,
+    ));
+    let mut service =
+        JsonRpcService::new(libra_db, mp_sender, role, chain_id, prune_window, config)
+            .with_public_full_node(public_full_node);
+    if let Some(pruned_state_db) = pruned_state_db {
+        service = service.with_pruned_state_db(pruned_state_db);
+    }
+    if config.warmup {
+        // requests are served regardless, only slower at first
+        if let Err(err) = runtime.block_on(service.warmup()) {
+            warn!("[rpc] failed to warm up: {}", err);
+        }
+    }
+    runtime.enter(|| otel::init(config));
+    let inflight = Arc::new(InflightRequests::new(config.max_inflight_requests));
+    let auth = Arc::new(ApiKeyAuth::new(config, &registry));
+    let batch_cost = Arc::new(BatchCost::new(config, &registry));
 
     let base_route = warp::any()
         .and(warp::post())
         .and(warp::header::exact("content-type", "application/json"))
         .and(warp::body::json())
+        .and(warp::header::optional::<String>(API_KEY_HEADER))
+        .and(warp::header::optional::<String>(REQUEST_ID_HEADER))
+        .and(warp::header::optional::<String>(STREAM_HEADER))
+        .and(warp::header::optional::<String>(U64_FORMAT_HEADER))
+        .and(warp::header::optional::<String>(JSON_FORMAT_HEADER))
+        .and(warp::header::optional::<String>(otel::TRACEPARENT_HEADER))
         .and(warp::any().map(move || service.clone()))
         .and(warp::any().map(move || Arc::clone(&registry)))
+        .and(warp::any().map(move || Arc::clone(&inflight)))
+        .and(warp::any().map(move || Arc::clone(&auth)))
+        .and(warp::any().map(move || Arc::clone(&batch_cost)))
         .and_then(rpc_endpoint);
 
     // For now we still allow user to use "/", but user should start to move to "/v1" soon
@@ -73,88 +164,281 @@ pub fn bootstrap(
     //
     // Note: we need to enter the runtime context first to actually bind, since
     //       tokio TcpListener can only be bound inside a tokio context.
-    let server = runtime.enter(move || warp::serve(full_route).bind(address));
-    runtime.handle().spawn(server);
+    let address = config.address;
+    match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert_path), Some(key_path)) => {
+            let resolver = Arc::new(
+                CertificateResolver::new(cert_path, key_path)
+                    .expect("[rpc] failed to load TLS certificate"),
+            );
+            let acceptor = tls_acceptor(Arc::clone(&resolver));
+            let listener =
+                std::net::TcpListener::bind(address).expect("[rpc] failed to bind address");
+            let (max_connections, idle_timeout) = connection_limits(config);
+            let server = runtime.enter(move || {
+                let listener =
+                    TcpListener::from_std(listener).expect("[rpc] failed to register listener");
+                let incoming = LimitedIncoming::new(listener, max_connections, idle_timeout);
+                warp::serve(full_route).serve_incoming(tls_incoming(incoming, acceptor))
+            });
+            runtime.handle().spawn(server);
+            if config.tls_reload_interval_ms > 0 {
+                runtime.handle().spawn(reload_tls_on_change(
+                    resolver,
+                    cert_path.clone(),
+                    key_path.clone(),
+                    Duration::from_millis(config.tls_reload_interval_ms),
+                ));
+            }
+        }
+        (None, None) => {
+            let listener =
+                std::net::TcpListener::bind(address).expect("[rpc] failed to bind address");
+            let (max_connections, idle_timeout) = connection_limits(config);
+            let server = runtime.enter(move || {
+                let listener =
+                    TcpListener::from_std(listener).expect("[rpc] failed to register listener");
+                warp::serve(full_route).serve_incoming(LimitedIncoming::new(
+                    listener,
+                    max_connections,
+                    idle_timeout,
+                ))
+            });
+            runtime.handle().spawn(server);
+        }
+        _ => panic!("[rpc] tls_cert_path and tls_key_path must be set together"),
+    }
     runtime
 }
 
+/// Returns the maximum number of connections and the idle timeout of connections, as configured
+fn connection_limits(config: &RpcConfig) -> (u64, Option<Duration>) {
+    let idle_timeout = Some(config.connection_idle_timeout_ms)
+        .filter(|timeout| *timeout > 0)
+        .map(Duration::from_millis);
+    (config.max_connections, idle_timeout)
+}
+
+/// Reloads the TLS certificate and key from their files whenever they change, swapping them in
+/// place for new handshakes without restarting the server.
+/// Files are checked every `interval`, and must be left unchanged for a whole interval before
+/// being reloaded so that a partially written certificate isn't picked up. Files failing to load
+/// are logged, and the previous certificate stays in use
+async fn reload_tls_on_change(
+    resolver: Arc<CertificateResolver>,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    interval: Duration,
+) {
+    let files_modified = || {
+        let modified = |path: &PathBuf| fs::metadata(path).and_then(|m| m.modified()).ok();
+        (modified(&cert_path), modified(&key_path))
+    };
+    let mut loaded = files_modified();
+    let mut last_seen = loaded;
+    let mut interval = tokio::time::interval(interval);
+    loop {
+        interval.tick().await;
+        let current = files_modified();
+        if current == loaded || current != last_seen {
+            last_seen = current;
+            continue;
+        }
+
+        match resolver.reload(&cert_path, &key_path) {
+            Ok(()) => info!("[rpc] TLS certificate changed, reloaded it"),
+            Err(err) => error!(
+                "[rpc] failed to reload TLS certificate, keeping the previous one: {}",
+                err
+            ),
+        }
+        loaded = current;
+    }
+}
+
 /// Creates JSON RPC endpoint by given node config
 pub fn bootstrap_from_config(
     config: &NodeConfig,
     libra_db: Arc<dyn DbReader>,
+    secondary_db: Option<Arc<dyn DbReader>>,
     mp_sender: MempoolClientSender,
 ) -> Runtime {
+    // reads of pruned state are let through to the read replica when it serves them
+    let pruned_state_db = secondary_db
+        .clone()
+        .filter(|_| config.rpc.failover_policy == FailoverPolicy::OnPruned);
+    let libra_db: Arc<dyn DbReader> = match secondary_db {
+        Some(secondary_db) => Arc::new(FailoverDb::new(
+            libra_db,
+            secondary_db,
+            config.rpc.failover_policy,
+            config.storage.prune_window,
+            config.rpc.max_secondary_lag,
+            config.rpc.log_failover,
+        )),
+        None => libra_db,
+    };
     bootstrap(
-        config.rpc.address,
+        &config.rpc,
         libra_db,
         mp_sender,
         config.base.role,
         config.base.chain_id,
+        config.storage.prune_window,
+        pruned_state_db,
+        is_public_full_node(config),
     )
 }
 
+/// Returns whether the node is a public full node: a full node without a VFN network, through
+/// which it would serve as the full node of a validator
+fn is_public_full_node(config: &NodeConfig) -> bool {
+    let vfn_network = NetworkId::vfn_network();
+    !config.base.role.is_validator()
+        && !config
+            .full_node_networks
+            .iter()
+            .any(|network| network.network_id == vfn_network)
+}
+
 /// JSON RPC entry point
 /// Handles all incoming rpc requests
 /// Performs routing based on methods defined in `registry`
+#[allow(clippy::too_many_arguments)]
 async fn rpc_endpoint(
     data: Value,
+    api_key: Option<String>,
+    request_id: Option<String>,
+    stream: Option<String>,
+    u64_format: Option<String>,
+    json_format: Option<String>,
+    traceparent: Option<String>,
     service: JsonRpcService,
     registry: Arc<RpcRegistry>,
+    inflight: Arc<InflightRequests>,
+    auth: Arc<ApiKeyAuth>,
+    batch_cost: Arc<BatchCost>,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     // take snapshot of latest version of DB to be used across all requests, especially for batched requests
     let ledger_info = service
         .get_latest_ledger_info()
         .map_err(|_| reject::custom(DatabaseError))?;
 
-    let resp = Ok(if let Value::Array(requests) = data {
-        // batch API call
-        let futures = requests.into_iter().map(|req| {
-            rpc_request_handler(
-                req,
-                service.clone(),
-                Arc::clone(&registry),
-                ledger_info.clone(),
+    let request_id = request_id
+        .filter(|id| is_valid_request_id(id))
+        .or_else(|| otel::request_id(traceparent.as_deref()))
+        .unwrap_or_else(generate_request_id);
+    // formats other than `number` and `string` fall back to the node's default
+    let u64_as_string = match u64_format.as_deref() {
+        Some("number") => false,
+        Some("string") => true,
+        _ => service.u64_as_string_by_default(),
+    };
+    let pretty = json_format.as_deref() == Some("pretty");
+    let reply = move |mut response: Value| {
+        if u64_as_string {
+            stringify_u64s(&mut response);
+        }
+        json_reply(&response, pretty)
+    };
+
+    let request = with_request_id(request_id, async move {
+        if let Value::Array(requests) = data {
+            // batch API call
+            // duplicate ids would make responses ambiguous, so the whole batch is rejected
+            if let Some(id) = find_duplicate_request_id(&requests) {
+                let trace_id = current_request_id().unwrap_or_default();
+                let mut response = new_response(service.chain_id(), &ledger_info, &trace_id);
+                set_response_error(
+                    &mut response,
+                    JsonRpcError::duplicate_request_id(id),
+                    Some(LABEL_INVALID_FORMAT),
+                );
+                return reply(Value::Object(response));
+            }
+            if let Err(err) = batch_cost.check(&requests) {
+                let trace_id = current_request_id().unwrap_or_default();
+                let mut response = new_response(service.chain_id(), &ledger_info, &trace_id);
+                set_response_error(&mut response, err, None);
+                return reply(Value::Object(response));
+            }
+            let futures = requests.into_iter().map(|req| {
+                rpc_request_handler(
+                    req,
+                    service.clone(),
+                    Arc::clone(&registry),
+                    Arc::clone(&inflight),
+                    Arc::clone(&auth),
+                    api_key.clone(),
+                    ledger_info.clone(),
+                )
+            });
+            let responses = join_all(futures).await;
+            reply(Value::Array(responses))
+        } else {
+            // single API call
+            // streamed responses are compact, so pretty responses are buffered instead
+            if stream.as_deref() == Some("true") && !pretty {
+                if let Some(streamed) = stream_transactions(
+                    &data,
+                    service.clone(),
+                    &registry,
+                    &inflight,
+                    &auth,
+                    ledger_info.clone(),
+                    u64_as_string,
+                )
+                .await
+                {
+                    return streamed;
+                }
+            }
+            let resp = rpc_request_handler(
+                data,
+                service,
+                registry,
+                inflight,
+                auth,
+                api_key,
+                ledger_info,
             )
-        });
-        let responses = join_all(futures).await;
-        warp::reply::json(&Value::Array(responses))
-    } else {
-        // single API call
-        let resp = rpc_request_handler(data, service, registry, ledger_info).await;
-        warp::reply::json(&resp)
+            .await;
+            reply(resp)
+        }
     });
+    let resp = Ok(otel::with_trace_context(traceparent.as_deref(), request).await);
 
     Ok(Box::new(resp) as Box<dyn warp::Reply>)
 }
 
+fn is_valid_request_id(request_id: &str) -> bool {
+    !request_id.is_empty()
+        && request_id.len() <= MAX_REQUEST_ID_LEN
+        && request_id.chars().all(|c| c.is_ascii_graphic())
+}
+
+/// Generates an id unique to this process
+fn generate_request_id() -> String {
+    static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(0);
+    format!("{:x}", NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed))
+}
+
 /// Handler of single RPC request
 /// Performs validation and executes corresponding rpc handler
-async fn rpc_request_handler(
+pub(crate) async fn rpc_request_handler(
     req: Value,
     service: JsonRpcService,
     registry: Arc<RpcRegistry>,
+    inflight: Arc<InflightRequests>,
+    auth: Arc<ApiKeyAuth>,
+    api_key: Option<String>,
     ledger_info: LedgerInfoWithSignatures,
 ) -> Value {
     let request: Map<String, Value>;
-    let mut response = Map::new();
     let version = ledger_info.ledger_info().version();
-    let timestamp = ledger_info.ledger_info().timestamp_usecs();
-
-    // set defaults: protocol version to 2.0, request id to null
-    response.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
-    response.insert("id".to_string(), Value::Null);
-    response.insert(
-        JSONRPC_LIBRA_LEDGER_VERSION.to_string(),
-        Value::Number(version.into()),
-    );
-    response.insert(
-        JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS.to_string(),
-        Value::Number(timestamp.into()),
-    );
-    response.insert(
-        JSONRPC_LIBRA_CHAIN_ID.to_string(),
-        Value::Number(service.chain_id().id().into()),
-    );
+    let trace_id = current_request_id().unwrap_or_default();
+    let mut response = new_response(service.chain_id(), &ledger_info, &trace_id);
+    let audit_log = service.audit_log();
 
     match req {
         Value::Object(data) => {
@@ -187,12 +471,24 @@ async fn rpc_request_handler(
         return Value::Object(response);
     }
 
-    // parse parameters
+    // parse the ledger version reads must reflect at least
+    let min_ledger_version = match parse_min_ledger_version(&request) {
+        Ok(min_ledger_version) => min_ledger_version,
+        Err(err) => {
+            set_response_error(&mut response, err, Some(LABEL_INVALID_FORMAT));
+            return Value::Object(response);
+        }
+    };
+
+    // parse parameters, which may be omitted for methods without required params
     let params;
     match request.get("params") {
         Some(Value::Array(parameters)) => {
             params = parameters.to_vec();
         }
+        None => {
+            params = vec![];
+        }
         _ => {
             set_response_error(
                 &mut response,
@@ -207,30 +503,124 @@ async fn rpc_request_handler(
         ledger_info,
         params,
     };
+    // calls to deprecated methods are served, with a notice prompting clients to migrate
+    if let Some(Value::String(name)) = request.get("method") {
+        if let Some(deprecation) =
+            resolve_method(&registry, name).and_then(|method| method.deprecation.as_ref())
+        {
+            if let Ok(notice) = serde_json::to_value(deprecation) {
+                response.insert(JSONRPC_LIBRA_DEPRECATION.to_string(), notice);
+            }
+        }
+    }
+
+    // calls are checked against the limits of their method, which depend on the role of the node,
+    // and count against its concurrency cap until they are answered
+    let method_limits = match (request.get("method"), service.method_limiter()) {
+        (Some(Value::String(name)), Some(limiter)) => limiter.check(name, &request_params.params),
+        _ => Ok(None),
+    };
+
     // get rpc handler
+    let mut handled_submit = false;
     match request.get("method") {
-        Some(Value::String(name)) => match registry.get(name) {
-            Some(handler) => match handler(service, request_params).await {
-                Ok(result) => {
-                    response.insert("result".to_string(), result);
+        Some(Value::String(name)) => match resolve_method(&registry, name) {
+            Some(_) if !auth.is_authorized(name, api_key.as_deref()) => {
+                set_response_error(&mut response, JsonRpcError::unauthorized(), None);
+                counters::REQUESTS
+                    .with_label_values(&[name, LABEL_UNAUTHORIZED])
+                    .inc();
+            }
+            // submitting isn't a read, so doesn't require the node to be up to date
+            Some(_) if unversioned_name(name) != "submit" && min_ledger_version > version => {
+                set_response_error(
+                    &mut response,
+                    JsonRpcError::node_too_stale(version, min_ledger_version),
+                    None,
+                );
+                counters::REQUESTS
+                    .with_label_values(&[name, LABEL_NODE_TOO_STALE])
+                    .inc();
+            }
+            Some(_) if method_limits.is_err() => {
+                if let Err((err, label)) = method_limits {
+                    set_response_error(&mut response, err, None);
+                    counters::REQUESTS.with_label_values(&[name, label]).inc();
+                }
+            }
+            Some(method) => match inflight.try_acquire() {
+                None => {
+                    set_response_error(&mut response, JsonRpcError::server_overloaded(), None);
                     counters::REQUESTS
-                        .with_label_values(&[name, LABEL_SUCCESS])
+                        .with_label_values(&[name, LABEL_OVERLOADED])
                         .inc();
                 }
-                Err(err) => {
-                    // check for custom error
-                    if let Some(custom_error) = err.downcast_ref::<JsonRpcError>() {
-                        set_response_error(&mut response, custom_error.clone(), None);
-                    } else {
-                        set_response_error(
-                            &mut response,
-                            JsonRpcError::internal_error(err.to_string()),
-                            None,
-                        );
+                Some(_guard) => {
+                    trace_code_block!("json-rpc::request", {"request", trace_id});
+                    let span = otel::CallSpan::start(name, &trace_id);
+                    handled_submit = unversioned_name(name) == "submit";
+                    let read_pool = service
+                        .read_pool()
+                        .filter(|_| !MEMPOOL_METHODS.contains(&unversioned_name(name)));
+                    let response_cache = service.response_cache();
+                    let cache_key = response_cache
+                        .as_ref()
+                        .and_then(|cache| cache.key(name, version, &request_params.params));
+                    let cached = match (&response_cache, &cache_key) {
+                        (Some(cache), Some(key)) => cache.get(key),
+                        _ => None,
+                    };
+                    let result = match cached {
+                        Some(result) => Ok(result),
+                        None => {
+                            let result = match read_pool {
+                                Some(read_pool) => read_pool
+                                    .run(with_request_id(
+                                        trace_id.clone(),
+                                        (method.handler)(service, request_params),
+                                    ))
+                                    .await
+                                    .unwrap_or_else(|err| Err(err.into())),
+                                None => (method.handler)(service, request_params).await,
+                            };
+                            // only successful results are cached, so failures are retried
+                            if let (Some(cache), Some(key), Ok(result)) =
+                                (&response_cache, cache_key, &result)
+                            {
+                                cache.insert(key, result.clone());
+                            }
+                            result
+                        }
+                    };
+                    match result {
+                        Ok(result) => {
+                            response.insert("result".to_string(), result);
+                            counters::REQUESTS
+                                .with_label_values(&[name, LABEL_SUCCESS])
+                                .inc();
+                            span.end(LABEL_SUCCESS);
+                        }
+                        Err(err) => {
+                            let err = handler_error(err);
+                            if let Some(too_large) = err.as_response_too_large() {
+                                counters::OVERSIZED_RESPONSES
+                                    .with_label_values(&[name])
+                                    .inc();
+                                warn!(
+                                    "[rpc] {} response too large ({} > {} bytes), params: {}",
+                                    name,
+                                    too_large.size,
+                                    too_large.max_size,
+                                    params_summary(request.get("params"))
+                                );
+                            }
+                            set_response_error(&mut response, err, None);
+                            counters::REQUESTS
+                                .with_label_values(&[name, LABEL_FAIL])
+                                .inc();
+                            span.end(LABEL_FAIL);
+                        }
                     }
-                    counters::REQUESTS
-                        .with_label_values(&[name, LABEL_FAIL])
-                        .inc();
                 }
             },
             None => {
@@ -250,9 +640,193 @@ async fn rpc_request_handler(
         }
     }
 
+    // calls to restricted methods are audited, whether they were authorized or not, except for
+    // the submissions handled, which `submit` audits along with their transaction
+    if let (Some(audit_log), Some(Value::String(name))) = (audit_log, request.get("method")) {
+        if auth.is_restricted(name) && !handled_submit {
+            let result = response
+                .get("error")
+                .and_then(|error| error.get("message"))
+                .and_then(Value::as_str)
+                .unwrap_or("success");
+            audit_log.record(&AuditEntry {
+                method: name,
+                request_id: &trace_id,
+                transaction: None,
+                result: result.to_string(),
+            });
+        }
+    }
+
     Value::Object(response)
 }
 
+/// Serves a single `get_transactions` request with a streamed response: the envelope is sent
+/// first, then each transaction as soon as its view is built, so the result is never held in
+/// memory as a whole. Results past the maximum response size cut the response short, as the
+/// buffered path would have rejected them.
+/// Returns None if the request must be served with a buffered response instead: if it isn't a
+/// valid, unrestricted `get_transactions` request the node can serve right away, so the buffered
+/// path reports the error.
+/// A transaction failing to be read once streaming started cuts the response short
+async fn stream_transactions(
+    data: &Value,
+    service: JsonRpcService,
+    registry: &RpcRegistry,
+    inflight: &Arc<InflightRequests>,
+    auth: &ApiKeyAuth,
+    ledger_info: LedgerInfoWithSignatures,
+    u64_as_string: bool,
+) -> Option<warp::reply::Response> {
+    let request = data.as_object()?;
+    let name = request.get("method")?.as_str()?;
+    let method = resolve_method(registry, name)?;
+    if unversioned_name(name) != "get_transactions"
+        || method.deprecation.is_some()
+        || auth.is_restricted(name)
+    {
+        return None;
+    }
+    let id = parse_request_id(request).ok()?;
+    verify_protocol(request).ok()?;
+    if parse_min_ledger_version(request).ok()? > ledger_info.ledger_info().version() {
+        return None;
+    }
+    let params = request.get("params")?.as_array()?.to_vec();
+    // calls exceeding the limits of the method are left to the regular path to reject
+    let method_guard = match service.method_limiter() {
+        Some(limiter) => limiter.check(name, &params).ok()?,
+        None => None,
+    };
+    let guard = inflight.try_acquire()?;
+
+    let trace_id = current_request_id().unwrap_or_default();
+    let mut response = new_response(service.chain_id(), &ledger_info, &trace_id);
+    response.insert("id".to_string(), id);
+    let request = JsonRpcRequest {
+        ledger_info,
+        params,
+    };
+    let views = match service.read_pool() {
+        Some(read_pool) => read_pool
+            .run(async move { transaction_views(&service, &request) })
+            .await
+            .unwrap_or_else(|err| Err(err.into())),
+        None => transaction_views(&service, &request),
+    };
+    let views = match views {
+        Ok(views) => views,
+        Err(err) => {
+            set_response_error(&mut response, handler_error(err), None);
+            counters::REQUESTS
+                .with_label_values(&[name, LABEL_FAIL])
+                .inc();
+            let mut response = Value::Object(response);
+            if u64_as_string {
+                stringify_u64s(&mut response);
+            }
+            return Some(json_reply(&response, false));
+        }
+    };
+
+    // the envelope, without its closing brace, opens the result array
+    let mut response = Value::Object(response);
+    if u64_as_string {
+        stringify_u64s(&mut response);
+    }
+    let mut envelope = serde_json::to_vec(&response).ok()?;
+    envelope.pop();
+    envelope.extend_from_slice(b",\"result\":[");
+    // the result size includes its brackets, like the size checked on the buffered path
+    let max_size = service.max_response_size() as usize;
+    let mut result_size = 2;
+    let method_name = name.to_string();
+    let views = views
+        .enumerate()
+        .map(move |(index, view)| -> anyhow::Result<Vec<u8>> {
+            let mut chunk = if index > 0 { vec![b','] } else { vec![] };
+            if u64_as_string {
+                let mut view = serde_json::to_value(&view?)?;
+                stringify_unsigned(&mut view);
+                serde_json::to_writer(&mut chunk, &view)?;
+            } else {
+                serde_json::to_writer(&mut chunk, &view?)?;
+            }
+            result_size += chunk.len();
+            if max_size > 0 && result_size > max_size {
+                counters::OVERSIZED_RESPONSES
+                    .with_label_values(&[&method_name])
+                    .inc();
+                warn!(
+                    "[rpc] {} streamed response too large (> {} bytes), cut short",
+                    method_name, max_size
+                );
+                return Err(JsonRpcError::response_too_large(result_size, max_size).into());
+            }
+            Ok(chunk)
+        });
+    let chunks = stream::once(future::ready(Ok(envelope)))
+        .chain(stream::iter(views))
+        .chain(stream::once(future::ready(Ok(b"]}".to_vec()))))
+        // the request counts as in flight until its response is fully sent
+        .inspect(move |_| {
+            let _ = (&guard, &method_guard);
+        });
+    counters::REQUESTS
+        .with_label_values(&[name, LABEL_SUCCESS])
+        .inc();
+    Some(json_stream_reply(chunks))
+}
+
+/// Returns the JSON RPC error reported for the given error of a handler
+fn handler_error(err: anyhow::Error) -> JsonRpcError {
+    // check for custom error
+    match err.downcast::<JsonRpcError>() {
+        Ok(custom_error) => custom_error,
+        Err(err) => JsonRpcError::internal_error(err.to_string()),
+    }
+}
+
+/// Returns the given params serialized, cut to their first `MAX_LOGGED_PARAMS_LEN` characters
+fn params_summary(params: Option<&Value>) -> String {
+    let params = params.map(Value::to_string).unwrap_or_default();
+    if params.chars().count() <= MAX_LOGGED_PARAMS_LEN {
+        return params;
+    }
+    let mut summary: String = params.chars().take(MAX_LOGGED_PARAMS_LEN).collect();
+    summary.push_str("...");
+    summary
+}
+
+/// Returns a response with the default fields set: protocol version to 2.0, request id to null,
+/// and the Libra specific ledger, chain and trace fields
+fn new_response(
+    chain_id: ChainId,
+    ledger_info: &LedgerInfoWithSignatures,
+    trace_id: &str,
+) -> Map<String, Value> {
+    let mut response = Map::new();
+    response.insert("jsonrpc".to_string(), Value::String("2.0".to_string()));
+    response.insert("id".to_string(), Value::Null);
+    response.insert(
+        JSONRPC_LIBRA_LEDGER_VERSION.to_string(),
+        Value::Number(ledger_info.ledger_info().version().into()),
+    );
+    response.insert(
+        JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS.to_string(),
+        Value::Number(ledger_info.ledger_info().timestamp_usecs().into()),
+    );
+    response.insert(
+        JSONRPC_LIBRA_CHAIN_ID.to_string(),
+        Value::Number(chain_id.id().into()),
+    );
+    response.insert(
+        JSONRPC_LIBRA_REQUEST_ID.to_string(),
+        Value::String(trace_id.to_string()),
+    );
+    response
+}
+
 // Sets the JSON RPC error value for a given response.
 // If a counter label is supplied, also increments the invalid request counter using the label,
 fn set_response_error(response: &mut Map<String, Value>, error: JsonRpcError, label: Option<&str>) {
@@ -276,6 +850,17 @@ fn parse_request_id(request: &Map<String, Value>) -> Result<Value, JsonRpcError>
     }
 }
 
+/// Returns the first non-null id shared by several requests of the batch, if any.
+/// Requests with a null id are notifications, which don't get distinct responses
+fn find_duplicate_request_id(requests: &[Value]) -> Option<&Value> {
+    let mut ids = HashSet::new();
+    requests
+        .iter()
+        .filter_map(|request| request.get("id"))
+        .filter(|id| !id.is_null())
+        .find(|id| !ids.insert(id.to_string()))
+}
+
 fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
     if let Some(Value::String(protocol)) = request.get("jsonrpc") {
         if protocol == "2.0" {
@@ -285,8 +870,273 @@ fn verify_protocol(request: &Map<String, Value>) -> Result<(), JsonRpcError> {
     Err(JsonRpcError::invalid_request())
 }
 
+/// Returns the optional minimum ledger version required by the request, 0 if absent. Like
+/// versions in params, it is given either as a number or as a numeric string
+fn parse_min_ledger_version(request: &Map<String, Value>) -> Result<u64, JsonRpcError> {
+    match request.get("min_ledger_version") {
+        Some(Value::Null) | None => Ok(0),
+        Some(min_ledger_version) => {
+            parse_u64(min_ledger_version).ok_or_else(JsonRpcError::invalid_request)
+        }
+    }
+}
+
 /// Warp rejection types
 #[derive(Debug)]
 struct DatabaseError;
 
 impl Reject for DatabaseError {}
+
+/// Tracks the number of requests being handled and caps it at `max_inflight`
+pub(crate) struct InflightRequests {
+    count: AtomicU64,
+    max_inflight: u64,
+}
+
+impl InflightRequests {
+    pub fn new(max_inflight: u64) -> Self {
+        Self {
+            count: AtomicU64::new(0),
+            max_inflight,
+        }
+    }
+
+    /// Registers a new inflight request, unless `max_inflight` requests are already being handled.
+    /// The request is deregistered when the returned guard is dropped
+    pub fn try_acquire(self: &Arc<Self>) -> Option<InflightGuard> {
+        let count = self.count.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.max_inflight > 0 && count > self.max_inflight {
+            self.count.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        counters::INFLIGHT_REQUESTS.inc();
+        Some(InflightGuard(Arc::clone(self)))
+    }
+}
+
+pub(crate) struct InflightGuard(Arc<InflightRequests>);
+
+impl Drop for InflightGuard {
+    fn drop(&mut self) {
+        self.0.count.fetch_sub(1, Ordering::SeqCst);
+        counters::INFLIGHT_REQUESTS.dec();
+    }
+}
+
+/// Restricts methods to the clients presenting an API key allowed to call them.
+/// Methods that aren't restricted are open to all clients
+pub(crate) struct ApiKeyAuth {
+    restricted_methods: HashSet<String>,
+    /// Methods each API key is allowed to call
+    api_keys: HashMap<String, HashSet<String>>,
+}
+
+impl ApiKeyAuth {
+    pub fn new(config: &RpcConfig, registry: &RpcRegistry) -> Self {
+        let methods = config
+            .restricted_methods
+            .iter()
+            .chain(config.api_keys.values().flatten());
+        for method in methods {
+            assert!(
+                resolve_method(registry, method).is_some(),
+                "[rpc] unknown method {} in API key config",
+                method
+            );
+        }
+        let unversioned = |methods: &[String]| -> HashSet<String> {
+            methods
+                .iter()
+                .map(|method| unversioned_name(method).to_string())
+                .collect()
+        };
+        Self {
+            restricted_methods: unversioned(&config.restricted_methods),
+            api_keys: config
+                .api_keys
+                .iter()
+                .map(|(key, methods)| (key.clone(), unversioned(methods)))
+                .collect(),
+        }
+    }
+
+    pub fn is_restricted(&self, method: &str) -> bool {
+        self.restricted_methods.contains(unversioned_name(method))
+    }
+
+    pub fn is_authorized(&self, method: &str, api_key: Option<&str>) -> bool {
+        let method = unversioned_name(method);
+        if !self.restricted_methods.contains(method) {
+            return true;
+        }
+        api_key
+            .and_then(|key| self.api_keys.get(key))
+            .map_or(false, |methods| methods.contains(method))
+    }
+}
+
+/// Costs of the methods that are more expensive than the default cost of 1
+const DEFAULT_METHOD_COSTS: &[(&str, u64)] = &[
+    ("get_account_creation_transaction", 100),
+    ("get_account_diff", 10),
+    ("get_account_state_with_proof", 10),
+    ("get_account_state_with_proof_by_version", 10),
+    ("get_account_state_with_proof_latest", 10),
+    ("get_account_state_with_proofs", 10),
+    ("get_events_by_type", 100),
+    ("get_gas_estimate", 100),
+    ("get_ledger_info_at_version", 10),
+    ("get_state_proof", 10),
+    ("list_child_vasps", 100),
+];
+
+/// Index of the param of the methods whose cost grows with the number of items requested: either
+/// a limit, or the list of requested items
+const LIMIT_PARAMS: &[(&str, usize)] = &[
+    ("get_transactions", 1),
+    ("get_transaction_infos", 1),
+    ("get_transactions_by_time", 2),
+    ("get_events", 2),
+    ("get_events_since_cursor", 1),
+    ("get_account_state_with_proofs", 1),
+    ("batch_fetch", 0),
+];
+
+/// Returns the number of items requested by the call to the given unversioned method with the
+/// given params, None if the method doesn't take a limit or a list of items
+fn requested_items(method: &str, params: &[Value]) -> Option<u64> {
+    let (_, index) = LIMIT_PARAMS.iter().find(|(name, _)| *name == method)?;
+    let param = params.get(*index)?;
+    parse_u64(param).or_else(|| param.as_array().map(|items| items.len() as u64))
+}
+
+/// Bounds the total cost of the requests of a batch, so a batch can't consume disproportionate
+/// resources by combining many expensive requests
+pub(crate) struct BatchCost {
+    /// Maximum total cost of a batch, 0 if unlimited
+    budget: u64,
+    costs: HashMap<String, u64>,
+}
+
+impl BatchCost {
+    pub fn new(config: &RpcConfig, registry: &RpcRegistry) -> Self {
+        for method in config.method_costs.keys() {
+            assert!(
+                resolve_method(registry, method).is_some(),
+                "[rpc] unknown method {} in method costs config",
+                method
+            );
+        }
+        let mut costs: HashMap<_, _> = DEFAULT_METHOD_COSTS
+            .iter()
+            .map(|(method, cost)| (method.to_string(), *cost))
+            .collect();
+        costs.extend(
+            config
+                .method_costs
+                .iter()
+                .map(|(method, cost)| (unversioned_name(method).to_string(), *cost)),
+        );
+        Self {
+            budget: config.batch_cost_budget,
+            costs,
+        }
+    }
+
+    /// Returns the cost of the request: the cost of its method, multiplied by the number of items
+    /// requested for methods taking a limit or a list of items
+    pub fn cost(&self, request: &Value) -> u64 {
+        let method = unversioned_name(
+            request
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+        );
+        let cost = self.costs.get(method).copied().unwrap_or(1);
+        let items = request
+            .get("params")
+            .and_then(Value::as_array)
+            .and_then(|params| requested_items(method, params))
+            .unwrap_or(1);
+        cost.saturating_mul(items)
+    }
+
+    /// Fails if the total cost of the requests exceeds the budget
+    pub fn check(&self, requests: &[Value]) -> Result<(), JsonRpcError> {
+        if self.budget == 0 {
+            return Ok(());
+        }
+        let cost = requests.iter().fold(0u64, |total, request| {
+            total.saturating_add(self.cost(request))
+        });
+        if cost > self.budget {
+            return Err(JsonRpcError::batch_too_costly(cost, self.budget));
+        }
+        Ok(())
+    }
+}
+
+/// Enforces the limits of the methods of the node, which depend on its role: full nodes serve the
+/// public, so can be configured with stricter limits than validators, which aren't limited
+pub(crate) struct MethodLimiter {
+    /// Limits of each limited method by unversioned name, with the number of its calls being
+    /// handled
+    limits: HashMap<String, (MethodLimits, Arc<AtomicU64>)>,
+}
+
+impl MethodLimiter {
+    /// Returns the limiter of a node with the given role, None if none of its methods is limited
+    pub fn new(config: &RpcConfig, role: RoleType) -> Option<Self> {
+        if role != RoleType::FullNode || config.full_node_method_limits.is_empty() {
+            return None;
+        }
+        let limits = config
+            .full_node_method_limits
+            .iter()
+            .map(|(method, limits)| {
+                let method = unversioned_name(method).to_string();
+                (method, (*limits, Arc::new(AtomicU64::new(0))))
+            })
+            .collect();
+        Some(Self { limits })
+    }
+
+    /// Checks the call to the given method with the given params against the limits of the
+    /// method, and registers it as being handled until the returned guard is dropped.
+    /// Rejections come with the label they are counted under
+    pub fn check(
+        &self,
+        method: &str,
+        params: &[Value],
+    ) -> Result<Option<MethodGuard>, (JsonRpcError, &'static str)> {
+        let method = unversioned_name(method);
+        let (limits, calls) = match self.limits.get(method) {
+            Some(limits) => limits,
+            None => return Ok(None),
+        };
+        if let Some(items) = requested_items(method, params) {
+            if limits.max_items > 0 && items > limits.max_items {
+                let reason = format!("at most {} items can be requested", limits.max_items);
+                return Err((
+                    JsonRpcError::invalid_limit(items, limits.max_items, reason),
+                    LABEL_INVALID_PARAMS,
+                ));
+            }
+        }
+        let count = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if limits.max_concurrent > 0 && count > limits.max_concurrent {
+            calls.fetch_sub(1, Ordering::SeqCst);
+            return Err((JsonRpcError::server_overloaded(), LABEL_OVERLOADED));
+        }
+        Ok(Some(MethodGuard(Arc::clone(calls))))
+    }
+}
+
+/// Call registered as being handled by a `MethodLimiter` until dropped
+pub(crate) struct MethodGuard(Arc<AtomicU64>);
+
+impl Drop for MethodGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
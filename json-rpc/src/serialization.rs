@@ -0,0 +1,167 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serialization of responses and decoding of requests with fewer allocations
+//!
+//! Replies are serialized in a buffer reused across the replies of a thread, and the serialized
+//! bytes are handed over as the reply body without being copied. The space of a body is reclaimed
+//! by the buffer once the body is sent and dropped, so replies don't allocate once the buffer has
+//! grown to the size of the largest ones. Response sizes are
+//! computed without serializing to memory at all. Streamed replies are sent in chunks, as they
+//! are serialized. Hex encoded LCS payloads are decoded in a buffer reused the same way, and
+//! deserialized from it.
+//!
+//! Clients which can't represent every u64 as a number, such as JavaScript clients past 2^53, can
+//! have the unsigned integers of their responses serialized as strings instead. Replies can also
+//! be pretty-printed for humans reading them, which only changes their whitespace.
+use anyhow::{format_err, Result};
+use bytes::{BufMut, BytesMut};
+use futures::Stream;
+use libra_json_rpc_types::views::{
+    JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS, JSONRPC_LIBRA_LEDGER_VERSION,
+};
+use libra_logger::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    io::{self, Write},
+};
+use warp::{
+    http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+    hyper::Body,
+    reply::{self, Reply, Response},
+};
+
+/// Largest capacity of the reply and decode buffers kept for the next use, so that a few large
+/// payloads don't pin memory on every thread
+const MAX_RETAINED_BUFFER_CAPACITY: usize = 4 * 1024 * 1024;
+
+thread_local! {
+    static REPLY_BUFFER: RefCell<BytesMut> = RefCell::new(BytesMut::new());
+    static DECODE_BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
+/// Returns the HTTP reply with the given JSON body, serialized in the reused reply buffer.
+/// The body is indented if `pretty`, and compact otherwise
+pub fn json_reply(value: &Value, pretty: bool) -> Response {
+    REPLY_BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        buffer.clear();
+        let mut writer = (&mut *buffer).writer();
+        let serialized = if pretty {
+            serde_json::to_writer_pretty(&mut writer, value)
+        } else {
+            serde_json::to_writer(&mut writer, value)
+        };
+        let reply = match serialized {
+            Ok(()) => {
+                // the serialized bytes are split off the buffer, which keeps its spare capacity
+                let mut response = Response::new(buffer.split().freeze().into());
+                response
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                response
+            }
+            Err(err) => {
+                error!("[json-rpc] failed to serialize reply: {}", err);
+                buffer.clear();
+                reply::with_status(reply::reply(), StatusCode::INTERNAL_SERVER_ERROR)
+                    .into_response()
+            }
+        };
+        if buffer.capacity() > MAX_RETAINED_BUFFER_CAPACITY {
+            *buffer = BytesMut::new();
+        }
+        reply
+    })
+}
+
+/// Returns the HTTP reply with a JSON body sent in the given chunks, as they are produced.
+/// The body is cut short if a chunk fails, so the client sees a truncated response
+pub(crate) fn json_stream_reply<S>(chunks: S) -> Response
+where
+    S: Stream<Item = Result<Vec<u8>>> + Send + 'static,
+{
+    let mut response = Response::new(Body::wrap_stream(chunks));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+/// Replaces the unsigned integers of the given response, or of each response of the given batch,
+/// by their decimal strings: those of the result, and the ledger version and timestamp.
+/// Request ids and error codes are left as sent
+pub(crate) fn stringify_u64s(response: &mut Value) {
+    match response {
+        Value::Array(responses) => responses.iter_mut().for_each(stringify_u64s),
+        Value::Object(response) => {
+            for field in &[
+                JSONRPC_LIBRA_LEDGER_VERSION,
+                JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS,
+                "result",
+            ] {
+                if let Some(value) = response.get_mut(*field) {
+                    stringify_unsigned(value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces every unsigned integer within the given value by its decimal string
+pub(crate) fn stringify_unsigned(value: &mut Value) {
+    match value {
+        Value::Number(number) if number.is_u64() => {
+            let string = number.to_string();
+            *value = Value::String(string);
+        }
+        Value::Array(values) => values.iter_mut().for_each(stringify_unsigned),
+        Value::Object(fields) => fields.values_mut().for_each(stringify_unsigned),
+        _ => {}
+    }
+}
+
+/// Returns the size in bytes of the JSON serialization of the given value
+pub(crate) fn serialized_size<T: Serialize>(value: &T) -> Result<usize> {
+    let mut counter = ByteCounter(0);
+    serde_json::to_writer(&mut counter, value)?;
+    Ok(counter.0)
+}
+
+/// Writer counting the bytes written to it, without storing them
+struct ByteCounter(usize);
+
+impl Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Decodes the given hex string in the reused decode buffer and deserializes the LCS value it holds
+///
+/// Invalid hex and invalid LCS are reported with distinct errors.
+pub(crate) fn decode_hex_lcs<T: DeserializeOwned>(data: &str) -> Result<T> {
+    DECODE_BUFFER.with(|buffer| {
+        let mut buffer = buffer.borrow_mut();
+        buffer.clear();
+        buffer.resize(data.len() / 2, 0);
+        let decoded = hex::decode_to_slice(data, buffer.as_mut_slice())
+            .map_err(|err| format_err!("invalid hex payload: {}", err))
+            .and_then(|()| {
+                lcs::from_bytes(buffer.as_slice())
+                    .map_err(|err| format_err!("invalid LCS payload: {}", err))
+            });
+        if buffer.capacity() > MAX_RETAINED_BUFFER_CAPACITY {
+            *buffer = Vec::new();
+        }
+        decoded
+    })
+}
@@ -0,0 +1,111 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coalescing of concurrent submissions into batches sent to mempool
+//!
+//! Under load, e.g. from a faucet, sending every submitted transaction to mempool in its own
+//! message makes mempool spawn a task and read storage for each of them. Transactions submitted
+//! within a short window of each other are instead gathered and sent to mempool in a single
+//! message, and each submitter still gets its own status back through its callback. The first
+//! transaction of a window waits for the window to end before being sent, so a transaction
+//! submitted alone is delayed by up to the window, and is sent on its own as before.
+use crate::{counters, errors::JsonRpcError};
+use anyhow::{Error, Result};
+use futures::{
+    channel::{mpsc::TrySendError, oneshot},
+    future,
+};
+use libra_logger::prelude::*;
+use libra_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use libra_types::transaction::SignedTransaction;
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time;
+
+type Submission = (SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>);
+
+/// Transactions submitted during the current window, waiting to be sent to mempool together
+pub(crate) struct SubmitBatcher {
+    window: Duration,
+    pending: Arc<Mutex<Vec<Submission>>>,
+}
+
+impl SubmitBatcher {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Adds the given transaction to the current batch, starting a new batch if there is none,
+    /// and returns the callback its submission status is sent to once mempool processed it.
+    /// The callback gets a mempool unavailable error if the batch can't be sent to mempool.
+    /// Must be called within a tokio runtime
+    pub fn submit(
+        &self,
+        mempool_sender: &MempoolClientSender,
+        transaction: SignedTransaction,
+    ) -> oneshot::Receiver<Result<SubmissionStatus>> {
+        let (req_sender, callback) = oneshot::channel();
+        let starts_batch = {
+            let mut pending = self
+                .pending
+                .lock()
+                .expect("failed to lock pending submissions");
+            pending.push((transaction, req_sender));
+            pending.len() == 1
+        };
+        // the batch is sent by a task of its own, so it's sent even if its first submitter
+        // stops waiting
+        if starts_batch {
+            let window = self.window;
+            let pending = Arc::clone(&self.pending);
+            let mut mempool_sender = mempool_sender.clone();
+            tokio::spawn(async move {
+                time::delay_for(window).await;
+                let mut batch =
+                    mem::take(&mut *pending.lock().expect("failed to lock pending submissions"));
+                counters::SUBMIT_BATCHES.inc();
+                counters::SUBMIT_BATCHED_TRANSACTIONS.add(batch.len() as i64);
+                let request = if batch.len() == 1 {
+                    let (transaction, req_sender) = batch.remove(0);
+                    MempoolClientRequest::SubmitTransaction(transaction, req_sender)
+                } else {
+                    MempoolClientRequest::SubmitTransactions(batch)
+                };
+                // the request is kept if it can't be sent, so its submitters learn it wasn't
+                // processed rather than having their callbacks dropped
+                let sent = match future::poll_fn(|cx| mempool_sender.poll_ready(cx)).await {
+                    Ok(()) => mempool_sender
+                        .try_send(request)
+                        .map_err(TrySendError::into_inner),
+                    Err(_) => Err(request),
+                };
+                if let Err(request) = sent {
+                    warn!("[rpc] failed to send submitted transactions to mempool");
+                    reject(request);
+                }
+            });
+        }
+        callback
+    }
+}
+
+/// Answers the submitters of the given request that mempool is unavailable
+fn reject(request: MempoolClientRequest) {
+    let req_senders = match request {
+        MempoolClientRequest::SubmitTransaction(_, req_sender) => vec![req_sender],
+        MempoolClientRequest::SubmitTransactions(batch) => batch
+            .into_iter()
+            .map(|(_, req_sender)| req_sender)
+            .collect(),
+        _ => vec![],
+    };
+    for req_sender in req_senders {
+        let _ = req_sender.send(Err(Error::new(JsonRpcError::mempool_unavailable())));
+    }
+}
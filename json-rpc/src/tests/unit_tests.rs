@@ -2,51 +2,99 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    audit::{AuditEntry, AuditLog},
+    circuit_breaker::{CircuitBreaker, CircuitBreakerDb},
+    connections::LimitedIncoming,
+    counters,
     errors::{InvalidArguments, JsonRpcError, ServerCode},
-    tests::utils::{test_bootstrap, MockLibraDB},
+    failover::FailoverDb,
+    methods::{build_registry, Deprecation, JsonRpcRequest, JsonRpcService},
+    openrpc::openrpc_document,
+    otel,
+    read_pool::ReadPool,
+    replay_protection::ReplayProtection,
+    response_cache::ResponseCache,
+    runtime::{rpc_request_handler, ApiKeyAuth, BatchCost, InflightRequests, MethodLimiter},
+    serialization::{decode_hex_lcs, json_reply, serialized_size},
+    tests::utils::{
+        call_method, test_bootstrap, test_bootstrap_with_role, test_service, MockLibraDB,
+    },
 };
 use futures::{channel::mpsc::channel, StreamExt};
-use libra_config::utils;
+use libra_config::{
+    config::{FailoverPolicy, MethodLimits, NetworkStatusPolicy, RoleType, RpcConfig},
+    utils,
+};
 use libra_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, HashValue, PrivateKey, Uniform};
 use libra_json_rpc_client::{
     views::{
-        AccountStateWithProofView, BlockMetadata, BytesView, EventView, StateProofView,
-        TransactionDataView, TransactionView, VMStatusView,
+        AccountStateWithProofResultView, AccountStateWithProofView,
+        AccountStateWithSparseProofView, AccountView, BlockMetadata, BytesView, EventView,
+        ScriptView, StateProofView, TransactionDataView, TransactionInfoView, TransactionView,
+        VMStatusView,
     },
     JsonRpcAsyncClient, JsonRpcBatch, JsonRpcResponse, ResponseAsView,
 };
 use libra_json_rpc_types::{
     response::JsonRpcErrorResponse,
     views::{
-        JSONRPC_LIBRA_CHAIN_ID, JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS, JSONRPC_LIBRA_LEDGER_VERSION,
+        CapabilitiesView, EpochChangeProofView, EventsPageView, LedgerInfoView, NetworkStatusView,
+        NodeConfigView, ServerVersionView, TransactionArgumentView, JSONRPC_LIBRA_CHAIN_ID,
+        JSONRPC_LIBRA_DEPRECATION, JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS,
+        JSONRPC_LIBRA_LEDGER_VERSION, JSONRPC_LIBRA_REQUEST_ID,
     },
 };
+use libra_mempool::MempoolClientRequest;
 use libra_proptest_helpers::ValueGenerator;
+use libra_temppath::TempPath;
 use libra_types::{
     account_address::AccountAddress,
-    account_config::AccountResource,
+    account_config::{
+        treasury_compliance_account_address, type_tag_for_currency_code, AccountResource,
+        AccountRole, BalanceResource, ChildVASP, CurrencyInfoResource, FreezingBit,
+    },
+    account_state::AccountState,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
+    block_info::BlockInfo,
     chain_id::ChainId,
     contract_event::ContractEvent,
-    event::EventKey,
-    ledger_info::LedgerInfoWithSignatures,
+    epoch_change::EpochChangeProof,
+    epoch_state::EpochState,
+    event::{EventHandle, EventKey},
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     mempool_status::{MempoolStatus, MempoolStatusCode},
+    on_chain_config::{OnChainConfig, RegisteredCurrencies},
     proof::{SparseMerkleProof, TransactionAccumulatorProof, TransactionInfoWithProof},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
-    transaction::{Transaction, TransactionInfo, TransactionPayload},
-    vm_status::StatusCode,
+    transaction::{
+        Script, SignedTransaction, Transaction, TransactionArgument, TransactionInfo,
+        TransactionPayload,
+    },
+    vm_status::{KeptVMStatus, StatusCode},
 };
 use libradb::test_helper::arb_blocks_to_commit;
-use move_core_types::language_storage::TypeTag;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+    move_resource::MoveResource,
+    parser::parse_type_tags,
+};
 use proptest::prelude::*;
 use std::{
     collections::{BTreeMap, HashMap},
     convert::TryFrom,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 use storage_interface::DbReader;
-use tokio::runtime::Runtime;
+use tokio::{
+    io::AsyncReadExt,
+    net::{TcpListener, TcpStream},
+    runtime::Runtime,
+    time::timeout,
+};
+use vm::file_format::{empty_module, AddressIdentifierIndex, IdentifierIndex, ModuleHandle};
 use vm_validator::{
     mocks::mock_vm_validator::MockVMValidator, vm_validator::TransactionValidation,
 };
@@ -119,6 +167,8 @@ fn mock_db() -> MockLibraDB {
         events,
         account_state_with_proof,
         timestamps,
+        drop_last_transaction_info: false,
+        epoch_ending_ledger_infos: vec![],
     }
 }
 
@@ -179,8 +229,7 @@ fn test_json_rpc_protocol() {
     assert_eq!(error_code(resp), -32601);
 
     // invalid arguments: too many arguments
-    let request =
-        serde_json::json!({"jsonrpc": "2.0", "method": "get_account", "params": [1, 2], "id": 1});
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_account", "params": [1, 2, 3], "id": 1});
     let resp = client.post(&url).json(&request).send().unwrap();
     assert_eq!(resp.status(), 200);
     let error_resp: JsonRpcErrorResponse = resp.json().unwrap();
@@ -189,8 +238,8 @@ fn test_json_rpc_protocol() {
 
     let invalid_args: InvalidArguments = error_resp.error.as_invalid_arguments().unwrap();
     assert_eq!(invalid_args.required, 1);
-    assert_eq!(invalid_args.optional, 0);
-    assert_eq!(invalid_args.given, 2);
+    assert_eq!(invalid_args.optional, 1);
+    assert_eq!(invalid_args.given, 3);
 
     // invalid arguments: not enough arguments
     let request =
@@ -228,6 +277,303 @@ fn test_json_rpc_protocol() {
     );
 }
 
+#[test]
+fn test_batch_duplicate_ids() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let mp_sender = channel(1024).0;
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db()), mp_sender);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let request = |id: serde_json::Value| serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": id});
+
+    // distinct ids, including ids equal once stringified, and notifications are all served
+    let batch = serde_json::json!([
+        request(serde_json::json!(1)),
+        request(serde_json::json!("1")),
+        request(serde_json::Value::Null),
+        request(serde_json::Value::Null),
+    ]);
+    let resp = client.post(&url).json(&batch).send().unwrap();
+    let responses: Vec<JsonMap> = resp.json().unwrap();
+    assert_eq!(responses.len(), 4);
+    assert!(responses.iter().all(|resp| resp.get("result").is_some()));
+
+    // a duplicate id rejects the whole batch
+    let batch = serde_json::json!([
+        request(serde_json::json!(1)),
+        request(serde_json::Value::Null),
+        request(serde_json::json!(2)),
+        request(serde_json::json!(1)),
+    ]);
+    let resp = client.post(&url).json(&batch).send().unwrap();
+    let error_resp: JsonRpcErrorResponse = resp.json().unwrap();
+    assert_eq!(error_resp.error.code, -32600);
+    assert!(error_resp.error.message.contains("duplicate id 1"));
+}
+
+#[test]
+fn test_min_ledger_version() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let mock_db = mock_db();
+    let version = mock_db.version;
+    let mp_sender = channel(1024).0;
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db), mp_sender);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+
+    // reads are served by a node that reached the minimum ledger version
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1, "min_ledger_version": version});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    let data: JsonMap = resp.json().unwrap();
+    assert!(data.get("result").is_some());
+
+    // and rejected by a node lagging behind it
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1, "min_ledger_version": version + 1});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(error_code(resp), ServerCode::NodeTooStale as i16);
+
+    // as numeric strings
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1, "min_ledger_version": version.to_string()});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    let data: JsonMap = resp.json().unwrap();
+    assert!(data.get("result").is_some());
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1, "min_ledger_version": (version + 1).to_string()});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(error_code(resp), ServerCode::NodeTooStale as i16);
+
+    // the minimum ledger version must be a version
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1, "min_ledger_version": "latest"});
+    let resp = client.post(&url).json(&request).send().unwrap();
+    assert_eq!(error_code(resp), -32600);
+}
+
+#[test]
+fn test_streamed_transactions() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let mp_sender = channel(1024).0;
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db()), mp_sender);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let call = |request: &serde_json::Value, stream: bool| {
+        let mut builder = client
+            .post(&url)
+            .header("x-request-id", "test")
+            .json(request);
+        if stream {
+            builder = builder.header("x-stream-response", "true");
+        }
+        builder.send().unwrap().json::<serde_json::Value>().unwrap()
+    };
+
+    // streamed responses are the same as buffered ones
+    for params in &[
+        serde_json::json!([0, 10, true]),
+        serde_json::json!([0, 10, false, "failed"]),
+    ] {
+        let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": params, "id": 1});
+        let streamed = call(&request, true);
+        assert!(streamed["result"].is_array());
+        assert_eq!(streamed, call(&request, false));
+    }
+
+    // errors are reported as usual, whether they are caught by the envelope or the method
+    for request in &[
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 0], "id": 1}),
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 10], "id": 1, "min_ledger_version": u64::MAX}),
+        serde_json::json!({"jsonrpc": "1.0", "method": "get_transactions", "params": [0, 10], "id": 1}),
+    ] {
+        let streamed = call(request, true);
+        assert!(streamed["error"].is_object());
+        assert_eq!(streamed, call(request, false));
+    }
+}
+
+#[test]
+fn test_streamed_transactions_response_too_large() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let config = RpcConfig {
+        address: address.parse().unwrap(),
+        max_response_size: 100,
+        ..Default::default()
+    };
+    let _runtime = crate::bootstrap(
+        &config,
+        Arc::new(mock_db()),
+        channel(1024).0,
+        RoleType::Validator,
+        ChainId::test(),
+        None,
+        None,
+        false,
+    );
+    let client = reqwest::blocking::Client::new();
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 10, true], "id": 1});
+    let oversized = || {
+        counters::OVERSIZED_RESPONSES
+            .with_label_values(&["get_transactions"])
+            .get()
+    };
+    let before = oversized();
+
+    // the streamed response is cut short once past the maximum response size
+    let streamed = client
+        .post(&format!("http://{}", address))
+        .header("x-stream-response", "true")
+        .json(&request)
+        .send()
+        .and_then(|resp| resp.text());
+    if let Ok(body) = streamed {
+        assert!(serde_json::from_str::<serde_json::Value>(&body).is_err());
+    }
+    assert!(oversized() > before);
+}
+
+#[test]
+fn test_u64_format() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let mp_sender = channel(1024).0;
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db()), mp_sender);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let call = |request: &serde_json::Value, format: Option<&str>, stream: bool| {
+        let mut builder = client
+            .post(&url)
+            .header("x-request-id", "test")
+            .json(request);
+        if let Some(format) = format {
+            builder = builder.header("x-u64-format", format);
+        }
+        if stream {
+            builder = builder.header("x-stream-response", "true");
+        }
+        builder.send().unwrap().json::<serde_json::Value>().unwrap()
+    };
+
+    let request =
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1});
+    let numbers = call(&request, None, false);
+    assert!(numbers["result"]["version"].is_u64());
+    assert_eq!(numbers, call(&request, Some("number"), false));
+    assert_eq!(numbers, call(&request, Some("unknown"), false));
+
+    let strings = call(&request, Some("string"), false);
+    assert_eq!(
+        strings["result"]["version"],
+        numbers["result"]["version"].to_string()
+    );
+    assert_eq!(
+        strings[JSONRPC_LIBRA_LEDGER_VERSION],
+        numbers[JSONRPC_LIBRA_LEDGER_VERSION].to_string()
+    );
+    // the request id is echoed as sent
+    assert_eq!(strings["id"], 1);
+
+    // errors keep their numeric code
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 0], "id": 1});
+    let error = call(&request, Some("string"), false);
+    assert!(error["error"]["code"].is_i64());
+
+    // streamed responses are formatted the same as buffered ones
+    let request = serde_json::json!({"jsonrpc": "2.0", "method": "get_transactions", "params": [0, 10, true], "id": 1});
+    let streamed = call(&request, Some("string"), true);
+    assert!(streamed["result"][0]["version"].is_string());
+    assert_eq!(streamed, call(&request, Some("string"), false));
+
+    // batches are formatted response by response
+    let batch = serde_json::json!([
+        {"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1},
+        {"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 2},
+    ]);
+    for response in call(&batch, Some("string"), false).as_array().unwrap() {
+        assert!(response["result"]["version"].is_string());
+    }
+}
+
+#[test]
+fn test_request_id() {
+    let address = format!("0.0.0.0:{}", utils::get_available_port());
+    let mp_sender = channel(1024).0;
+    let _runtime = test_bootstrap(address.parse().unwrap(), Arc::new(mock_db()), mp_sender);
+    let client = reqwest::blocking::Client::new();
+    let url = format!("http://{}", address);
+    let request =
+        serde_json::json!({"jsonrpc": "2.0", "method": "get_metadata", "params": [], "id": 1});
+    let request_id = |resp: reqwest::blocking::Response| {
+        let data: JsonMap = resp.json().unwrap();
+        data.get(JSONRPC_LIBRA_REQUEST_ID)
+            .and_then(|id| id.as_str())
+            .expect("must have")
+            .to_string()
+    };
+
+    // the id provided by the client is echoed back
+    let resp = client
+        .post(&url)
+        .header("x-request-id", "client-id")
+        .json(&request)
+        .send()
+        .unwrap();
+    assert_eq!(request_id(resp), "client-id");
+
+    // otherwise, and for invalid ids, one is generated per call
+    let first = request_id(client.post(&url).json(&request).send().unwrap());
+    let second = request_id(
+        client
+            .post(&url)
+            .header("x-request-id", "a".repeat(65))
+            .json(&request)
+            .send()
+            .unwrap(),
+    );
+    assert!(!first.is_empty());
+    assert_ne!(first, second);
+    assert_ne!(second, "a".repeat(65));
+
+    // trace contexts only provide the request id while spans are exported
+    let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+    let resp = client
+        .post(&url)
+        .header("traceparent", traceparent)
+        .json(&request)
+        .send()
+        .unwrap();
+    assert_ne!(request_id(resp), "0af7651916cd43dd8448eb211c80319c");
+}
+
+#[test]
+fn test_traceparent() {
+    let trace_id = "0af7651916cd43dd8448eb211c80319c";
+    assert_eq!(
+        otel::trace_id("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"),
+        Some(trace_id)
+    );
+    // later versions may append fields
+    assert_eq!(
+        otel::trace_id("01-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra"),
+        Some(trace_id)
+    );
+    for traceparent in &[
+        "",
+        "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+        "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+        "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+        "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01",
+        "00-0af7651916cd43dd8448eb211c8031-b7ad6b7169203331-01",
+        "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+        "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+    ] {
+        assert_eq!(otel::trace_id(traceparent), None, "{}", traceparent);
+    }
+
+    // without an exporter, trace contexts are ignored
+    assert_eq!(
+        otel::request_id(Some(
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        )),
+        None
+    );
+}
+
 #[test]
 fn test_transaction_submission() {
     let (mp_sender, mut mp_events) = channel(1);
@@ -243,7 +589,7 @@ fn test_transaction_submission() {
     // future that mocks shared mempool execution
     runtime.spawn(async move {
         let validator = MockVMValidator;
-        while let Some((txn, cb)) = mp_events.next().await {
+        while let Some(MempoolClientRequest::SubmitTransaction(txn, cb)) = mp_events.next().await {
             let vm_status = validator.validate_transaction(txn).unwrap().status();
             let result = if vm_status.is_some() {
                 (MempoolStatus::new(MempoolStatusCode::VmError), vm_status)
@@ -354,6 +700,209 @@ fn test_transaction_submission() {
 //    }
 //}
 
+#[test]
+fn test_is_account_frozen_unknown_account() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "is_account_frozen".to_string(),
+        vec![serde_json::json!(AccountAddress::random().to_string())],
+    );
+
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(result, serde_json::Value::Null);
+}
+
+#[test]
+fn test_get_account_at_pruned_version() {
+    let mut mock_db = mock_db();
+    let mut config_state = AccountState::default();
+    config_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&vec![Identifier::new("Coin1").unwrap()]).unwrap(),
+    );
+    mock_db.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&config_state).unwrap());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let address = get_first_account_from_mock_db(&mock_db);
+    let version = mock_db.version;
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        channel(1).0,
+        RoleType::Validator,
+        ChainId::test(),
+        Some(1),
+        &RpcConfig::default(),
+    );
+    let call = |address: AccountAddress, version: u64| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_account",
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::json!(version),
+            ],
+        )
+    };
+
+    // existing accounts can't be read once their state was pruned
+    let err = call(address, 0)
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.code, ServerCode::StatePruned as i16);
+    // neither can missing ones, which would otherwise look like they never existed
+    let missing = AccountAddress::random();
+    let err = call(missing, 0)
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.code, ServerCode::StatePruned as i16);
+    // null is reserved for accounts missing at versions still available
+    assert_eq!(call(missing, version).unwrap(), serde_json::Value::Null);
+}
+
+#[test]
+fn test_account_view_role_fields() {
+    let address = AccountAddress::random();
+    let account = AccountResource::new(
+        0,
+        vec![],
+        None,
+        None,
+        EventHandle::new(EventKey::new_from_address(&address, 0), 0),
+        EventHandle::new(EventKey::new_from_address(&address, 1), 0),
+    );
+    let parent = AccountAddress::random();
+    let role = AccountRole::ChildVASP(lcs::from_bytes(&lcs::to_bytes(&parent).unwrap()).unwrap());
+    let freezing_bit: FreezingBit = lcs::from_bytes(&lcs::to_bytes(&false).unwrap()).unwrap();
+
+    let view = AccountView::new(&account, BTreeMap::new(), role, freezing_bit);
+    assert_eq!(
+        view.parent_vasp_address,
+        Some(BytesView::from(&parent.to_vec()))
+    );
+    assert_eq!(view.num_children, None);
+    assert_eq!(view.compliance_key, None);
+    assert_eq!(view.received_mint_events_key, None);
+
+    // fields of other roles aren't serialized, and views without role fields can be deserialized
+    let mut value = serde_json::to_value(&view).unwrap();
+    assert!(value.get("num_children").is_none());
+    value.as_object_mut().unwrap().remove("parent_vasp_address");
+    let view: AccountView = serde_json::from_value(value).unwrap();
+    assert_eq!(view.parent_vasp_address, None);
+}
+
+#[test]
+fn test_get_resource() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+    let address = get_first_account_from_mock_db(&mock_db);
+    let get_resource = |runtime: &mut Runtime, address: AccountAddress, resource_type: &str| {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_request(
+            "get_resource".to_string(),
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::json!(resource_type),
+            ],
+        );
+        runtime.block_on(client.execute(batch)).unwrap().remove(0)
+    };
+
+    let account_resource =
+        get_resource(&mut runtime, address, "0x1::LibraAccount::LibraAccount").unwrap();
+    let expected = AccountState::try_from(&mock_db.all_accounts[&address])
+        .unwrap()
+        .get_account_resource()
+        .unwrap()
+        .unwrap();
+    if let JsonRpcResponse::UnknownResponse(value) = account_resource {
+        let bytes: BytesView = serde_json::from_value(value).unwrap();
+        let resource: AccountResource = lcs::from_bytes(&bytes.into_bytes().unwrap()).unwrap();
+        assert_eq!(resource.sequence_number(), expected.sequence_number());
+    } else {
+        panic!("did not receive expected json rpc response");
+    }
+
+    // missing resources and accounts are null
+    for (address, resource_type) in &[
+        (address, "0x1::LibraAccount::Balance<0x1::Unknown::Unknown>"),
+        (AccountAddress::random(), "0x1::LibraAccount::LibraAccount"),
+    ] {
+        let response = get_resource(&mut runtime, *address, resource_type).unwrap();
+        assert!(matches!(
+            response,
+            JsonRpcResponse::UnknownResponse(serde_json::Value::Null)
+        ));
+    }
+
+    // the resource type must be a struct
+    assert!(get_resource(&mut runtime, address, "u64").is_err());
+    assert!(get_resource(&mut runtime, address, "not a type").is_err());
+}
+
+#[test]
+fn test_get_latest_version() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request("get_latest_version".to_string(), vec![]);
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(result, serde_json::json!(mock_db.version));
+}
+
+#[test]
+fn test_get_account_diff() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+    let version = mock_db.get_latest_version().unwrap();
+
+    // an account that doesn't exist at either version didn't change
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "get_account_diff".to_string(),
+        vec![
+            serde_json::json!(AccountAddress::random().to_string()),
+            serde_json::json!(0),
+            serde_json::json!(version),
+        ],
+    );
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(
+        result,
+        serde_json::json!({"added": [], "removed": [], "changed": []})
+    );
+
+    // versions can't be ahead of the ledger
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "get_account_diff".to_string(),
+        vec![
+            serde_json::json!(AccountAddress::random().to_string()),
+            serde_json::json!(0),
+            serde_json::json!(version + 1),
+        ],
+    );
+    let responses = runtime.block_on(client.execute(batch)).unwrap();
+    assert!(responses[0].is_err());
+}
+
+#[test]
+fn test_get_account_role_unknown_account() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "get_account_role".to_string(),
+        vec![serde_json::json!(AccountAddress::random().to_string())],
+    );
+
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(result, serde_json::Value::Null);
+}
+
 #[test]
 fn test_get_metadata_latest() {
     let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
@@ -411,258 +960,3243 @@ fn test_get_events() {
         fetched_event.transaction_version, first_event_version,
         "Tx version wrong"
     );
+    assert_eq!(fetched_event.event_index, None);
+    // every event of the page has the hash of the transaction emitting it
+    for event in &events {
+        let (tx, _) = &mock_db.all_txns[event.transaction_version as usize];
+        assert_eq!(event.transaction_hash, Some(tx.hash().to_hex()));
+    }
 }
 
 #[test]
-fn test_get_transactions() {
-    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
-
-    let version = mock_db.get_latest_version().unwrap();
-    let page = 800usize;
-
-    for base_version in (0..version)
-        .map(u64::from)
-        .take(page)
-        .collect::<Vec<_>>()
-        .into_iter()
-    {
-        let mut batch = JsonRpcBatch::default();
-        batch.add_get_transactions_request(base_version, page as u64, true);
-        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
-        let txns = TransactionView::vec_from_response(result).unwrap();
-
-        for (i, view) in txns.iter().enumerate() {
-            let version = base_version + i as u64;
-            assert_eq!(view.version, version);
-            let (tx, status) = &mock_db.all_txns[version as usize];
-            assert_eq!(view.hash, tx.hash().to_hex());
-
-            // Check we returned correct events
-            let expected_events = mock_db
-                .events
-                .iter()
-                .filter(|(v, _)| *v == view.version)
-                .map(|(_, e)| e)
-                .collect::<Vec<_>>();
+fn test_get_events_cursor() {
+    let mut mock_db = mock_db();
+    // a stream of three events, emitted by the first transactions
+    let key = EventKey::random();
+    for sequence_number in 0..3 {
+        let event = ContractEvent::new(key, sequence_number, TypeTag::Bool, vec![]);
+        mock_db.events.push((sequence_number + 1, event));
+    }
+    let event_key = serde_json::json!(hex::encode(key.as_bytes()));
+    let other_key = serde_json::json!(hex::encode(EventKey::random().as_bytes()));
 
-            assert_eq!(expected_events.len(), view.events.len());
-            assert_eq!(VMStatusView::from(status), view.vm_status);
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str, params: Vec<serde_json::Value>| {
+        call_method(&service, &ledger_info, method, params)
+    };
 
-            for (i, event_view) in view.events.iter().enumerate() {
-                let expected_event = expected_events.get(i).expect("Expected event didn't find");
-                assert_eq!(event_view.sequence_number, expected_event.sequence_number());
-                assert_eq!(event_view.transaction_version, version);
-                assert_eq!(
-                    event_view.key.0,
-                    BytesView::from(expected_event.key().as_bytes()).0
-                );
-                // TODO: check event_data
-            }
+    let all_events = call(
+        "get_events",
+        vec![
+            event_key.clone(),
+            serde_json::json!(0),
+            serde_json::json!(1000),
+        ],
+    )
+    .unwrap();
 
-            match tx {
-                Transaction::BlockMetadata(t) => match view.transaction {
-                    TransactionDataView::BlockMetadata { timestamp_usecs } => {
-                        assert_eq!(t.clone().into_inner().unwrap().1, timestamp_usecs);
-                    }
-                    _ => panic!("Returned value doesn't match!"),
-                },
-                Transaction::WaypointWriteSet(_) => match view.transaction {
-                    TransactionDataView::WriteSet { .. } => {}
-                    _ => panic!("Returned value doesn't match!"),
-                },
-                Transaction::UserTransaction(t) => match &view.transaction {
-                    TransactionDataView::UserTransaction {
-                        sender,
-                        script_hash,
-                        chain_id,
-                        ..
-                    } => {
-                        assert_eq!(&t.sender().to_string(), sender);
-                        assert_eq!(&t.chain_id().id(), chain_id);
-                        // TODO: verify every field
-                        if let TransactionPayload::Script(s) = t.payload() {
-                            assert_eq!(script_hash, &HashValue::sha3_256_of(s.code()).to_hex());
-                        }
-                    }
-                    _ => panic!("Returned value doesn't match!"),
-                },
-            }
+    // following cursors one event at a time returns the same events
+    let mut events = vec![];
+    let mut start = serde_json::json!(0);
+    loop {
+        let page = call(
+            "v2.get_events",
+            vec![event_key.clone(), start.clone(), serde_json::json!(1)],
+        )
+        .unwrap();
+        let page: EventsPageView = serde_json::from_value(page).unwrap();
+        if page.events.is_empty() {
+            break;
         }
+        events.extend(page.events);
+        start = serde_json::json!(page.next_cursor);
     }
+    assert_eq!(events.len(), 3);
+    assert_eq!(serde_json::to_value(&events).unwrap(), all_events);
+
+    // a cursor is only valid for the stream it was returned for
+    let err = call(
+        "v2.get_events",
+        vec![other_key, start, serde_json::json!(1)],
+    )
+    .unwrap_err()
+    .downcast::<JsonRpcError>()
+    .unwrap();
+    assert_eq!(err.as_invalid_param().unwrap().index, 1);
+
+    let invalid_cursor = serde_json::json!("cursor:zz");
+    assert!(call(
+        "v2.get_events",
+        vec![event_key, invalid_cursor, serde_json::json!(1)]
+    )
+    .is_err());
 }
 
 #[test]
-fn test_get_account_transaction() {
-    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+fn test_get_events_since_cursor() {
+    let day_usecs = 24 * 60 * 60 * 1_000_000u64;
+    let mut mock_db = mock_db();
+    *mock_db.timestamps.last_mut().unwrap() = 2 * day_usecs;
+    let key = EventKey::random();
+    for sequence_number in 0..3 {
+        let event = ContractEvent::new(key, sequence_number, TypeTag::Bool, vec![]);
+        mock_db.events.push((sequence_number + 1, event));
+    }
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str, params: Vec<serde_json::Value>| {
+        call_method(&service, &ledger_info, method, params)
+    };
+    let since_cursor = |cursor: &str| {
+        let page = call(
+            "get_events_since_cursor",
+            vec![serde_json::json!(cursor), serde_json::json!(2)],
+        )?;
+        Ok::<_, anyhow::Error>(serde_json::from_value::<EventsPageView>(page).unwrap())
+    };
+
+    // cursors of v2.get_events resume the stream after the events returned
+    let page = call(
+        "v2.get_events",
+        vec![
+            serde_json::json!(hex::encode(key.as_bytes())),
+            serde_json::json!(0),
+            serde_json::json!(2),
+        ],
+    )
+    .unwrap();
+    let page: EventsPageView = serde_json::from_value(page).unwrap();
+    let page = since_cursor(&page.next_cursor).unwrap();
+    let sequence_numbers: Vec<_> = page.events.iter().map(|e| e.sequence_number).collect();
+    assert_eq!(sequence_numbers, vec![2]);
+    // once caught up, the cursor stays put until new events are committed, within a ledger info
+    let caught_up = since_cursor(&page.next_cursor).unwrap();
+    assert!(caught_up.events.is_empty());
+    assert_eq!(caught_up.next_cursor, page.next_cursor);
+
+    // a cursor issued over a day before the ledger info has expired
+    let stale_cursor = format!(
+        "cursor:{}",
+        hex::encode(lcs::to_bytes(&(key, 0u64, day_usecs - 1)).unwrap())
+    );
+    for cursor in &[stale_cursor.as_str(), "cursor:zz", "0"] {
+        let err = since_cursor(cursor)
+            .unwrap_err()
+            .downcast::<JsonRpcError>()
+            .unwrap();
+        assert_eq!(err.as_invalid_param().unwrap().index, 0);
+    }
+}
+
+#[test]
+fn test_get_events_start_past_count() {
+    let mut mock_db = mock_db();
+    // an account that sent three payments
+    let address = AccountAddress::random();
+    let key = EventKey::new_from_address(&address, 0);
+    let account = AccountResource::new(
+        0,
+        vec![],
+        None,
+        None,
+        EventHandle::new(key, 3),
+        EventHandle::new(EventKey::new_from_address(&address, 1), 0),
+    );
+    let mut account_state = AccountState::default();
+    account_state.insert(
+        AccountResource::resource_path(),
+        lcs::to_bytes(&account).unwrap(),
+    );
+    mock_db
+        .all_accounts
+        .insert(address, AccountStateBlob::try_from(&account_state).unwrap());
+    for sequence_number in 0..3 {
+        let event = ContractEvent::new(key, sequence_number, TypeTag::Bool, vec![]);
+        mock_db.events.push((sequence_number + 1, event));
+    }
+    let event_key = serde_json::json!(hex::encode(key.as_bytes()));
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_events = |start: u64| {
+        let events = call_method(
+            &service,
+            &ledger_info,
+            "get_events",
+            vec![
+                event_key.clone(),
+                serde_json::json!(start),
+                serde_json::json!(10),
+            ],
+        )
+        .unwrap();
+        events.as_array().unwrap().len()
+    };
+
+    assert_eq!(get_events(2), 1);
+    // starting at, just past and far beyond the event count returns no events
+    for start in &[3, 4, u64::max_value()] {
+        assert_eq!(get_events(*start), 0);
+    }
+}
+
+/// Mock DB serving the given currencies, registered in the given order, as (code, exchange rate
+/// to LBR in 32|32 fixed-point, can mint)
+fn mock_db_with_currencies(currencies: &[(&str, u64, bool)]) -> MockLibraDB {
+    let mut account_state = AccountState::default();
+    let registered: Vec<_> = currencies
+        .iter()
+        .map(|(code, _, _)| Identifier::new(*code).unwrap())
+        .collect();
+    account_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&registered).unwrap(),
+    );
+    for (code, (_, exchange_rate, can_mint)) in registered.into_iter().zip(currencies) {
+        let events = || EventHandle::new(EventKey::random(), 0);
+        // fields of the CurrencyInfo resource, in declaration order
+        let currency_info = (
+            0u128,
+            0u64,
+            *exchange_rate,
+            false,
+            1_000_000u64,
+            100u64,
+            code.clone(),
+            *can_mint,
+            (events(), events(), events(), events(), events()),
+        );
+        account_state.insert(
+            CurrencyInfoResource::resource_path_for(code).path,
+            lcs::to_bytes(&currency_info).unwrap(),
+        );
+    }
+    let mut mock_db = mock_db();
+    // the mock serves batched resource reads from this account state
+    mock_db.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&account_state).unwrap());
+    mock_db
+}
+
+#[test]
+fn test_get_currencies_order() {
+    // currencies are registered out of order, and Coin2 was retired
+    let mock_db =
+        mock_db_with_currencies(&[("LBR", 1, true), ("Coin2", 1, false), ("Coin1", 1, true)]);
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_currencies = |params: Vec<serde_json::Value>| {
+        let currencies = call_method(&service, &ledger_info, "get_currencies", params).unwrap();
+        currencies
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|currency| currency["code"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(get_currencies(vec![]), vec!["Coin1", "Coin2", "LBR"]);
+
+    // retired currencies are only left out on request
+    assert_eq!(
+        get_currencies(vec![serde_json::json!(true)]),
+        vec!["Coin1", "Coin2", "LBR"]
+    );
+    assert_eq!(
+        get_currencies(vec![serde_json::json!(false)]),
+        vec!["Coin1", "LBR"]
+    );
+}
+
+#[test]
+fn test_convert_amount() {
+    // Coin1 is worth half an LBR, Coin2 a whole one
+    let mock_db = mock_db_with_currencies(&[("Coin1", 1 << 31, true), ("Coin2", 1 << 32, true)]);
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let convert = |amount: u64, from: &str, to: &str| {
+        call_method(
+            &service,
+            &ledger_info,
+            "convert_amount",
+            vec![
+                serde_json::json!(amount),
+                serde_json::json!(from),
+                serde_json::json!(to),
+            ],
+        )
+    };
+
+    assert_eq!(convert(4_000_000, "Coin1", "Coin2").unwrap(), 2_000_000);
+    assert_eq!(convert(2_000_000, "Coin2", "Coin1").unwrap(), 4_000_000);
+    // amounts are rounded down to the smallest unit
+    assert_eq!(convert(3, "Coin1", "Coin2").unwrap(), 1);
+
+    let err = convert(u64::max_value(), "Coin2", "Coin1")
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.as_invalid_param().unwrap().index, 0);
+
+    let err = convert(1, "Coin1", "Coin3")
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    let invalid_param = err.as_invalid_param().unwrap();
+    assert_eq!(invalid_param.index, 2);
+    assert_eq!(invalid_param.name, "to_currency");
+}
+
+#[test]
+fn test_warmup() {
+    let warmup = |mock_db: MockLibraDB| {
+        let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+        Runtime::new().unwrap().block_on(service.warmup())
+    };
+
+    // warming up reads the registered currencies
+    let mut without_currencies = mock_db();
+    without_currencies.account_state_with_proof[0].blob = None;
+    assert!(warmup(without_currencies).is_err());
+
+    let mut with_currencies = mock_db();
+    let mut account_state = AccountState::default();
+    account_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&Vec::<Identifier>::new()).unwrap(),
+    );
+    with_currencies.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&account_state).unwrap());
+    assert!(warmup(with_currencies).is_ok());
+}
+
+#[test]
+fn test_get_account_currencies() {
+    let mut mock_db = mock_db();
+    let codes = [
+        Identifier::new("Coin1").unwrap(),
+        Identifier::new("Coin2").unwrap(),
+    ];
+    let mut config_state = AccountState::default();
+    config_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&codes.to_vec()).unwrap(),
+    );
+    mock_db.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&config_state).unwrap());
+
+    // a child VASP holding balances in both currencies
+    let address = AccountAddress::random();
+    let account = AccountResource::new(
+        0,
+        vec![],
+        None,
+        None,
+        EventHandle::new(EventKey::new_from_address(&address, 0), 0),
+        EventHandle::new(EventKey::new_from_address(&address, 1), 0),
+    );
+    let mut account_state = AccountState::default();
+    account_state.insert(
+        AccountResource::resource_path(),
+        lcs::to_bytes(&account).unwrap(),
+    );
+    account_state.insert(
+        ChildVASP::resource_path(),
+        lcs::to_bytes(&AccountAddress::random()).unwrap(),
+    );
+    account_state.insert(FreezingBit::resource_path(), lcs::to_bytes(&false).unwrap());
+    for (index, code) in codes.iter().enumerate() {
+        account_state.insert(
+            BalanceResource::access_path_for(type_tag_for_currency_code(code.clone())),
+            lcs::to_bytes(&BalanceResource::new(index as u64 + 10)).unwrap(),
+        );
+    }
+    mock_db
+        .all_accounts
+        .insert(address, AccountStateBlob::try_from(&account_state).unwrap());
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |currencies: serde_json::Value| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_account",
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::Value::Null,
+                currencies,
+            ],
+        )
+    };
+    let balances = |currencies: serde_json::Value| {
+        let account: AccountView = serde_json::from_value(call(currencies).unwrap()).unwrap();
+        account
+            .balances
+            .iter()
+            .map(|balance| (balance.currency.clone(), balance.amount))
+            .collect::<Vec<_>>()
+    };
+
+    // all registered currencies by default
+    assert_eq!(
+        balances(serde_json::Value::Null),
+        vec![("Coin1".to_string(), 10), ("Coin2".to_string(), 11)]
+    );
+    assert_eq!(
+        balances(serde_json::json!(["Coin2"])),
+        vec![("Coin2".to_string(), 11)]
+    );
+    assert!(balances(serde_json::json!([])).is_empty());
+
+    let err = call(serde_json::json!(["Coin2", "Coin3"]))
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.as_invalid_param().unwrap().index, 2);
+}
+
+#[test]
+fn test_get_account_verify() {
+    let mut mock_db = mock_db();
+    let mut config_state = AccountState::default();
+    config_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&vec![Identifier::new("Coin1").unwrap()]).unwrap(),
+    );
+    mock_db.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&config_state).unwrap());
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let address = AccountAddress::random();
+    let call = |verify: serde_json::Value| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_account",
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::Value::Null,
+                serde_json::Value::Null,
+                verify,
+            ],
+        )
+    };
+
+    // proofs are only read and verified when asked to
+    assert_eq!(
+        call(serde_json::Value::Null).unwrap(),
+        serde_json::Value::Null
+    );
+    assert_eq!(
+        call(serde_json::json!(false)).unwrap(),
+        serde_json::Value::Null
+    );
+    // the mock storage's proofs don't match its ledger info
+    let err = call(serde_json::json!(true)).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("account state failed verification"));
+
+    let err = call(serde_json::json!("yes"))
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.as_invalid_param().unwrap().index, 3);
+}
+
+#[test]
+fn test_get_events_by_type() {
+    let mut mock_db = mock_db();
+    let expected_hash = mock_db.all_txns[1].0.hash().to_hex();
+    let event_type = "0x1::LibraAccount::SentPaymentEvent";
+    let event = ContractEvent::new(
+        EventKey::new_from_address(&AccountAddress::random(), 0),
+        0,
+        parse_type_tags(event_type).unwrap().remove(0),
+        b"event_data".to_vec(),
+    );
+    mock_db.events.insert(0, (1, event));
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_events_by_type = |end_version: u64, limit: u64| {
+        let request = JsonRpcRequest {
+            params: vec![
+                serde_json::json!(event_type),
+                serde_json::json!(0),
+                serde_json::json!(end_version),
+                serde_json::json!(limit),
+            ],
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_events_by_type")
+            .unwrap()
+            .handler;
+        Runtime::new()
+            .unwrap()
+            .block_on(handler(service.clone(), request))
+    };
+
+    let result = get_events_by_type(10, 10).unwrap();
+    let events: Vec<EventView> = serde_json::from_value(result).unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].transaction_version, 1);
+    assert_eq!(events[0].event_index, Some(0));
+    assert_eq!(events[0].transaction_hash, Some(expected_hash));
+
+    // the scanned version range is capped
+    assert!(get_events_by_type(1000, 10).is_err());
+
+    // as is the number of events returned
+    let err = get_events_by_type(10, 1001)
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    assert_eq!(err.as_invalid_limit().unwrap().max_limit, 1000);
+}
+
+#[test]
+fn test_get_mempool_transactions_for_account() {
+    let (mp_sender, mut mp_events) = channel(1);
+    let mock_db = Arc::new(mock_db());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = JsonRpcService::new(
+        mock_db.clone(),
+        mp_sender,
+        RoleType::Validator,
+        ChainId::test(),
+        None,
+        &RpcConfig::default(),
+    );
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let sender = AccountAddress::random();
+    let pending: Vec<_> = (0..3)
+        .map(|seq| get_test_signed_txn(sender, seq, &privkey, privkey.public_key(), None))
+        .collect();
+
+    // future that mocks shared mempool, holding the pending transactions of the sender
+    let mut runtime = Runtime::new().unwrap();
+    runtime.spawn(async move {
+        while let Some(MempoolClientRequest::GetAccountTransactions(address, limit, cb)) =
+            mp_events.next().await
+        {
+            let txns = if address == sender {
+                pending.iter().take(limit).cloned().collect()
+            } else {
+                vec![]
+            };
+            cb.send(txns).unwrap();
+        }
+    });
+
+    let mut get_mempool_transactions = |params: Vec<serde_json::Value>| {
+        let request = JsonRpcRequest {
+            params,
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_mempool_transactions_for_account")
+            .unwrap()
+            .handler;
+        runtime.block_on(handler(service.clone(), request))
+    };
+
+    let result = get_mempool_transactions(vec![serde_json::json!(sender.to_string())]).unwrap();
+    let txns: Vec<TransactionView> = serde_json::from_value(result).unwrap();
+    assert_eq!(txns.len(), 3);
+    for (seq, txn) in txns.iter().enumerate() {
+        assert_eq!(txn.vm_status, VMStatusView::Pending);
+        assert_eq!(txn.version, 0);
+        match &txn.transaction {
+            TransactionDataView::UserTransaction {
+                sequence_number, ..
+            } => assert_eq!(*sequence_number, seq as u64),
+            _ => panic!("expected user transaction"),
+        }
+    }
+
+    let result = get_mempool_transactions(vec![
+        serde_json::json!(sender.to_string()),
+        serde_json::json!(2),
+    ])
+    .unwrap();
+    let txns: Vec<TransactionView> = serde_json::from_value(result).unwrap();
+    assert_eq!(txns.len(), 2);
+
+    let other = AccountAddress::random();
+    let result = get_mempool_transactions(vec![serde_json::json!(other.to_string())]).unwrap();
+    assert_eq!(result, serde_json::json!([]));
+
+    // the number of transactions is capped
+    assert!(get_mempool_transactions(vec![
+        serde_json::json!(sender.to_string()),
+        serde_json::json!(1000),
+    ])
+    .is_err());
+
+    // an unreachable mempool is reported as such
+    let service = test_service(mock_db, &RpcConfig::default());
+    let err = call_method(
+        &service,
+        &ledger_info,
+        "get_mempool_transactions_for_account",
+        vec![serde_json::json!(sender.to_string())],
+    )
+    .unwrap_err()
+    .downcast::<JsonRpcError>()
+    .unwrap();
+    assert_eq!(err.code, ServerCode::MempoolUnavailable as i16);
+}
+
+#[test]
+fn test_event_view_type_tag() {
+    let type_tag = parse_type_tags("0x1::LibraAccount::Balance<0x1::Coin1::Coin1>")
+        .unwrap()
+        .remove(0);
+    let event = ContractEvent::new(
+        EventKey::new_from_address(&AccountAddress::random(), 0),
+        0,
+        type_tag.clone(),
+        b"event_data".to_vec(),
+    );
+    let view = EventView::from((0, event));
+    assert_eq!(
+        view.type_tag.as_deref(),
+        Some(
+            "0x00000000000000000000000000000001::LibraAccount::Balance<\
+             0x00000000000000000000000000000001::Coin1::Coin1>"
+        )
+    );
+    assert_eq!(
+        parse_type_tags(&view.type_tag.unwrap()).unwrap(),
+        vec![type_tag]
+    );
+}
+
+#[test]
+fn test_get_events_count_unknown_account() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let event_key = EventKey::new_from_address(&AccountAddress::random(), 0);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "get_events_count".to_string(),
+        vec![serde_json::json!(hex::encode(event_key.as_bytes()))],
+    );
+
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(result, serde_json::json!(0));
+}
+
+#[test]
+fn test_get_events_count() {
+    let mut mock_db = mock_db();
+    let address = AccountAddress::random();
+    let sent_key = EventKey::new_from_address(&address, 0);
+    let account = AccountResource::new(
+        0,
+        vec![],
+        None,
+        None,
+        EventHandle::new(sent_key, 7),
+        EventHandle::new(EventKey::new_from_address(&address, 1), 0),
+    );
+    let mut account_state = AccountState::default();
+    account_state.insert(
+        AccountResource::resource_path(),
+        lcs::to_bytes(&account).unwrap(),
+    );
+    mock_db
+        .all_accounts
+        .insert(address, AccountStateBlob::try_from(&account_state).unwrap());
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_events_count = |key: EventKey| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_events_count",
+            vec![serde_json::json!(hex::encode(key.as_bytes()))],
+        )
+    };
+
+    assert_eq!(get_events_count(sent_key).unwrap(), 7);
+
+    // streams of other resources of the account can't be counted
+    let err = get_events_count(EventKey::new_from_address(&address, 2))
+        .unwrap_err()
+        .downcast::<JsonRpcError>()
+        .unwrap();
+    let invalid_param = err.as_invalid_param().unwrap();
+    assert_eq!(invalid_param.index, 0);
+    assert_eq!(invalid_param.name, "key");
+}
+
+#[test]
+fn test_get_transactions() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let version = mock_db.get_latest_version().unwrap();
+    let page = 800usize;
+
+    for base_version in (0..version)
+        .map(u64::from)
+        .take(page)
+        .collect::<Vec<_>>()
+        .into_iter()
+    {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_get_transactions_request(base_version, page as u64, true);
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+        let txns = TransactionView::vec_from_response(result).unwrap();
+
+        for (i, view) in txns.iter().enumerate() {
+            let version = base_version + i as u64;
+            assert_eq!(view.version, version);
+            let (tx, status) = &mock_db.all_txns[version as usize];
+            assert_eq!(view.hash, tx.hash().to_hex());
+            assert_eq!(view.bytes, lcs::to_bytes(tx).unwrap().len() as u64);
+            assert_eq!(
+                view.gas_currency,
+                tx.as_signed_user_txn()
+                    .ok()
+                    .map(|txn| txn.gas_currency_code().to_string())
+            );
+
+            // Check we returned correct events
+            let expected_events = mock_db
+                .events
+                .iter()
+                .filter(|(v, _)| *v == view.version)
+                .map(|(_, e)| e)
+                .collect::<Vec<_>>();
+
+            assert_eq!(expected_events.len(), view.events.len());
+            assert_eq!(VMStatusView::from(status), view.vm_status);
+
+            for (i, event_view) in view.events.iter().enumerate() {
+                let expected_event = expected_events.get(i).expect("Expected event didn't find");
+                assert_eq!(event_view.sequence_number, expected_event.sequence_number());
+                assert_eq!(event_view.transaction_version, version);
+                assert_eq!(event_view.event_index, Some(i as u64));
+                assert_eq!(event_view.transaction_hash, Some(view.hash.clone()));
+                assert_eq!(
+                    event_view.key.0,
+                    BytesView::from(expected_event.key().as_bytes()).0
+                );
+                // TODO: check event_data
+            }
+
+            match tx {
+                Transaction::BlockMetadata(t) => match view.transaction {
+                    TransactionDataView::BlockMetadata { timestamp_usecs } => {
+                        assert_eq!(t.clone().into_inner().unwrap().1, timestamp_usecs);
+                    }
+                    _ => panic!("Returned value doesn't match!"),
+                },
+                Transaction::WaypointWriteSet(_) => match view.transaction {
+                    TransactionDataView::WriteSet { .. } => {}
+                    _ => panic!("Returned value doesn't match!"),
+                },
+                Transaction::UserTransaction(t) => match &view.transaction {
+                    TransactionDataView::UserTransaction {
+                        sender,
+                        script_hash,
+                        chain_id,
+                        ..
+                    } => {
+                        assert_eq!(&t.sender().to_string(), sender);
+                        assert_eq!(&t.chain_id().id(), chain_id);
+                        // TODO: verify every field
+                        if let TransactionPayload::Script(s) = t.payload() {
+                            assert_eq!(script_hash, &HashValue::sha3_256_of(s.code()).to_hex());
+                        }
+                    }
+                    _ => panic!("Returned value doesn't match!"),
+                },
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_transactions_events_cap() {
+    let mut mock_db = mock_db();
+    // three events emitted by the transaction at version 1, two by the one at version 2
+    mock_db.events.clear();
+    for (version, count) in &[(1, 3), (2, 2)] {
+        for sequence_number in 0..*count {
+            let event =
+                ContractEvent::new(EventKey::random(), sequence_number, TypeTag::Bool, vec![]);
+            mock_db.events.push((*version, event));
+        }
+    }
+    let mock_db = Arc::new(mock_db);
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let get_transactions = |config: &RpcConfig| {
+        let service = test_service(mock_db.clone(), config);
+        let request = JsonRpcRequest {
+            params: vec![
+                serde_json::json!(1),
+                serde_json::json!(2),
+                serde_json::json!(true),
+            ],
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry().remove("get_transactions").unwrap().handler;
+        let result = Runtime::new()
+            .unwrap()
+            .block_on(handler(service, request))
+            .unwrap();
+        let txs: Vec<TransactionView> = serde_json::from_value(result).unwrap();
+        txs.iter()
+            .map(|tx| (tx.events.len(), tx.events_truncated))
+            .collect::<Vec<_>>()
+    };
+
+    let mut config = RpcConfig::default();
+    config.max_events_per_transaction = 0;
+    config.max_transactions_events = 0;
+    assert_eq!(get_transactions(&config), vec![(3, false), (2, false)]);
+
+    // the first transaction is cut to the per transaction cap, the second to what's left overall
+    config.max_events_per_transaction = 2;
+    config.max_transactions_events = 3;
+    assert_eq!(get_transactions(&config), vec![(2, true), (1, true)]);
+}
+
+#[test]
+fn test_get_transaction_infos() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str| {
+        call_method(
+            &service,
+            &ledger_info,
+            method,
+            vec![serde_json::json!(1), serde_json::json!(3)],
+        )
+        .unwrap()
+    };
+
+    // the infos match the transactions of the same range
+    let infos: Vec<TransactionInfoView> =
+        serde_json::from_value(call("get_transaction_infos")).unwrap();
+    let txs: Vec<TransactionView> = serde_json::from_value(call("get_transactions")).unwrap();
+    assert_eq!(infos.len(), 3);
+    assert_eq!(infos.len(), txs.len());
+    for (info, tx) in infos.iter().zip(&txs) {
+        assert_eq!(info.version, tx.version);
+        assert_eq!(info.vm_status, tx.vm_status);
+        assert_eq!(info.gas_used, tx.gas_used);
+        // the mock storage's transaction infos hold zero hashes
+        assert_eq!(info.state_root_hash, HashValue::zero().to_hex());
+    }
+}
+
+#[test]
+fn test_get_transactions_inconsistent_storage() {
+    let mut mock_db = mock_db();
+    mock_db.drop_last_transaction_info = true;
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str| {
+        call_method(
+            &service,
+            &ledger_info,
+            method,
+            vec![serde_json::json!(1), serde_json::json!(3)],
+        )
+    };
+
+    // transactions missing their info aren't silently left out of the result
+    for method in &["get_transactions", "get_transaction_infos"] {
+        let err = call(method).unwrap_err();
+        assert!(err.downcast_ref::<JsonRpcError>().is_none());
+        assert_eq!(
+            err.to_string(),
+            "storage returned 2 transaction infos for 3 transactions from version 1"
+        );
+    }
+}
+
+#[test]
+fn test_get_transactions_by_time() {
+    let mut mock_db = mock_db();
+    // blocks of two transactions, 10 microseconds apart
+    let num_txns = mock_db.all_txns.len() as u64;
+    mock_db.version = num_txns - 1;
+    mock_db.timestamps = (0..num_txns).map(|version| version / 2 * 10).collect();
+    let timestamps = mock_db.timestamps.clone();
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_versions = |start_time: u64, end_time: u64, limit: u64| {
+        let request = JsonRpcRequest {
+            params: vec![
+                serde_json::json!(start_time),
+                serde_json::json!(end_time),
+                serde_json::json!(limit),
+            ],
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_transactions_by_time")
+            .unwrap()
+            .handler;
+        Runtime::new()
+            .unwrap()
+            .block_on(handler(service.clone(), request))
+            .map(|transactions| {
+                transactions
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|transaction| transaction["version"].as_u64().unwrap())
+                    .collect::<Vec<_>>()
+            })
+    };
+    let expected = |start_time: u64, end_time: u64, limit: usize| {
+        (0..num_txns)
+            .filter(|version| {
+                let timestamp = timestamps[*version as usize];
+                start_time <= timestamp && timestamp < end_time
+            })
+            .take(limit)
+            .collect::<Vec<_>>()
+    };
+
+    let last_timestamp = *timestamps.last().unwrap();
+    for (start_time, end_time, limit) in &[
+        (0, 10, 100),
+        (5, 25, 100),
+        (10, 20, 1),
+        (0, last_timestamp + 1, 1000),
+        (last_timestamp + 1, last_timestamp + 100, 100),
+        (10, 10, 100),
+    ] {
+        assert_eq!(
+            get_versions(*start_time, *end_time, *limit).unwrap(),
+            expected(*start_time, *end_time, *limit as usize)
+        );
+    }
+
+    // the window can't end before it starts, and the limit is capped
+    assert!(get_versions(20, 10, 100).is_err());
+    assert!(get_versions(0, 10, 0).is_err());
+    assert!(get_versions(0, 10, 1001).is_err());
+}
+
+#[test]
+fn test_get_transactions_with_status_filter() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let limit = std::cmp::min(mock_db.get_latest_version().unwrap(), 1000);
+    for (filter, executed) in &[("executed", true), ("failed", false)] {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_request(
+            "get_transactions".to_string(),
+            vec![
+                serde_json::json!(0),
+                serde_json::json!(limit),
+                serde_json::json!(true),
+                serde_json::json!(filter),
+            ],
+        );
+        let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+        let txns = TransactionView::vec_from_response(result).unwrap();
+
+        let expected_versions: Vec<u64> = mock_db
+            .all_txns
+            .iter()
+            .take(limit as usize)
+            .enumerate()
+            .filter(|(_, (_, status))| (status == &KeptVMStatus::Executed) == *executed)
+            .map(|(v, _)| v as u64)
+            .collect();
+        let versions: Vec<u64> = txns.iter().map(|view| view.version).collect();
+        assert_eq!(versions, expected_versions);
+
+        for view in txns {
+            let (tx, status) = &mock_db.all_txns[view.version as usize];
+            assert_eq!(view.hash, tx.hash().to_hex());
+            assert_eq!(VMStatusView::from(status), view.vm_status);
+            for event_view in view.events {
+                assert_eq!(event_view.transaction_version, view.version);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_transactions_include_events_default() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let (version, _) = mock_db.events.first().cloned().expect("mock db has events");
+    let mut config = RpcConfig::default();
+    config.include_events_by_default = true;
+    let service = test_service(Arc::new(mock_db), &config);
+    let handler = build_registry().remove("get_transactions").unwrap().handler;
+    let mut runtime = Runtime::new().unwrap();
+    // returns the number of events of the transaction, given the include_events param if any
+    let mut get_events = |include_events: Option<serde_json::Value>| {
+        let mut params = vec![serde_json::json!(version), serde_json::json!(1)];
+        params.extend(include_events);
+        let request = JsonRpcRequest {
+            params,
+            ledger_info: ledger_info.clone(),
+        };
+        let result = runtime.block_on(handler(service.clone(), request)).unwrap();
+        let txns: Vec<TransactionView> = serde_json::from_value(result).unwrap();
+        txns[0].events.len()
+    };
+
+    // absent or null include_events defaults to the server config
+    let events = get_events(None);
+    assert!(events > 0);
+    assert_eq!(get_events(Some(serde_json::Value::Null)), events);
+    assert_eq!(get_events(Some(serde_json::json!(true))), events);
+
+    // an explicit include_events takes precedence
+    assert_eq!(get_events(Some(serde_json::json!(false))), 0);
+}
+
+#[test]
+fn test_get_treasury_compliance_info_missing_account() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let request = JsonRpcRequest {
+        params: vec![],
+        ledger_info,
+    };
+
+    // the mock chain has no treasury compliance account
+    let handler = build_registry()
+        .remove("get_treasury_compliance_info")
+        .unwrap()
+        .handler;
+    let err = Runtime::new()
+        .unwrap()
+        .block_on(handler(service, request))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "treasury compliance account {} not found",
+            treasury_compliance_account_address()
+        )
+    );
+}
+
+#[test]
+fn test_get_account_creation_transaction() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    // neither an unknown account nor one without creation transaction in history has a creation
+    // transaction
+    for address in &[
+        AccountAddress::random(),
+        get_first_account_from_mock_db(&mock_db),
+    ] {
+        let mut batch = JsonRpcBatch::default();
+        batch.add_request(
+            "get_account_creation_transaction".to_string(),
+            vec![serde_json::json!(address.to_string())],
+        );
+        let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+        assert_eq!(result, serde_json::Value::Null);
+    }
+}
+
+#[test]
+fn test_get_transactions_response_too_large() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mut config = RpcConfig::default();
+    config.max_response_size = 100;
+    let service = test_service(Arc::new(mock_db), &config);
+    let params = serde_json::json!([0, 10, true]);
+    let request = JsonRpcRequest {
+        params: params.as_array().unwrap().clone(),
+        ledger_info: ledger_info.clone(),
+    };
+
+    let handler = build_registry().remove("get_transactions").unwrap().handler;
+    let err = Runtime::new()
+        .unwrap()
+        .block_on(handler(service.clone(), request))
+        .unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, ServerCode::ResponseTooLarge as i16);
+    let data = err.as_response_too_large().unwrap();
+    assert!(data.size > data.max_size);
+    assert_eq!(data.max_size, 100);
+
+    // rejected responses are counted per method
+    let oversized = || {
+        counters::OVERSIZED_RESPONSES
+            .with_label_values(&["get_transactions"])
+            .get()
+    };
+    let before = oversized();
+    let registry = build_registry();
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let response = Runtime::new().unwrap().block_on(rpc_request_handler(
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "get_transactions",
+            "params": params,
+            "id": 1
+        }),
+        service,
+        Arc::new(registry),
+        Arc::new(InflightRequests::new(0)),
+        auth,
+        None,
+        ledger_info,
+    ));
+    assert_eq!(
+        response["error"]["code"],
+        ServerCode::ResponseTooLarge as i16
+    );
+    assert!(oversized() > before);
+}
+
+#[test]
+fn test_numeric_string_params() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_transactions = |start_version: serde_json::Value, limit: serde_json::Value| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_transactions",
+            vec![start_version, limit],
+        )
+    };
+
+    // u64 params can be given as numeric strings
+    let expected = get_transactions(serde_json::json!(0), serde_json::json!(5)).unwrap();
+    let result = get_transactions(serde_json::json!("0"), serde_json::json!("5")).unwrap();
+    assert_eq!(result, expected);
+
+    for (start_version, limit) in &[
+        (serde_json::json!("zero"), serde_json::json!(5)),
+        (serde_json::json!(0), serde_json::json!("-5")),
+        (serde_json::json!(0), serde_json::json!(1.5)),
+    ] {
+        let err = get_transactions(start_version.clone(), limit.clone()).unwrap_err();
+        assert!(err.to_string().contains("must be an unsigned integer"));
+    }
+}
+
+#[test]
+fn test_invalid_param() {
+    let mock_db = mock_db();
+    let address = get_first_account_from_mock_db(&mock_db);
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str, params: Vec<serde_json::Value>| {
+        let err = call_method(&service, &ledger_info, method, params).unwrap_err();
+        err.downcast::<JsonRpcError>().unwrap()
+    };
+
+    // the error gives the position and name of the param, whatever its type
+    for (method, params, index, name) in &[
+        (
+            "get_transactions",
+            vec![serde_json::json!(0), serde_json::json!("ten")],
+            1,
+            "limit",
+        ),
+        (
+            "get_account_transaction",
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::json!(0),
+                serde_json::json!("yes"),
+            ],
+            2,
+            "include_events",
+        ),
+        ("get_events_count", vec![serde_json::json!(1)], 0, "key"),
+    ] {
+        let err = call(method, params.clone());
+        assert_eq!(err.code, -32602);
+        assert!(err
+            .message
+            .starts_with(&format!("Invalid params: param {} ({})", index, name)));
+        let data = err.as_invalid_param().unwrap();
+        assert_eq!(data.index, *index);
+        assert_eq!(data.name, *name);
+    }
+}
+
+#[test]
+fn test_omitted_empty_and_null_params() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let config = RpcConfig::default();
+    let service = test_service(Arc::new(mock_db), &config);
+    let registry = build_registry();
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let registry = Arc::new(registry);
+    // params are omitted when None
+    let call = |method: &str, params: Option<serde_json::Value>| {
+        let mut request = serde_json::json!({"jsonrpc": "2.0", "method": method, "id": 1});
+        if let Some(params) = params {
+            request["params"] = params;
+        }
+        Runtime::new().unwrap().block_on(rpc_request_handler(
+            request,
+            service.clone(),
+            registry.clone(),
+            Arc::new(InflightRequests::new(0)),
+            auth.clone(),
+            None,
+            ledger_info.clone(),
+        ))
+    };
+    let error_message = |response: &serde_json::Value| {
+        assert_eq!(response["error"]["code"], -32602);
+        response["error"]["message"].as_str().unwrap().to_string()
+    };
+
+    // optional params default whether omitted, absent or null
+    let expected = call("get_metadata", Some(serde_json::json!([])))["result"].clone();
+    assert!(expected.is_object());
+    for params in &[None, Some(serde_json::json!([null]))] {
+        assert_eq!(call("get_metadata", params.clone())["result"], expected);
+    }
+    let address = AccountAddress::random().to_string();
+    let response = call("get_account", Some(serde_json::json!([address, null])));
+    assert!(response.get("error").is_none());
+
+    // missing required params are reported as a wrong number of params
+    for params in &[None, Some(serde_json::json!([]))] {
+        let message = error_message(&call("get_transactions", params.clone()));
+        assert!(!message.starts_with("Invalid params: param"));
+    }
+    // required params given as null are reported as such
+    assert_eq!(
+        error_message(&call(
+            "get_transactions",
+            Some(serde_json::json!([null, 10]))
+        )),
+        "Invalid params: param 0 (start_version) is required, given null"
+    );
+    assert_eq!(
+        error_message(&call("get_account", Some(serde_json::json!([null])))),
+        "Invalid params: param 0 (account) is required, given null"
+    );
+
+    // params must be an array when given
+    error_message(&call("get_metadata", Some(serde_json::Value::Null)));
+}
+
+#[test]
+fn test_full_node_method_limits() {
+    let mock_db = Arc::new(mock_db());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mut config = RpcConfig::default();
+    config.full_node_method_limits.insert(
+        "get_transactions".to_string(),
+        MethodLimits {
+            max_items: 10,
+            max_concurrent: 1,
+        },
+    );
+    let registry = build_registry();
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let registry = Arc::new(registry);
+    let call = |role: RoleType, limit: serde_json::Value| {
+        let service = JsonRpcService::new(
+            mock_db.clone(),
+            channel(1).0,
+            role,
+            ChainId::test(),
+            None,
+            &config,
+        );
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "get_transactions",
+            "params": [0, limit, false],
+            "id": 1,
+        });
+        Runtime::new().unwrap().block_on(rpc_request_handler(
+            request,
+            service,
+            registry.clone(),
+            Arc::new(InflightRequests::new(0)),
+            auth.clone(),
+            None,
+            ledger_info.clone(),
+        ))
+    };
+
+    // validators aren't bound by the limits of full nodes
+    assert!(call(RoleType::Validator, serde_json::json!(20))
+        .get("error")
+        .is_none());
+    assert!(call(RoleType::FullNode, serde_json::json!(10))
+        .get("error")
+        .is_none());
+    for limit in vec![serde_json::json!(20), serde_json::json!("20")] {
+        let response = call(RoleType::FullNode, limit);
+        assert_eq!(response["error"]["code"], -32602);
+        assert_eq!(response["error"]["data"]["InvalidLimit"]["max_limit"], 10);
+    }
+
+    // calls past the concurrency cap are rejected until earlier ones are answered
+    assert!(MethodLimiter::new(&config, RoleType::Validator).is_none());
+    let limiter = MethodLimiter::new(&config, RoleType::FullNode).unwrap();
+    let params = vec![serde_json::json!(0), serde_json::json!(1)];
+    let guard = limiter.check("v2.get_transactions", &params).unwrap();
+    assert!(guard.is_some());
+    let (err, _) = limiter.check("get_transactions", &params).err().unwrap();
+    assert_eq!(err.message, JsonRpcError::server_overloaded().message);
+    assert!(limiter.check("get_events", &params).unwrap().is_none());
+    drop(guard);
+    assert!(limiter.check("get_transactions", &params).is_ok());
+}
+
+#[test]
+fn test_limit_validation() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str, params: Vec<serde_json::Value>| {
+        call_method(&service, &ledger_info, method, params)
+            .unwrap_err()
+            .downcast::<JsonRpcError>()
+            .unwrap()
+    };
+    let event_key = serde_json::json!(hex::encode(EventKey::random().as_bytes()));
+
+    for (limit, message) in &[
+        (0, "Invalid params: limit must be greater than 0, got 0"),
+        (1001, "Invalid params: limit must be <= 1000, got 1001"),
+    ] {
+        let limit = serde_json::json!(limit);
+        let errors = vec![
+            call(
+                "get_transactions",
+                vec![serde_json::json!(0), limit.clone()],
+            ),
+            call(
+                "get_events",
+                vec![event_key.clone(), serde_json::json!(0), limit.clone()],
+            ),
+        ];
+        for err in errors {
+            assert_eq!(err.code, -32602);
+            assert_eq!(&err.message, message);
+            let data = err.as_invalid_limit().unwrap();
+            assert_eq!(serde_json::json!(data.limit), limit);
+            assert_eq!(data.max_limit, 1000);
+        }
+    }
+}
+
+#[test]
+fn test_u64_param_bounds() {
+    let ledger_info = mock_db().get_latest_ledger_info().unwrap();
+    let request = |param: serde_json::Value| JsonRpcRequest {
+        params: vec![param],
+        ledger_info: ledger_info.clone(),
+    };
+
+    for param in &[
+        serde_json::json!(u64::MAX),
+        serde_json::json!(u64::MAX.to_string()),
+    ] {
+        let version = request(param.clone()).get_u64_param(0, "version").unwrap();
+        assert_eq!(version, u64::MAX);
+    }
+
+    // out of range, negative and fractional values fail with an error naming the param
+    for param in &[
+        serde_json::json!(u64::MAX as f64 * 2.0),
+        serde_json::json!("18446744073709551616"),
+        serde_json::json!(-1),
+        serde_json::json!("-1"),
+        serde_json::json!(1.5),
+        serde_json::json!("1.5"),
+        serde_json::json!(true),
+    ] {
+        let err = request(param.clone())
+            .get_u64_param(0, "version")
+            .unwrap_err();
+        let err = err.downcast_ref::<JsonRpcError>().unwrap();
+        assert_eq!(err.code, -32602);
+        assert!(err.message.contains("version"));
+        assert!(err.message.contains(&u64::MAX.to_string()));
+    }
+
+    let versions = request(serde_json::json!([0, "1", u64::MAX]))
+        .get_u64_array_param(0, "versions")
+        .unwrap();
+    assert_eq!(versions, vec![0, 1, u64::MAX]);
+    assert!(request(serde_json::json!([0, -1]))
+        .get_u64_array_param(0, "versions")
+        .is_err());
+}
+
+#[test]
+fn test_reply_serialization() {
+    let mock_db = mock_db();
+    let views: Vec<TransactionView> = mock_db
+        .all_txns
+        .iter()
+        .take(10)
+        .enumerate()
+        .map(|(version, (txn, status))| TransactionView {
+            version: version as u64,
+            hash: txn.hash().to_hex(),
+            bytes: 0,
+            gas_currency: None,
+            transaction: txn.clone().into(),
+            events: vec![],
+            vm_status: status.into(),
+            gas_used: 0,
+            proof: None,
+            events_truncated: false,
+        })
+        .collect();
+    let value = serde_json::to_value(&views).unwrap();
+    let expected = serde_json::to_vec(&value).unwrap();
+
+    assert_eq!(serialized_size(&views).unwrap(), expected.len());
+
+    // replies are well formed, including when the buffer of the thread is reused
+    for _ in 0..2 {
+        let reply = json_reply(&value, false);
+        assert_eq!(reply.status(), 200);
+        assert_eq!(reply.headers()["content-type"], "application/json");
+    }
+
+    // pretty replies only differ by their whitespace
+    let body = |pretty: bool| {
+        let body = json_reply(&value, pretty).into_body();
+        Runtime::new()
+            .unwrap()
+            .block_on(warp::hyper::body::to_bytes(body))
+            .unwrap()
+    };
+    let (compact, pretty) = (body(false), body(true));
+    assert_eq!(compact.as_ref(), expected.as_slice());
+    assert!(pretty.len() > compact.len());
+    assert!(pretty.contains(&b'\n'));
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&pretty).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_submit_reserved_sender() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    for sender in &[
+        AccountAddress::new([0u8; AccountAddress::LENGTH]),
+        AccountAddress::from_hex_literal("0x1").unwrap(),
+    ] {
+        let txn = get_test_signed_txn(*sender, 0, &privkey, privkey.public_key(), None);
+        let request = JsonRpcRequest {
+            params: vec![serde_json::json!(hex::encode(lcs::to_bytes(&txn).unwrap()))],
+            ledger_info: ledger_info.clone(),
+        };
+        // rejected before reaching mempool, which isn't running
+        let handler = build_registry().remove("submit").unwrap().handler;
+        let err = Runtime::new()
+            .unwrap()
+            .block_on(handler(service.clone(), request))
+            .unwrap_err()
+            .downcast::<JsonRpcError>()
+            .unwrap();
+        assert!(err.message.contains("reserved sender"));
+        assert_eq!(err.as_invalid_param().unwrap().index, 0);
+    }
+}
+
+#[test]
+fn test_submit_mempool_unavailable() {
+    let mock_db = Arc::new(mock_db());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let txn = get_test_signed_txn(
+        AccountAddress::random(),
+        0,
+        &privkey,
+        privkey.public_key(),
+        None,
+    );
+    let submit = |mempool_sender, submit_batch_window_ms| {
+        let mut config = RpcConfig::default();
+        config.submit_batch_window_ms = submit_batch_window_ms;
+        let request = JsonRpcRequest {
+            params: vec![serde_json::json!(hex::encode(lcs::to_bytes(&txn).unwrap()))],
+            ledger_info: ledger_info.clone(),
+        };
+        let service = JsonRpcService::new(
+            mock_db.clone(),
+            mempool_sender,
+            RoleType::Validator,
+            ChainId::test(),
+            None,
+            &config,
+        );
+        let handler = build_registry().remove("submit").unwrap().handler;
+        handler(service, request)
+    };
+    let mut runtime = Runtime::new().unwrap();
+
+    // mempool is gone, whether the transaction is sent on its own or batched
+    for submit_batch_window_ms in &[0, 10] {
+        let (mempool_sender, mempool_receiver) = channel(1);
+        drop(mempool_receiver);
+        let err = runtime
+            .block_on(submit(mempool_sender, *submit_batch_window_ms))
+            .unwrap_err()
+            .downcast::<JsonRpcError>()
+            .unwrap();
+        assert_eq!(err.code, ServerCode::MempoolUnavailable as i16);
+    }
+
+    // mempool receives the transaction but drops it without answering, so it may have been
+    // accepted and the client is told to poll for it
+    let (mempool_sender, mut mempool_receiver) = channel(1);
+    let mempool = async move {
+        let request = mempool_receiver.next().await;
+        drop(request);
+    };
+    let (result, _) = runtime.block_on(futures::future::join(submit(mempool_sender, 0), mempool));
+    let err = result.unwrap_err().downcast::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, ServerCode::SubmissionStatusUnknown as i16);
+    let hash = Transaction::UserTransaction(txn.clone()).hash().to_hex();
+    assert!(err.message.contains(&hash));
+}
+
+#[test]
+fn test_submit_batched() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let (mempool_sender, mut mempool_receiver) = channel(16);
+    let mut config = RpcConfig::default();
+    config.submit_batch_window_ms = 50;
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        mempool_sender,
+        RoleType::Validator,
+        ChainId::test(),
+        None,
+        &config,
+    );
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let submit = || {
+        let txn = get_test_signed_txn(
+            AccountAddress::random(),
+            0,
+            &privkey,
+            privkey.public_key(),
+            None,
+        );
+        let request = JsonRpcRequest {
+            params: vec![serde_json::json!(hex::encode(lcs::to_bytes(&txn).unwrap()))],
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry().remove("submit").unwrap().handler;
+        handler(service.clone(), request)
+    };
+    let mut runtime = Runtime::new().unwrap();
+
+    // accepts every transaction, listing the number of transactions of each mempool request
+    let mempool = runtime.spawn(async move {
+        let mut sizes = vec![];
+        for _ in 0..2 {
+            let accepted = (MempoolStatus::new(MempoolStatusCode::Accepted), None);
+            match mempool_receiver.next().await.unwrap() {
+                MempoolClientRequest::SubmitTransaction(_, callback) => {
+                    sizes.push(1);
+                    callback.send(Ok(accepted)).unwrap();
+                }
+                MempoolClientRequest::SubmitTransactions(submissions) => {
+                    sizes.push(submissions.len());
+                    for (_, callback) in submissions {
+                        callback.send(Ok(accepted.clone())).unwrap();
+                    }
+                }
+                _ => panic!("unexpected mempool request"),
+            }
+        }
+        sizes
+    });
+
+    // concurrent submissions are sent together, and each gets its own status back
+    let results = runtime.block_on(futures::future::join_all((0..3).map(|_| submit())));
+    assert!(results.iter().all(|result| result.is_ok()));
+    // a submission alone is sent on its own
+    assert!(runtime.block_on(submit()).is_ok());
+    assert_eq!(runtime.block_on(mempool).unwrap(), vec![3, 1]);
+}
+
+#[test]
+fn test_decode_hex_lcs() {
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let sender = AccountAddress::random();
+    let txn = get_test_signed_txn(sender, 0, &privkey, privkey.public_key(), None);
+    let payload = hex::encode(lcs::to_bytes(&txn).unwrap());
+
+    // decoding works when the buffer of the thread is reused, including for a shorter payload
+    for _ in 0..2 {
+        let decoded: SignedTransaction = decode_hex_lcs(&payload).unwrap();
+        assert_eq!(decoded, txn);
+    }
+    let decoded: u64 = decode_hex_lcs(&hex::encode(lcs::to_bytes(&7u64).unwrap())).unwrap();
+    assert_eq!(decoded, 7);
+
+    for invalid_hex in &["zz", "abc"] {
+        let err = decode_hex_lcs::<SignedTransaction>(invalid_hex).unwrap_err();
+        assert!(err.to_string().starts_with("invalid hex payload"));
+    }
+    let err = decode_hex_lcs::<SignedTransaction>(&payload[..payload.len() - 2]).unwrap_err();
+    assert!(err.to_string().starts_with("invalid LCS payload"));
+}
+
+#[test]
+fn test_get_account_transaction() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    for (acc, blob) in mock_db.all_accounts.iter() {
+        let ar = AccountResource::try_from(blob).unwrap();
+        for seq in 1..ar.sequence_number() {
+            let mut batch = JsonRpcBatch::default();
+            batch.add_get_account_transaction_request(*acc, seq, true);
+
+            let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+            let tx_view = TransactionView::optional_from_response(result)
+                .unwrap()
+                .expect("Transaction didn't exists!");
+
+            let (expected_tx, expected_status) = mock_db
+                .all_txns
+                .iter()
+                .find_map(|(t, status)| {
+                    if let Ok(x) = t.as_signed_user_txn() {
+                        if x.sender() == *acc && x.sequence_number() == seq {
+                            assert_eq!(tx_view.hash, t.hash().to_hex());
+                            return Some((x, status));
+                        }
+                    }
+                    None
+                })
+                .expect("Couldn't find tx");
+
+            // Check we returned correct events
+            let expected_events = mock_db
+                .events
+                .iter()
+                .filter(|(ev, _)| *ev == tx_view.version)
+                .map(|(_, e)| e)
+                .collect::<Vec<_>>();
+
+            assert_eq!(tx_view.events.len(), expected_events.len());
+
+            // check VM status
+            assert_eq!(tx_view.vm_status, VMStatusView::from(expected_status));
+
+            for (i, event_view) in tx_view.events.iter().enumerate() {
+                let expected_event = expected_events.get(i).expect("Expected event didn't find");
+                assert_eq!(event_view.sequence_number, expected_event.sequence_number());
+                assert_eq!(event_view.transaction_version, tx_view.version);
+                assert_eq!(
+                    event_view.key.0,
+                    BytesView::from(expected_event.key().as_bytes()).0
+                );
+                // TODO: check event_data
+            }
+
+            let tx_data_view = tx_view.transaction;
+
+            // Always user transaction
+            match tx_data_view {
+                TransactionDataView::UserTransaction {
+                    sender,
+                    sequence_number,
+                    script_hash,
+                    ..
+                } => {
+                    assert_eq!(acc.to_string(), sender);
+                    assert_eq!(seq, sequence_number);
+
+                    if let TransactionPayload::Script(s) = expected_tx.payload() {
+                        assert_eq!(script_hash, HashValue::sha3_256_of(s.code()).to_hex());
+                    }
+                }
+                _ => panic!("wrong type"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_account_transaction_proof() {
+    let mock_db = mock_db();
+    let (txn, status) = mock_db
+        .all_txns
+        .iter()
+        .find_map(|(txn, status)| {
+            txn.as_signed_user_txn()
+                .ok()
+                .map(|txn| (txn.clone(), status))
+        })
+        .unwrap();
+    let status = status.clone();
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_transaction = |include_proof: Option<bool>| {
+        let mut params = vec![
+            serde_json::json!(txn.sender().to_string()),
+            serde_json::json!(txn.sequence_number()),
+            serde_json::json!(false),
+        ];
+        if let Some(include_proof) = include_proof {
+            params.push(serde_json::json!(include_proof));
+        }
+        let request = JsonRpcRequest {
+            params,
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_account_transaction")
+            .unwrap()
+            .handler;
+        Runtime::new()
+            .unwrap()
+            .block_on(handler(service.clone(), request))
+            .unwrap()
+    };
+
+    // the proof is left out by default
+    for include_proof in &[None, Some(false)] {
+        assert!(get_transaction(*include_proof).get("proof").is_none());
+    }
+
+    let view: TransactionView = serde_json::from_value(get_transaction(Some(true))).unwrap();
+    let proof: TransactionInfoWithProof =
+        lcs::from_bytes(&view.proof.unwrap().into_bytes().unwrap()).unwrap();
+    assert_eq!(proof.transaction_info().status(), &status);
+}
+
+#[test]
+// Check that if version and ledger_version parameters are None, then the server returns the latest
+// known state.
+fn test_get_account_state_with_proof_null_versions() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let account = get_first_account_from_mock_db(&mock_db);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_state_with_proof_request(account, None, None);
+
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+
+    let received_proof = AccountStateWithProofView::from_response(result).unwrap();
+    let expected_proof = get_first_state_proof_from_mock_db(&mock_db);
+
+    // Check latest version returned, when no version specified
+    assert_eq!(received_proof.version, expected_proof.version);
+}
+
+#[test]
+fn test_get_account_state_with_proof_latest() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let account = get_first_account_from_mock_db(&mock_db);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_state_with_proof_latest_request(account);
+    batch.add_get_account_state_with_proof_request(account, None, None);
+
+    let responses = runtime.block_on(client.execute(batch)).unwrap();
+    let mut proofs = responses
+        .into_iter()
+        .map(|response| AccountStateWithProofView::from_response(response.unwrap()).unwrap());
+    let latest = proofs.next().unwrap();
+
+    // the proof is the same as the one of the full method defaulting to the latest versions
+    assert_eq!(latest, proofs.next().unwrap());
+    assert_eq!(
+        latest.version,
+        get_first_state_proof_from_mock_db(&mock_db).version
+    );
+}
+
+#[test]
+fn test_get_account_state_with_proof_raw() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let account = get_first_account_from_mock_db(&mock_db);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request(
+        "get_account_state_with_proof".to_string(),
+        vec![
+            serde_json::json!(account.to_string()),
+            serde_json::json!(0),
+            serde_json::json!(0),
+            serde_json::json!(true),
+        ],
+    );
+
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    let raw = hex::decode(result.as_str().unwrap()).unwrap();
+    let received_proof: AccountStateWithProof = lcs::from_bytes(&raw).unwrap();
+    assert_eq!(received_proof, get_first_state_proof_from_mock_db(&mock_db));
+}
+
+#[test]
+fn test_get_account_state_with_proofs() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let latest_version = ledger_info.ledger_info().version();
+    let address = get_first_account_from_mock_db(&mock_db);
+    let expected_proof = get_first_state_proof_from_mock_db(&mock_db);
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        channel(1).0,
+        RoleType::Validator,
+        ChainId::test(),
+        Some(1),
+        &RpcConfig::default(),
+    );
+    let request = JsonRpcRequest {
+        params: vec![
+            serde_json::json!(address.to_string()),
+            serde_json::json!([latest_version, 0, latest_version + 1, latest_version]),
+            serde_json::json!(latest_version),
+        ],
+        ledger_info: ledger_info.clone(),
+    };
+
+    let handler = build_registry()
+        .remove("get_account_state_with_proofs")
+        .unwrap()
+        .handler;
+    let mut runtime = Runtime::new().unwrap();
+    let result = runtime.block_on(handler(service.clone(), request)).unwrap();
+    let results: Vec<AccountStateWithProofResultView> = serde_json::from_value(result).unwrap();
+    assert_eq!(results.len(), 4);
+    match &results[0] {
+        AccountStateWithProofResultView::Ok(proof) => {
+            assert_eq!(proof.version, expected_proof.version)
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    match &results[1] {
+        AccountStateWithProofResultView::Err { error } => {
+            assert_eq!(error.code, ServerCode::StatePruned as i16)
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+    assert!(matches!(
+        results[2],
+        AccountStateWithProofResultView::Err { .. }
+    ));
+    // repeated versions get the same proof, in the requested order
+    match &results[3] {
+        AccountStateWithProofResultView::Ok(proof) => {
+            assert_eq!(proof.version, expected_proof.version)
+        }
+        result => panic!("unexpected result: {:?}", result),
+    }
+
+    // invalid ledger versions are reported by their param name
+    let request = JsonRpcRequest {
+        params: vec![
+            serde_json::json!(address.to_string()),
+            serde_json::json!([latest_version]),
+            serde_json::json!("x"),
+        ],
+        ledger_info,
+    };
+    let err = runtime.block_on(handler(service, request)).unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert!(err.message.contains("ledger_version"), "{}", err.message);
+}
+
+/// Returns the bytecode of an empty module with the given id, depending on the given modules
+fn module_bytecode(id: &ModuleId, dependencies: &[ModuleId]) -> Vec<u8> {
+    let mut module = empty_module();
+    module.address_identifiers[0] = *id.address();
+    module.identifiers[0] = id.name().to_owned();
+    for dependency in dependencies {
+        module.module_handles.push(ModuleHandle {
+            address: AddressIdentifierIndex(module.address_identifiers.len() as u16),
+            name: IdentifierIndex(module.identifiers.len() as u16),
+        });
+        module.address_identifiers.push(*dependency.address());
+        module.identifiers.push(dependency.name().to_owned());
+    }
+    let mut bytecode = vec![];
+    module.freeze().unwrap().serialize(&mut bytecode).unwrap();
+    bytecode
+}
+
+#[test]
+fn test_get_account_modules_with_deps() {
+    let (a, b) = (AccountAddress::random(), AccountAddress::random());
+    let module_id = |address, name| ModuleId::new(address, Identifier::new(name).unwrap());
+    let (a1, a2, b1, b2) = (
+        module_id(a, "A1"),
+        module_id(a, "A2"),
+        module_id(b, "B1"),
+        module_id(b, "B2"),
+    );
+    let missing = module_id(b, "Missing");
+    let mut mock_db = mock_db();
+    let mut publish = |address, modules: Vec<(&ModuleId, Vec<u8>)>| {
+        let mut account_state = AccountState::default();
+        for (id, bytecode) in modules {
+            account_state.insert(id.access_vector(), bytecode);
+        }
+        mock_db
+            .all_accounts
+            .insert(address, AccountStateBlob::try_from(&account_state).unwrap());
+    };
+    // A1 and B1 depend on each other, A2 depends on a module that isn't published
+    publish(
+        a,
+        vec![
+            (&a1, module_bytecode(&a1, &[b1.clone()])),
+            (&a2, module_bytecode(&a2, &[missing])),
+        ],
+    );
+    publish(
+        b,
+        vec![
+            (&b1, module_bytecode(&b1, &[a1.clone()])),
+            (&b2, module_bytecode(&b2, &[])),
+        ],
+    );
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_modules = |params: Vec<serde_json::Value>| {
+        let request = JsonRpcRequest {
+            params,
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_account_modules_with_deps")
+            .unwrap()
+            .handler;
+        Runtime::new()
+            .unwrap()
+            .block_on(handler(service.clone(), request))
+    };
+
+    // the module is followed by its dependencies, each once despite the cycle
+    let modules = get_modules(vec![
+        serde_json::json!(a.to_string()),
+        serde_json::json!("A1"),
+    ])
+    .unwrap();
+    let names: Vec<_> = modules
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|module| module["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["A1", "B1"]);
+    let bytecode: BytesView = serde_json::from_value(modules[1]["bytecode"].clone()).unwrap();
+    assert_eq!(
+        bytecode.into_bytes().unwrap(),
+        module_bytecode(&b1, &[a1.clone()])
+    );
+    assert_eq!(modules[1]["address"], serde_json::json!(b.to_string()));
+
+    // missing dependencies and modules fail the request
+    assert!(get_modules(vec![serde_json::json!(a.to_string())]).is_err());
+    assert!(get_modules(vec![
+        serde_json::json!(a.to_string()),
+        serde_json::json!("A3")
+    ])
+    .is_err());
+
+    // accounts without modules have none
+    let modules = get_modules(vec![
+        serde_json::json!(AccountAddress::random().to_string()),
+    ]);
+    assert_eq!(modules.unwrap(), serde_json::json!([]));
+}
+
+#[test]
+fn test_batch_fetch() {
+    let mut mock_db = mock_db();
+    let (address, blob) = mock_db
+        .all_accounts
+        .iter()
+        .next()
+        .map(|(address, blob)| (*address, blob.clone()))
+        .unwrap();
+    // the mock serves batched resource reads from this account state
+    mock_db.account_state_with_proof[0].blob = Some(blob.clone());
+    let account_state = AccountState::try_from(&blob).unwrap();
+    let (_, event) = mock_db.events[0].clone();
+    let event_key = hex::encode(event.key().as_bytes());
+
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let call = |method: &str, params: Vec<serde_json::Value>| {
+        call_method(&service, &ledger_info, method, params)
+    };
+
+    let account_resource = serde_json::json!({
+        "type": "resource",
+        "address": address.to_string(),
+        "resource_type": "0x1::LibraAccount::LibraAccount",
+    });
+    let events = serde_json::json!({
+        "type": "events",
+        "key": event_key,
+        "start": event.sequence_number(),
+        "limit": 10,
+    });
+    let results = call(
+        "batch_fetch",
+        vec![serde_json::json!([account_resource, events])],
+    )
+    .unwrap();
+    let resource: BytesView = serde_json::from_value(results[0].clone()).unwrap();
+    let resource: AccountResource = lcs::from_bytes(&resource.into_bytes().unwrap()).unwrap();
+    assert_eq!(
+        resource.sequence_number(),
+        account_state
+            .get_account_resource()
+            .unwrap()
+            .unwrap()
+            .sequence_number()
+    );
+    let expected_events = call(
+        "get_events",
+        vec![
+            serde_json::json!(event_key),
+            serde_json::json!(event.sequence_number()),
+            serde_json::json!(10),
+        ],
+    )
+    .unwrap();
+    assert_eq!(results[1], expected_events);
+
+    // missing resources and balances are null, without failing the other reads
+    let results = call(
+        "batch_fetch",
+        vec![serde_json::json!([
+            {"type": "balance", "address": AccountAddress::random().to_string(), "currency": "LBR"},
+            {"type": "resource", "address": address.to_string(), "resource_type": "0x1::Unknown::Unknown"},
+            account_resource,
+        ])],
+    )
+    .unwrap();
+    assert_eq!(results[0], serde_json::Value::Null);
+    assert_eq!(results[1], serde_json::Value::Null);
+    assert!(results[2].is_string());
+
+    // reads are validated before any is made
+    for fetches in &[
+        serde_json::json!([{"type": "unknown"}]),
+        serde_json::json!([{"type": "events", "key": event_key, "start": 0, "limit": 0}]),
+        serde_json::json!(vec![account_resource; 101]),
+    ] {
+        assert!(call("batch_fetch", vec![fetches.clone()]).is_err());
+    }
+}
+
+#[test]
+fn test_get_account_state_with_proof() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let account = get_first_account_from_mock_db(&mock_db);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_account_state_with_proof_request(account, Some(0), Some(0));
+
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+
+    let received_proof = AccountStateWithProofView::from_response(result).unwrap();
+    let expected_proof = get_first_state_proof_from_mock_db(&mock_db);
+    let expected_blob = expected_proof.blob.as_ref().unwrap();
+    let expected_sm_proof = expected_proof.proof.transaction_info_to_account_proof();
+    let expected_txn_info_with_proof = expected_proof.proof.transaction_info_with_proof();
+
+    //version
+    assert_eq!(received_proof.version, expected_proof.version);
+
+    // blob
+    let account_blob: AccountStateBlob =
+        lcs::from_bytes(&received_proof.blob.unwrap().into_bytes().unwrap()).unwrap();
+    assert_eq!(account_blob, *expected_blob);
+
+    // proof
+    let sm_proof: SparseMerkleProof = lcs::from_bytes(
+        &received_proof
+            .proof
+            .transaction_info_to_account_proof
+            .into_bytes()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(sm_proof, *expected_sm_proof);
+    let txn_info: TransactionInfo =
+        lcs::from_bytes(&received_proof.proof.transaction_info.into_bytes().unwrap()).unwrap();
+    let li_proof: TransactionAccumulatorProof = lcs::from_bytes(
+        &received_proof
+            .proof
+            .ledger_info_to_transaction_info_proof
+            .into_bytes()
+            .unwrap(),
+    )
+    .unwrap();
+    let txn_info_with_proof = TransactionInfoWithProof::new(li_proof, txn_info);
+    assert_eq!(txn_info_with_proof, *expected_txn_info_with_proof);
+}
+
+#[test]
+fn test_get_account_state_with_proof_by_version() {
+    let mock_db = mock_db();
+    let account = get_first_account_from_mock_db(&mock_db);
+    let expected_blob = mock_db.all_accounts[&account].clone();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mock_db = Arc::new(mock_db);
+    let call = |prune_window: Option<u64>, params: Vec<serde_json::Value>| {
+        let service = JsonRpcService::new(
+            mock_db.clone(),
+            channel(1).0,
+            RoleType::Validator,
+            ChainId::test(),
+            prune_window,
+            &RpcConfig::default(),
+        );
+        let request = JsonRpcRequest {
+            params,
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_account_state_with_proof_by_version")
+            .unwrap()
+            .handler;
+        Runtime::new().unwrap().block_on(handler(service, request))
+    };
+
+    let result = call(None, vec![serde_json::json!(account.to_string())]).unwrap();
+    let view: AccountStateWithSparseProofView = serde_json::from_value(result).unwrap();
+    assert_eq!(view.version, mock_db.version);
+    let blob: AccountStateBlob =
+        lcs::from_bytes(&view.blob.unwrap().into_bytes().unwrap()).unwrap();
+    assert_eq!(blob, expected_blob);
+    let proof: SparseMerkleProof = lcs::from_bytes(&view.proof.into_bytes().unwrap()).unwrap();
+    assert_eq!(proof, SparseMerkleProof::new(None, vec![]));
+
+    // the proof of a missing account proves its absence
+    let result = call(
+        None,
+        vec![serde_json::json!(AccountAddress::random().to_string())],
+    )
+    .unwrap();
+    let view: AccountStateWithSparseProofView = serde_json::from_value(result).unwrap();
+    assert_eq!(view.blob, None);
+
+    let err = call(
+        Some(1),
+        vec![serde_json::json!(account.to_string()), serde_json::json!(0)],
+    )
+    .unwrap_err()
+    .downcast::<JsonRpcError>()
+    .unwrap();
+    assert_eq!(err.code, ServerCode::StatePruned as i16);
+}
+
+#[test]
+fn test_get_state_proof() {
+    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1024);
+
+    let version = mock_db.version;
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_state_proof_request(version);
+    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    let proof = StateProofView::from_response(result).unwrap();
+    let li: LedgerInfoWithSignatures =
+        lcs::from_bytes(&proof.ledger_info_with_signatures.into_bytes().unwrap()).unwrap();
+    assert_eq!(li.ledger_info().version(), version);
+}
+
+#[test]
+fn test_get_state_proof_epoch_change_only() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let version = mock_db.version;
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let get_state_proof = |params: Vec<serde_json::Value>| {
+        call_method(&service, &ledger_info, "get_state_proof", params).unwrap()
+    };
+
+    let full = get_state_proof(vec![serde_json::json!(version)]);
+    assert!(full["ledger_consistency_proof"].is_string());
+
+    // the epoch change proof and ledger info are the same, without the consistency proof
+    let trimmed = get_state_proof(vec![serde_json::json!(version), serde_json::json!(true)]);
+    assert!(trimmed.get("ledger_consistency_proof").is_none());
+    assert_eq!(trimmed["epoch_change_proof"], full["epoch_change_proof"]);
+    assert_eq!(
+        trimmed["ledger_info_with_signatures"],
+        full["ledger_info_with_signatures"]
+    );
+    let proof: StateProofView = serde_json::from_value(trimmed).unwrap();
+    assert!(proof.ledger_consistency_proof.is_none());
+
+    // the epoch changes are read from the epoch of the known version
+    let ledger_info = |epoch: u64, version: u64, ends_epoch: bool| {
+        let next_epoch_state = if ends_epoch {
+            Some(EpochState::empty())
+        } else {
+            None
+        };
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    HashValue::zero(),
+                    version,
+                    0,
+                    next_epoch_state,
+                ),
+                HashValue::zero(),
+            ),
+            BTreeMap::new(),
+        )
+    };
+    let mut mock_db = mock_db();
+    // epochs 0 and 1 ended at versions 0 and 3, the ledger is in epoch 2
+    mock_db.epoch_ending_ledger_infos = vec![ledger_info(0, 0, true), ledger_info(1, 3, true)];
+    let latest_ledger_info = ledger_info(2, version, false);
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let epoch_changes = |known_version: u64| {
+        let result = call_method(
+            &service,
+            &latest_ledger_info,
+            "get_state_proof",
+            vec![serde_json::json!(known_version), serde_json::json!(true)],
+        )
+        .unwrap();
+        let proof: StateProofView = serde_json::from_value(result).unwrap();
+        let proof: EpochChangeProof =
+            lcs::from_bytes(&proof.epoch_change_proof.into_bytes().unwrap()).unwrap();
+        proof
+            .ledger_info_with_sigs
+            .iter()
+            .map(|li| li.ledger_info().epoch())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(epoch_changes(0), vec![0, 1]);
+    assert_eq!(epoch_changes(2), vec![1]);
+    assert_eq!(epoch_changes(3), vec![1]);
+    assert_eq!(epoch_changes(4), Vec::<u64>::new());
+
+    // the known version can't be ahead of the ledger
+    assert!(call_method(
+        &service,
+        &latest_ledger_info,
+        "get_state_proof",
+        vec![serde_json::json!(version + 1), serde_json::json!(true)],
+    )
+    .is_err());
+}
+
+#[test]
+fn test_get_epoch_change_proof() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let handler = build_registry()
+        .remove("get_epoch_change_proof")
+        .unwrap()
+        .handler;
+    let mut runtime = Runtime::new().unwrap();
+    let request = |epoch: u64| JsonRpcRequest {
+        params: vec![serde_json::json!(epoch)],
+        ledger_info: ledger_info.clone(),
+    };
+
+    // a client at the latest epoch has no epoch change to catch up with
+    let result = runtime
+        .block_on(handler(service.clone(), request(0)))
+        .unwrap();
+    let proof: EpochChangeProofView = serde_json::from_value(result).unwrap();
+    let li: LedgerInfoWithSignatures =
+        lcs::from_bytes(&proof.ledger_info_with_signatures.into_bytes().unwrap()).unwrap();
+    assert_eq!(li, ledger_info);
+    assert!(!proof.more);
+
+    // a client can't be ahead of the node
+    assert!(runtime.block_on(handler(service, request(1))).is_err());
+}
+
+#[test]
+fn test_get_ledger_info_at_version() {
+    let ledger_info = |epoch: u64, version: u64, ends_epoch: bool| {
+        let next_epoch_state = if ends_epoch {
+            Some(EpochState::empty())
+        } else {
+            None
+        };
+        LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    HashValue::zero(),
+                    version,
+                    0,
+                    next_epoch_state,
+                ),
+                HashValue::zero(),
+            ),
+            BTreeMap::new(),
+        )
+    };
+    let mut mock_db = mock_db();
+    let latest_version = mock_db.version;
+    // epochs 0 and 1 ended at versions 0 and 3, the ledger is in epoch 2
+    mock_db.epoch_ending_ledger_infos = vec![ledger_info(0, 0, true), ledger_info(1, 3, true)];
+    let latest_ledger_info = ledger_info(2, latest_version, false);
+    let mock_db = Arc::new(mock_db);
+    let call = |prune_window: Option<u64>, version: u64| {
+        let service = JsonRpcService::new(
+            mock_db.clone(),
+            channel(1).0,
+            RoleType::Validator,
+            ChainId::test(),
+            prune_window,
+            &RpcConfig::default(),
+        );
+        let request = JsonRpcRequest {
+            params: vec![serde_json::json!(version)],
+            ledger_info: latest_ledger_info.clone(),
+        };
+        let handler = build_registry()
+            .remove("get_ledger_info_at_version")
+            .unwrap()
+            .handler;
+        Runtime::new()
+            .unwrap()
+            .block_on(handler(service, request))
+            .map(|result| serde_json::from_value::<LedgerInfoView>(result).unwrap())
+    };
+
+    // the ledger info at the version, or the nearest one after it, is returned
+    for (version, expected) in &[
+        (0, ledger_info(0, 0, true)),
+        (1, ledger_info(1, 3, true)),
+        (3, ledger_info(1, 3, true)),
+        (4, latest_ledger_info.clone()),
+        (latest_version, latest_ledger_info.clone()),
+    ] {
+        let view = call(None, *version).unwrap();
+        assert_eq!(view.version, expected.ledger_info().version());
+        assert_eq!(view.epoch, expected.ledger_info().epoch());
+        assert_eq!(view.ends_epoch, expected.ledger_info().ends_epoch());
+        let li: LedgerInfoWithSignatures =
+            lcs::from_bytes(&view.ledger_info_with_signatures.into_bytes().unwrap()).unwrap();
+        assert_eq!(li, *expected);
+    }
+
+    // versions ahead of the ledger or pruned are rejected
+    assert!(call(None, latest_version + 1).is_err());
+    let err = call(Some(1), 0).unwrap_err();
+    let err = err.downcast_ref::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, ServerCode::StatePruned as i16);
+}
+
+#[test]
+fn test_get_server_version() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request("get_server_version".to_string(), vec![]);
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    let server_version: ServerVersionView = serde_json::from_value(result).unwrap();
+    assert_eq!(server_version.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(server_version.git_commit, env!("GIT_REV"));
+    assert!(server_version.build_timestamp > 0);
+}
+
+#[test]
+fn test_get_network_status() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_get_network_status_request();
+
+    if let JsonRpcResponse::NetworkStatusResponse(connected_peers) =
+        execute_batch_and_get_first_response(&client, &mut runtime, batch)
+    {
+        // expect no connected peers when no network is running
+        assert_eq!(connected_peers.as_u64().unwrap(), 0);
+    } else {
+        panic!("did not receive expected json rpc response");
+    }
+}
+
+#[test]
+fn test_get_network_status_by_network() {
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+
+    // a peer connected in both directions is counted once
+    let peer = AccountAddress::random().to_string();
+    for origin in &["Inbound", "Outbound"] {
+        network::counters::LIBRA_NETWORK_PEER_CONNECTED
+            .with_label_values(&["validator", "Private(network_status)", &peer, origin])
+            .set(1);
+    }
+
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request("get_network_status_by_network".to_string(), vec![]);
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    let network_status: NetworkStatusView = serde_json::from_value(result).unwrap();
+    assert_eq!(network_status.networks["Private(network_status)"], 1);
+}
+
+#[test]
+fn test_get_peers_role_gating() {
+    let validator_peer = AccountAddress::random().to_string();
+    network::counters::LIBRA_NETWORK_PEER_CONNECTED
+        .with_label_values(&["validator", "Validator", &validator_peer, "Outbound"])
+        .set(1);
+    let public_peer = AccountAddress::random().to_string();
+    network::counters::LIBRA_NETWORK_PEER_CONNECTED
+        .with_label_values(&["full_node", "Public", &public_peer, "Inbound"])
+        .set(1);
+
+    // validators list their peers
+    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request("get_peers".to_string(), vec![]);
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert!(result
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!({"peer_id": validator_peer, "network": "Validator", "direction": "Outbound"})));
+
+    // public full nodes don't
+    let (client, mut runtime) = create_client_and_runtime_with_role(RoleType::FullNode);
+    let mut batch = JsonRpcBatch::default();
+    batch.add_request("get_peers".to_string(), vec![]);
+    let result = execute_batch_and_get_first_unknown_response(&client, &mut runtime, batch);
+    assert_eq!(result, serde_json::json!([]));
+}
+
+#[test]
+fn test_get_network_status_role_gating() {
+    let public_peer = AccountAddress::random().to_string();
+    network::counters::LIBRA_NETWORK_PEER_CONNECTED
+        .with_label_values(&["full_node", "Public", &public_peer, "Inbound"])
+        .set(1);
+    let mock_db = Arc::new(mock_db());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let call = |method: &str, role: RoleType, policy: NetworkStatusPolicy| {
+        let mut config = RpcConfig::default();
+        config.public_network_status = policy;
+        let service = JsonRpcService::new(
+            mock_db.clone(),
+            channel(1).0,
+            role,
+            ChainId::test(),
+            None,
+            &config,
+        );
+        let request = JsonRpcRequest {
+            params: vec![],
+            ledger_info: ledger_info.clone(),
+        };
+        let handler = build_registry().remove(method).unwrap().handler;
+        Runtime::new().unwrap().block_on(handler(service, request))
+    };
+    let by_network = "get_network_status_by_network";
+
+    // public full nodes don't disclose their network status by default
+    for method in &["get_network_status", by_network] {
+        let err = call(method, RoleType::FullNode, NetworkStatusPolicy::Disabled).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<JsonRpcError>().unwrap().code,
+            JsonRpcError::method_not_found().code
+        );
+    }
+
+    // or only their number of peers
+    let result = call(
+        "get_network_status",
+        RoleType::FullNode,
+        NetworkStatusPolicy::Reduced,
+    )
+    .unwrap();
+    assert!(result.is_u64());
+    let result = call(by_network, RoleType::FullNode, NetworkStatusPolicy::Reduced).unwrap();
+    assert_eq!(result["networks"], serde_json::json!({}));
+    let result = call(by_network, RoleType::FullNode, NetworkStatusPolicy::Full).unwrap();
+    assert_eq!(result["networks"]["Public"], 1);
+
+    // validators always disclose their full network status
+    let result = call(
+        by_network,
+        RoleType::Validator,
+        NetworkStatusPolicy::Disabled,
+    )
+    .unwrap();
+    assert!(result["networks"].is_object());
+
+    // so do validator full nodes, even while disconnected from their validator
+    let service = JsonRpcService::new(
+        mock_db.clone(),
+        channel(1).0,
+        RoleType::FullNode,
+        ChainId::test(),
+        None,
+        &RpcConfig::default(),
+    )
+    .with_public_full_node(false);
+    let request = JsonRpcRequest {
+        params: vec![],
+        ledger_info: ledger_info.clone(),
+    };
+    let handler = build_registry().remove(by_network).unwrap().handler;
+    let result = Runtime::new()
+        .unwrap()
+        .block_on(handler(service, request))
+        .unwrap();
+    assert!(result["networks"].get("vfn").is_none());
+    assert_eq!(result["networks"]["Public"], 1);
+}
+
+#[test]
+fn test_get_capabilities() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mut config = RpcConfig::default();
+    config.batch_cost_budget = 0;
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        channel(1).0,
+        RoleType::Validator,
+        ChainId::test(),
+        Some(100),
+        &config,
+    );
+    let request = JsonRpcRequest {
+        params: vec![],
+        ledger_info,
+    };
+    let handler = build_registry().remove("get_capabilities").unwrap().handler;
+    let result = Runtime::new()
+        .unwrap()
+        .block_on(handler(service, request))
+        .unwrap();
+    let capabilities: CapabilitiesView = serde_json::from_value(result).unwrap();
+
+    assert!(capabilities
+        .methods
+        .contains(&"get_transactions".to_string()));
+    assert!(capabilities.methods.contains(&"v2.get_account".to_string()));
+    assert!(capabilities.batch);
+    assert_eq!(capabilities.max_batch_cost, None);
+    assert_eq!(capabilities.max_transactions_limit, 1000);
+    assert_eq!(
+        capabilities.max_response_size,
+        Some(config.max_response_size)
+    );
+    assert_eq!(capabilities.encodings, vec!["json".to_string()]);
+    assert!(!capabilities.subscriptions);
+    assert!(!capabilities.historical_reads);
+    assert_eq!(capabilities.prune_window, Some(100));
+}
+
+#[test]
+fn test_get_node_config() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mut config = RpcConfig::default();
+    config.restricted_methods = vec!["submit".to_string()];
+    config
+        .api_keys
+        .insert("secret-key".to_string(), vec!["submit".to_string()]);
+    let service = JsonRpcService::new(
+        Arc::new(mock_db),
+        channel(1).0,
+        RoleType::FullNode,
+        ChainId::test(),
+        None,
+        &config,
+    );
+    let request = JsonRpcRequest {
+        params: vec![],
+        ledger_info,
+    };
+    let handler = build_registry().remove("get_node_config").unwrap().handler;
+    let result = Runtime::new()
+        .unwrap()
+        .block_on(handler(service, request))
+        .unwrap();
 
-    for (acc, blob) in mock_db.all_accounts.iter() {
-        let ar = AccountResource::try_from(blob).unwrap();
-        for seq in 1..ar.sequence_number() {
-            let mut batch = JsonRpcBatch::default();
-            batch.add_get_account_transaction_request(*acc, seq, true);
+    // secrets from the config are never disclosed
+    assert!(!result.to_string().contains("secret-key"));
+    let node_config: NodeConfigView = serde_json::from_value(result).unwrap();
+    assert_eq!(node_config.role, "full_node");
+    assert_eq!(node_config.chain_id, ChainId::test().id());
+    assert_eq!(node_config.max_transactions_limit, 1000);
+    assert!(node_config.submit_restricted);
+    assert_eq!(node_config.prune_window, None);
+    assert_eq!(node_config.encodings, vec!["json".to_string()]);
+    assert_eq!(
+        node_config.include_events_by_default,
+        config.include_events_by_default
+    );
+}
 
-            let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
-            let tx_view = TransactionView::optional_from_response(result)
-                .unwrap()
-                .expect("Transaction didn't exists!");
+#[test]
+fn test_openrpc_document_matches_registry() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let service = test_service(Arc::new(mock_db), &RpcConfig::default());
+    let registry = build_registry();
 
-            let (expected_tx, expected_status) = mock_db
-                .all_txns
-                .iter()
-                .find_map(|(t, status)| {
-                    if let Ok(x) = t.as_signed_user_txn() {
-                        if x.sender() == *acc && x.sequence_number() == seq {
-                            assert_eq!(tx_view.hash, t.hash().to_hex());
-                            return Some((x, status));
-                        }
-                    }
-                    None
-                })
-                .expect("Couldn't find tx");
+    let document = openrpc_document();
+    let methods = document["methods"].as_array().unwrap();
+    assert_eq!(methods.len(), registry.len());
 
-            // Check we returned correct events
-            let expected_events = mock_db
-                .events
-                .iter()
-                .filter(|(ev, _)| *ev == tx_view.version)
-                .map(|(_, e)| e)
-                .collect::<Vec<_>>();
+    let mut runtime = Runtime::new().unwrap();
+    for method in methods {
+        let name = method["name"].as_str().unwrap();
+        let handler = &registry
+            .get(name)
+            .unwrap_or_else(|| panic!("{} is not registered", name))
+            .handler;
+        let params = method["params"].as_array().unwrap();
+        let required = params
+            .iter()
+            .filter(|param| param["required"] == true)
+            .count();
 
-            assert_eq!(tx_view.events.len(), expected_events.len());
+        // given too many params, the handler reports its declared number of params
+        let request = JsonRpcRequest {
+            params: vec![serde_json::Value::Null; params.len() + 1],
+            ledger_info: ledger_info.clone(),
+        };
+        let err = runtime
+            .block_on(handler(service.clone(), request))
+            .unwrap_err();
+        let invalid_arguments = err
+            .downcast_ref::<JsonRpcError>()
+            .and_then(JsonRpcError::as_invalid_arguments)
+            .unwrap();
+        assert_eq!(invalid_arguments.required, required, "{}", name);
+        assert_eq!(
+            invalid_arguments.optional,
+            params.len() - required,
+            "{}",
+            name
+        );
+    }
+}
 
-            // check VM status
-            assert_eq!(tx_view.vm_status, VMStatusView::from(expected_status));
+#[test]
+fn test_storage_circuit_breaker() {
+    let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
 
-            for (i, event_view) in tx_view.events.iter().enumerate() {
-                let expected_event = expected_events.get(i).expect("Expected event didn't find");
-                assert_eq!(event_view.sequence_number, expected_event.sequence_number());
-                assert_eq!(event_view.transaction_version, tx_view.version);
-                assert_eq!(
-                    event_view.key.0,
-                    BytesView::from(expected_event.key().as_bytes()).0
-                );
-                // TODO: check event_data
-            }
+    // failures below the threshold keep the breaker closed
+    breaker.record(false);
+    assert!(breaker.try_acquire());
+    breaker.record(false);
+    assert!(!breaker.try_acquire());
 
-            let tx_data_view = tx_view.transaction;
+    // after the cooldown a single probe of storage is let through
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(breaker.try_acquire());
+    assert!(!breaker.try_acquire());
+    // a probe telling nothing about storage lets another one through
+    breaker.release();
+    assert!(breaker.try_acquire());
+    // a successful probe closes the breaker
+    breaker.record(true);
+    breaker.record(false);
+    assert!(breaker.try_acquire());
+    assert!(breaker.try_acquire());
 
-            // Always user transaction
-            match tx_data_view {
-                TransactionDataView::UserTransaction {
-                    sender,
-                    sequence_number,
-                    script_hash,
-                    ..
-                } => {
-                    assert_eq!(acc.to_string(), sender);
-                    assert_eq!(seq, sequence_number);
+    // a disabled breaker never opens
+    let disabled = CircuitBreaker::new(0, Duration::from_millis(50));
+    for _ in 0..10 {
+        disabled.record(false);
+    }
+    assert!(disabled.try_acquire());
+}
 
-                    if let TransactionPayload::Script(s) = expected_tx.payload() {
-                        assert_eq!(script_hash, HashValue::sha3_256_of(s.code()).to_hex());
-                    }
-                }
-                _ => panic!("wrong type"),
-            }
+#[test]
+fn test_storage_circuit_breaker_ignores_bad_requests() {
+    let mock_db = mock_db();
+    let version = mock_db.version;
+    let address = *mock_db.all_accounts.keys().next().unwrap();
+    let db = CircuitBreakerDb::new(
+        Arc::new(mock_db),
+        CircuitBreaker::new(1, Duration::from_secs(60)),
+        None,
+    );
+
+    // reads ahead of the ledger are rejected without counting as storage failures
+    for _ in 0..10 {
+        assert!(db.get_block_timestamp(version + 1).is_err());
+        assert!(db.get_transactions(0, 1, version + 1, false).is_err());
+        assert!(db
+            .get_account_state_with_proof_by_version(address, version + 1)
+            .is_err());
+        assert!(db
+            .get_account_state_with_proof(address, version, version - 1)
+            .is_err());
+        assert!(db.get_epoch_ending_ledger_infos(2, 1).is_err());
+    }
+    assert!(db.get_block_timestamp(version).is_ok());
+    assert!(db.get_latest_account_state(address).unwrap().is_some());
+}
+
+#[test]
+fn test_get_account_pruned_state_from_secondary_db() {
+    let mut mock_db = mock_db();
+    let mut config_state = AccountState::default();
+    config_state.insert(
+        RegisteredCurrencies::CONFIG_ID.access_path().path,
+        lcs::to_bytes(&vec![Identifier::new("Coin1").unwrap()]).unwrap(),
+    );
+    mock_db.account_state_with_proof[0].blob =
+        Some(AccountStateBlob::try_from(&config_state).unwrap());
+    let address = get_first_account_from_mock_db(&mock_db);
+    let mut secondary = mock_db.clone();
+    secondary.version = 10;
+    let secondary: Arc<dyn DbReader> = Arc::new(secondary);
+    // the primary DB no longer holds the account
+    let mut primary = mock_db;
+    primary.version += 1_000;
+    primary.all_accounts.clear();
+    let ledger_info = primary.get_latest_ledger_info().unwrap();
+    let db = FailoverDb::new(
+        Arc::new(primary),
+        secondary.clone(),
+        FailoverPolicy::OnPruned,
+        Some(100),
+        0,
+        false,
+    );
+    let service = JsonRpcService::new(
+        Arc::new(db),
+        channel(1).0,
+        RoleType::Validator,
+        ChainId::test(),
+        Some(100),
+        &RpcConfig::default(),
+    )
+    .with_pruned_state_db(secondary);
+    let call = |version: u64| {
+        call_method(
+            &service,
+            &ledger_info,
+            "get_account",
+            vec![
+                serde_json::json!(address.to_string()),
+                serde_json::json!(version),
+            ],
+        )
+    };
+
+    // pruned state is read from the secondary DB up to its latest version
+    assert_ne!(call(10).unwrap(), serde_json::Value::Null);
+    let err = call(11).unwrap_err().downcast::<JsonRpcError>().unwrap();
+    assert_eq!(err.code, ServerCode::StatePruned as i16);
+}
+
+#[test]
+fn test_failover_to_lagging_secondary_db() {
+    let mock_db = mock_db();
+    let address = *mock_db.all_accounts.keys().next().unwrap();
+    let mut primary = mock_db.clone();
+    primary.version += 1_000;
+    let version = primary.version;
+    let failover_db = |secondary_version, policy, prune_window| {
+        let mut secondary = mock_db.clone();
+        secondary.version = secondary_version;
+        let mut primary = primary.clone();
+        if policy == FailoverPolicy::OnPruned {
+            primary.all_accounts.clear();
         }
+        FailoverDb::new(
+            Arc::new(CircuitBreakerDb::new(
+                Arc::new(primary),
+                CircuitBreaker::new(1, Duration::from_secs(60)),
+                None,
+            )),
+            Arc::new(secondary),
+            policy,
+            prune_window,
+            100,
+            false,
+        )
+    };
+
+    // reads failing on the primary DB fail over to a secondary DB lagging by at most 100 versions
+    let db = failover_db(version - 100, FailoverPolicy::OnError, None);
+    assert!(db
+        .get_account_state_with_proof_by_version(address, version + 1)
+        .is_ok());
+    let db = failover_db(version - 101, FailoverPolicy::OnError, None);
+    assert!(db
+        .get_account_state_with_proof_by_version(address, version + 1)
+        .is_err());
+
+    // pruned state is only read from a secondary DB holding its version
+    let db = failover_db(10, FailoverPolicy::OnPruned, Some(100));
+    let (blob, _) = db
+        .get_account_state_with_proof_by_version(address, 10)
+        .unwrap();
+    assert!(blob.is_some());
+    let (blob, _) = db
+        .get_account_state_with_proof_by_version(address, 11)
+        .unwrap();
+    assert!(blob.is_none());
+}
+
+#[test]
+fn test_audit_log() {
+    let path = TempPath::new();
+    let entry = |method| AuditEntry {
+        method,
+        request_id: "1",
+        transaction: None,
+        result: "success".to_string(),
+    };
+    AuditLog::new(path.path(), true).record(&entry("submit"));
+    // reopening the audit log resumes the hash chain
+    let audit_log = AuditLog::new(path.path(), true);
+    assert!(audit_log.ensure_writable().is_ok());
+    audit_log.record(&entry("get_account"));
+
+    let content = std::fs::read_to_string(path.path()).unwrap();
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(lines.len(), 2);
+    let entries: Vec<serde_json::Value> = lines
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries[0]["method"], "submit");
+    assert_eq!(entries[0]["previous_hash"], HashValue::zero().to_hex());
+    assert_eq!(entries[1]["method"], "get_account");
+    assert_eq!(
+        entries[1]["previous_hash"],
+        HashValue::sha3_256_of(lines[0].as_bytes()).to_hex()
+    );
+
+    // the hash chain is resumed from the tail of logs longer than a read chunk
+    let audit_log = AuditLog::new(path.path(), true);
+    let long_id = "1".repeat(5_000);
+    audit_log.record(&AuditEntry {
+        request_id: &long_id,
+        ..entry("get_account")
+    });
+    audit_log.record(&entry("get_account"));
+    AuditLog::new(path.path(), true).record(&entry("get_account"));
+    let content = std::fs::read_to_string(path.path()).unwrap();
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(lines.len(), 5);
+    for (previous, line) in lines.iter().zip(lines.iter().skip(1)) {
+        let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert_eq!(
+            entry["previous_hash"],
+            HashValue::sha3_256_of(previous.as_bytes()).to_hex()
+        );
     }
+
+    // an unwritable audit log only rejects submissions in strict mode
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let err = AuditLog::new(dir.path(), true)
+        .ensure_writable()
+        .unwrap_err();
+    assert_eq!(err.code, ServerCode::AuditLogUnavailable as i16);
+    assert!(AuditLog::new(dir.path(), false).ensure_writable().is_ok());
 }
 
 #[test]
-// Check that if version and ledger_version parameters are None, then the server returns the latest
-// known state.
-fn test_get_account_state_with_proof_null_versions() {
-    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+fn test_restricted_submit_audited_once() {
+    let path = TempPath::new();
+    let mock_db = Arc::new(mock_db());
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let mut config = RpcConfig::default();
+    config.audit_log_path = Some(path.path().to_path_buf());
+    config.restricted_methods = vec!["submit".to_string()];
+    config
+        .api_keys
+        .insert("submitter".to_string(), vec!["submit".to_string()]);
+    let registry = build_registry();
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let registry = Arc::new(registry);
+    let service = test_service(mock_db, &config);
+    let call = |api_key: Option<&str>| {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "submit",
+            "params": ["invalid"],
+            "id": 1,
+        });
+        Runtime::new().unwrap().block_on(rpc_request_handler(
+            request,
+            service.clone(),
+            registry.clone(),
+            Arc::new(InflightRequests::new(0)),
+            auth.clone(),
+            api_key.map(str::to_string),
+            ledger_info.clone(),
+        ))
+    };
 
-    let account = get_first_account_from_mock_db(&mock_db);
-    let mut batch = JsonRpcBatch::default();
-    batch.add_get_account_state_with_proof_request(account, None, None);
+    // both authorized and unauthorized submissions get a single entry
+    assert!(call(Some("submitter")).get("error").is_some());
+    assert!(call(None).get("error").is_some());
+    let content = std::fs::read_to_string(path.path()).unwrap();
+    let entries: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(entries.len(), 2);
+    assert!(entries.iter().all(|entry| entry["method"] == "submit"));
+    assert_ne!(entries[0]["result"], entries[1]["result"]);
+}
 
-    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+#[test]
+fn test_replay_protection() {
+    let replay_protection = ReplayProtection::new(Duration::from_millis(50));
+    let privkey = Ed25519PrivateKey::generate_for_testing();
+    let sender = AccountAddress::random();
+    let txn = get_test_signed_txn(sender, 0, &privkey, privkey.public_key(), None);
+    let replay = get_test_signed_txn(
+        sender,
+        0,
+        &privkey,
+        privkey.public_key(),
+        Some(Script::new(vec![], vec![], vec![])),
+    );
 
-    let received_proof = AccountStateWithProofView::from_response(result).unwrap();
-    let expected_proof = get_first_state_proof_from_mock_db(&mock_db);
+    replay_protection.record(&txn);
+    // resubmitting the same transaction is let through, reusing its sequence number isn't
+    assert!(replay_protection.check(&txn).is_ok());
+    let err = replay_protection.check(&replay).unwrap_err();
+    assert_eq!(err.code, ServerCode::SequenceInFlight as i16);
+    let next = get_test_signed_txn(sender, 1, &privkey, privkey.public_key(), None);
+    assert!(replay_protection.check(&next).is_ok());
 
-    // Check latest version returned, when no version specified
-    assert_eq!(received_proof.version, expected_proof.version);
+    // the sequence number can be reused once the window elapsed
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(replay_protection.check(&replay).is_ok());
 }
 
 #[test]
-fn test_get_account_state_with_proof() {
-    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+fn test_inflight_requests_limit() {
+    let inflight = Arc::new(InflightRequests::new(2));
 
-    let account = get_first_account_from_mock_db(&mock_db);
-    let mut batch = JsonRpcBatch::default();
-    batch.add_get_account_state_with_proof_request(account, Some(0), Some(0));
+    let first = inflight.try_acquire();
+    let second = inflight.try_acquire();
+    assert!(first.is_some());
+    assert!(second.is_some());
+    assert!(inflight.try_acquire().is_none());
 
-    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
+    // completing a request frees up a slot
+    drop(first);
+    assert!(inflight.try_acquire().is_some());
 
-    let received_proof = AccountStateWithProofView::from_response(result).unwrap();
-    let expected_proof = get_first_state_proof_from_mock_db(&mock_db);
-    let expected_blob = expected_proof.blob.as_ref().unwrap();
-    let expected_sm_proof = expected_proof.proof.transaction_info_to_account_proof();
-    let expected_txn_info_with_proof = expected_proof.proof.transaction_info_with_proof();
+    // 0 disables the limit
+    let unlimited = Arc::new(InflightRequests::new(0));
+    let guards: Vec<_> = (0..100).map(|_| unlimited.try_acquire()).collect();
+    assert!(guards.iter().all(Option::is_some));
+}
 
-    //version
-    assert_eq!(received_proof.version, expected_proof.version);
+#[test]
+fn test_read_pool() {
+    let mut runtime = Runtime::new().unwrap();
+    let pool = Arc::new(ReadPool::new(1, 1));
 
-    // blob
-    let account_blob: AccountStateBlob =
-        lcs::from_bytes(&received_proof.blob.unwrap().into_bytes().unwrap()).unwrap();
-    assert_eq!(account_blob, *expected_blob);
+    // reads run on the threads of the pool
+    let thread_name = runtime
+        .block_on(pool.run(async { std::thread::current().name().map(String::from) }))
+        .unwrap();
+    assert_eq!(thread_name.as_deref(), Some("rpc-read-0"));
 
-    // proof
-    let sm_proof: SparseMerkleProof = lcs::from_bytes(
-        &received_proof
-            .proof
-            .transaction_info_to_account_proof
-            .into_bytes()
-            .unwrap(),
-    )
-    .unwrap();
-    assert_eq!(sm_proof, *expected_sm_proof);
-    let txn_info: TransactionInfo =
-        lcs::from_bytes(&received_proof.proof.transaction_info.into_bytes().unwrap()).unwrap();
-    let li_proof: TransactionAccumulatorProof = lcs::from_bytes(
-        &received_proof
-            .proof
-            .ledger_info_to_transaction_info_proof
-            .into_bytes()
-            .unwrap(),
-    )
-    .unwrap();
-    let txn_info_with_proof = TransactionInfoWithProof::new(li_proof, txn_info);
-    assert_eq!(txn_info_with_proof, *expected_txn_info_with_proof);
+    // a panicking read fails without taking down its thread
+    assert!(runtime.block_on(pool.run(async { panic!() })).is_err());
+    assert_eq!(runtime.block_on(pool.run(async { 1 })).unwrap(), 1);
+
+    // reads are rejected as overloaded once the queue is full
+    let (started_sender, started_receiver) = std::sync::mpsc::channel();
+    let (release_sender, release_receiver) = std::sync::mpsc::channel();
+    let busy = runtime.spawn({
+        let pool = Arc::clone(&pool);
+        async move {
+            pool.run(async move {
+                started_sender.send(()).unwrap();
+                release_receiver.recv().unwrap();
+            })
+            .await
+        }
+    });
+    started_receiver.recv().unwrap();
+    runtime.block_on(async {
+        let mut queued = Box::pin(pool.run(async { 2 }));
+        assert!(futures::poll!(queued.as_mut()).is_pending());
+        let err = pool.run(async { 3 }).await.unwrap_err();
+        assert_eq!(err.message, JsonRpcError::server_overloaded().message);
+
+        release_sender.send(()).unwrap();
+        assert_eq!(queued.await.unwrap(), 2);
+        assert!(busy.await.unwrap().is_ok());
+    });
 }
 
 #[test]
-fn test_get_state_proof() {
-    let (mock_db, client, mut runtime) = create_database_client_and_runtime(1024);
+fn test_connection_limits() {
+    Runtime::new().unwrap().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut incoming = LimitedIncoming::new(listener, 1, Some(Duration::from_millis(100)));
+        let mut buf = [0u8; 1];
 
-    let version = mock_db.version;
-    let mut batch = JsonRpcBatch::default();
-    batch.add_get_state_proof_request(version);
-    let result = execute_batch_and_get_first_response(&client, &mut runtime, batch);
-    let proof = StateProofView::from_response(result).unwrap();
-    let li: LedgerInfoWithSignatures =
-        lcs::from_bytes(&proof.ledger_info_with_signatures.into_bytes().unwrap()).unwrap();
-    assert_eq!(li.ledger_info().version(), version);
+        let _client = TcpStream::connect(address).await.unwrap();
+        let mut connection = incoming.next().await.unwrap().unwrap();
+
+        // connections past the cap are closed right away
+        let mut rejected = TcpStream::connect(address).await.unwrap();
+        assert!(timeout(Duration::from_millis(50), incoming.next())
+            .await
+            .is_err());
+        assert_eq!(rejected.read(&mut buf).await.unwrap_or(0), 0);
+
+        // idle connections time out, freeing up their slot
+        let err = connection.read(&mut buf).await.unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::TimedOut);
+        drop(connection);
+        let _client = TcpStream::connect(address).await.unwrap();
+        assert!(incoming.next().await.unwrap().is_ok());
+    });
 }
 
 #[test]
-fn test_get_network_status() {
-    let (_mock_db, client, mut runtime) = create_database_client_and_runtime(1);
+fn test_deprecated_method_notice() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let config = RpcConfig::default();
+    let service = test_service(Arc::new(mock_db), &config);
+    let mut registry = build_registry();
+    registry.get_mut("get_latest_version").unwrap().deprecation = Some(Deprecation {
+        replacement: "get_metadata",
+        sunset_date: "2021-01-01",
+    });
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let registry = Arc::new(registry);
+    let call = |method: &str| {
+        let request =
+            serde_json::json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1});
+        Runtime::new().unwrap().block_on(rpc_request_handler(
+            request,
+            service.clone(),
+            registry.clone(),
+            Arc::new(InflightRequests::new(0)),
+            auth.clone(),
+            None,
+            ledger_info.clone(),
+        ))
+    };
 
-    let mut batch = JsonRpcBatch::default();
-    batch.add_get_network_status_request();
+    // deprecated methods are still served, with a notice
+    let response = call("get_latest_version");
+    assert!(response["result"].is_u64());
+    assert_eq!(
+        response[JSONRPC_LIBRA_DEPRECATION],
+        serde_json::json!({"replacement": "get_metadata", "sunset_date": "2021-01-01"})
+    );
 
-    if let JsonRpcResponse::NetworkStatusResponse(connected_peers) =
-        execute_batch_and_get_first_response(&client, &mut runtime, batch)
-    {
-        // expect no connected peers when no network is running
-        assert_eq!(connected_peers.as_u64().unwrap(), 0);
-    } else {
-        panic!("did not receive expected json rpc response");
+    let response = call("get_metadata");
+    assert!(response.get(JSONRPC_LIBRA_DEPRECATION).is_none());
+}
+
+#[test]
+fn test_v2_method_routing() {
+    let mock_db = mock_db();
+    let ledger_info = mock_db.get_latest_ledger_info().unwrap();
+    let config = RpcConfig::default();
+    let service = test_service(Arc::new(mock_db), &config);
+    let registry = build_registry();
+    let auth = Arc::new(ApiKeyAuth::new(&config, &registry));
+    let registry = Arc::new(registry);
+    let call = |method: &str, params: serde_json::Value| {
+        let request =
+            serde_json::json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+        Runtime::new().unwrap().block_on(rpc_request_handler(
+            request,
+            service.clone(),
+            registry.clone(),
+            Arc::new(InflightRequests::new(0)),
+            auth.clone(),
+            None,
+            ledger_info.clone(),
+        ))
+    };
+
+    // methods with a v2 variant are served by it, both versions treating unknown accounts alike
+    let address = AccountAddress::random().to_string();
+    let v1 = call("get_account", serde_json::json!([address]));
+    let v2 = call("v2.get_account", serde_json::json!([address]));
+    assert_eq!(v1.get("result"), v2.get("result"));
+    assert_eq!(v1.get("error"), v2.get("error"));
+    assert_ne!(v2["error"]["code"], JsonRpcError::method_not_found().code);
+
+    // the others fall back to their v1 handler
+    let v1 = call("get_latest_version", serde_json::json!([]));
+    let v2 = call("v2.get_latest_version", serde_json::json!([]));
+    assert_eq!(v1["result"], v2["result"]);
+
+    for method in &[
+        "v2.unknown",
+        "v3.get_latest_version",
+        "v2.v2.get_latest_version",
+    ] {
+        let response = call(method, serde_json::json!([]));
+        assert_eq!(
+            response["error"]["code"],
+            JsonRpcError::method_not_found().code
+        );
     }
+
+    // all versions of a method share its restrictions
+    let mut config = RpcConfig::default();
+    config.restricted_methods = vec!["submit".to_string()];
+    config
+        .api_keys
+        .insert("submitter".to_string(), vec!["v2.submit".to_string()]);
+    let auth = ApiKeyAuth::new(&config, &registry);
+    assert!(!auth.is_authorized("v2.submit", None));
+    assert!(auth.is_authorized("v2.submit", Some("submitter")));
+    assert!(auth.is_authorized("submit", Some("submitter")));
+}
+
+#[test]
+fn test_api_key_auth() {
+    let registry = build_registry();
+    let mut config = RpcConfig::default();
+    config.restricted_methods = vec!["submit".to_string(), "get_peers".to_string()];
+    config
+        .api_keys
+        .insert("submitter".to_string(), vec!["submit".to_string()]);
+    let auth = ApiKeyAuth::new(&config, &registry);
+
+    // unrestricted methods are open to everyone
+    assert!(auth.is_authorized("get_account", None));
+    assert!(auth.is_authorized("get_account", Some("unknown")));
+
+    // restricted methods require a key allowing them
+    assert!(!auth.is_authorized("submit", None));
+    assert!(!auth.is_authorized("submit", Some("unknown")));
+    assert!(auth.is_authorized("submit", Some("submitter")));
+    assert!(!auth.is_authorized("get_peers", Some("submitter")));
+}
+
+#[test]
+fn test_batch_cost() {
+    let registry = build_registry();
+    let request = |method: &str, params: serde_json::Value| serde_json::json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1});
+    let transactions = request("get_transactions", serde_json::json!([0, 50, false]));
+    let events = request("get_events", serde_json::json!(["00", 0, 40]));
+    let account = request("get_account", serde_json::json!(["00"]));
+    let child_vasps = request("list_child_vasps", serde_json::json!(["00"]));
+
+    let mut config = RpcConfig::default();
+    config.batch_cost_budget = 100;
+    let batch_cost = BatchCost::new(&config, &registry);
+    assert_eq!(batch_cost.cost(&transactions), 50);
+    assert_eq!(batch_cost.cost(&events), 40);
+    let string_limit = request("get_transactions", serde_json::json!([0, "50", false]));
+    assert_eq!(batch_cost.cost(&string_limit), 50);
+    assert_eq!(batch_cost.cost(&account), 1);
+    assert_eq!(batch_cost.cost(&child_vasps), 100);
+    assert!(batch_cost
+        .check(&[transactions.clone(), events.clone(), account.clone()])
+        .is_ok());
+    let err = batch_cost
+        .check(&[transactions.clone(), events.clone(), child_vasps])
+        .unwrap_err();
+    assert_eq!(err.code, ServerCode::BatchTooCostly as i16);
+
+    // configured costs override the default ones
+    config.method_costs.insert("get_account".to_string(), 20);
+    let batch_cost = BatchCost::new(&config, &registry);
+    assert!(batch_cost
+        .check(&[transactions.clone(), events.clone(), account.clone()])
+        .is_err());
+
+    // 0 disables the limit
+    config.batch_cost_budget = 0;
+    let batch_cost = BatchCost::new(&config, &registry);
+    assert!(batch_cost.check(&[transactions, events, account]).is_ok());
+}
+
+#[test]
+#[should_panic]
+fn test_api_key_auth_unknown_method() {
+    let mut config = RpcConfig::default();
+    config.restricted_methods = vec!["not_a_method".to_string()];
+    ApiKeyAuth::new(&config, &build_registry());
+}
+
+#[test]
+fn test_unknown_script_view() {
+    let code = vec![1, 2, 3];
+    let script = Script::new(
+        code.clone(),
+        vec![TypeTag::U64],
+        vec![
+            TransactionArgument::U64(10),
+            TransactionArgument::Bool(true),
+        ],
+    );
+    let view = ScriptView::from(TransactionPayload::Script(script));
+    assert_eq!(
+        view,
+        ScriptView::Script {
+            name: "<unknown transaction>".to_string(),
+            code: BytesView::from(&code),
+            type_arguments: vec!["U64".to_string()],
+            arguments: vec![
+                TransactionArgumentView::U64(10),
+                TransactionArgumentView::Bool(true),
+            ],
+        }
+    );
+    assert_eq!(
+        serde_json::to_value(&view).unwrap()["arguments"],
+        serde_json::json!([{"type": "u64", "value": 10}, {"type": "bool", "value": true}])
+    );
 }
 
 /// Creates and returns a MockLibraDB, JsonRpcAsyncClient and corresponding server Runtime tuple for
@@ -690,6 +4224,26 @@ fn create_database_client_and_runtime(
     (mock_db, client, runtime)
 }
 
+/// Creates and returns a JsonRpcAsyncClient and corresponding server Runtime for a node with the
+/// given role.
+fn create_client_and_runtime_with_role(role: RoleType) -> (JsonRpcAsyncClient, Runtime) {
+    let port = utils::get_available_port();
+    let address = format!("0.0.0.0:{}", port);
+
+    let runtime = test_bootstrap_with_role(
+        address.parse().unwrap(),
+        Arc::new(mock_db()),
+        channel(1).0,
+        role,
+    );
+    let client = JsonRpcAsyncClient::new(
+        reqwest::Url::from_str(format!("http://127.0.0.1:{}/v1", port).as_str())
+            .expect("invalid url"),
+    );
+
+    (client, runtime)
+}
+
 /// Returns the first account address stored in the given mock database.
 fn get_first_account_from_mock_db(mock_db: &MockLibraDB) -> AccountAddress {
     *mock_db
@@ -722,7 +4276,53 @@ fn execute_batch_and_get_first_response(
         .unwrap()
 }
 
+/// Executes the given JsonRPCBatch for a method the client has no typed response for, and returns
+/// the raw JSON result of the first request in the batch.
+fn execute_batch_and_get_first_unknown_response(
+    client: &JsonRpcAsyncClient,
+    runtime: &mut Runtime,
+    batch: JsonRpcBatch,
+) -> serde_json::Value {
+    match execute_batch_and_get_first_response(client, runtime, batch) {
+        JsonRpcResponse::UnknownResponse(value) => value,
+        response => panic!("unexpected response: {:?}", response),
+    }
+}
+
 fn error_code(resp: reqwest::blocking::Response) -> i16 {
     let err_resp: JsonRpcErrorResponse = resp.json().unwrap();
     err_resp.error.code
 }
+
+#[test]
+fn test_response_cache() {
+    let mut ttls = BTreeMap::new();
+    ttls.insert("get_currencies".to_string(), 60_000);
+    ttls.insert("get_metadata".to_string(), 1);
+    ttls.insert("submit".to_string(), 60_000);
+    let cache = ResponseCache::new(&ttls);
+
+    // mempool methods and methods without a time to live aren't cached
+    assert!(cache.key("submit", 1, &[]).is_none());
+    assert!(cache.key("get_account", 1, &[]).is_none());
+
+    let params = [serde_json::json!("LBR")];
+    let key = cache.key("get_currencies", 1, &params).unwrap();
+    assert!(cache.get(&key).is_none());
+    cache.insert(key.clone(), serde_json::json!([]));
+    assert_eq!(cache.get(&key), Some(serde_json::json!([])));
+
+    // results are only reused for the same method, params and ledger version
+    for other_key in &[
+        cache.key("get_currencies", 2, &params).unwrap(),
+        cache.key("get_currencies", 1, &[]).unwrap(),
+        cache.key("v2.get_currencies", 1, &params).unwrap(),
+    ] {
+        assert!(cache.get(other_key).is_none());
+    }
+
+    let key = cache.key("get_metadata", 1, &[]).unwrap();
+    cache.insert(key.clone(), serde_json::json!({}));
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(cache.get(&key).is_none());
+}
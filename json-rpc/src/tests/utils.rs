@@ -1,8 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::methods::{build_registry, JsonRpcRequest, JsonRpcService};
 use anyhow::{Error, Result};
-use libra_config::config::RoleType;
+use futures::channel::mpsc::channel;
+use libra_config::config::{RoleType, RpcConfig};
 use libra_crypto::HashValue;
 use libra_mempool::MempoolClientSender;
 use libra_types::{
@@ -23,6 +25,7 @@ use libra_types::{
     },
     vm_status::KeptVMStatus,
 };
+use serde_json::Value;
 use std::{collections::BTreeMap, net::SocketAddr, sync::Arc};
 use storage_interface::{DbReader, StartupInfo, TreeState};
 use tokio::runtime::Runtime;
@@ -34,15 +37,63 @@ pub fn test_bootstrap(
     libra_db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
 ) -> Runtime {
-    crate::bootstrap(
+    test_bootstrap_with_role(address, libra_db, mp_sender, RoleType::Validator)
+}
+
+/// Same as `test_bootstrap`, for a node with the given role
+pub(crate) fn test_bootstrap_with_role(
+    address: SocketAddr,
+    libra_db: Arc<dyn DbReader>,
+    mp_sender: MempoolClientSender,
+    role: RoleType,
+) -> Runtime {
+    let config = RpcConfig {
         address,
+        ..Default::default()
+    };
+    crate::bootstrap(
+        &config,
         libra_db,
         mp_sender,
+        role,
+        ChainId::test(),
+        None,
+        None,
+        !role.is_validator(),
+    )
+}
+
+/// Creates JSON RPC service of a Validator node reading from the given DB, with the given config
+/// Should only be used for unit-tests
+pub(crate) fn test_service(libra_db: Arc<dyn DbReader>, config: &RpcConfig) -> JsonRpcService {
+    JsonRpcService::new(
+        libra_db,
+        channel(1).0,
         RoleType::Validator,
         ChainId::test(),
+        None,
+        config,
     )
 }
 
+/// Calls the given method of the given service with the given params, at the given ledger info
+/// Should only be used for unit-tests
+pub(crate) fn call_method(
+    service: &JsonRpcService,
+    ledger_info: &LedgerInfoWithSignatures,
+    method: &str,
+    params: Vec<Value>,
+) -> Result<Value> {
+    let request = JsonRpcRequest {
+        params,
+        ledger_info: ledger_info.clone(),
+    };
+    let handler = build_registry().remove(method).unwrap().handler;
+    Runtime::new()
+        .unwrap()
+        .block_on(handler(service.clone(), request))
+}
+
 /// Lightweight mock of LibraDB
 #[derive(Clone)]
 pub(crate) struct MockLibraDB {
@@ -52,6 +103,10 @@ pub(crate) struct MockLibraDB {
     pub events: Vec<(u64, ContractEvent)>,
     pub account_state_with_proof: Vec<AccountStateWithProof>,
     pub timestamps: Vec<u64>,
+    /// Drops the last transaction info of the transaction lists returned, like inconsistent storage
+    pub drop_last_transaction_info: bool,
+    /// Ledger infos ending epochs, in epoch order
+    pub epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
 }
 
 impl DbReader for MockLibraDB {
@@ -153,6 +208,9 @@ impl DbReader for MockLibraDB {
                     status.clone(),
                 ));
             });
+        if self.drop_last_transaction_info {
+            txn_infos.pop();
+        }
         let first_transaction_version = transactions.first().map(|_| start_version);
         let proof = TransactionListProof::new(AccumulatorRangeProof::new_empty(), txn_infos);
 
@@ -160,7 +218,7 @@ impl DbReader for MockLibraDB {
             transactions,
             events: if fetch_events {
                 Some(
-                    (start_version..start_version + limit)
+                    (start_version..start_version + transactions.len() as u64)
                         .map(|version| {
                             self.events
                                 .iter()
@@ -261,10 +319,16 @@ impl DbReader for MockLibraDB {
 
     fn get_epoch_ending_ledger_infos(
         &self,
-        _start_epoch: u64,
-        _end_epoch: u64,
+        start_epoch: u64,
+        end_epoch: u64,
     ) -> Result<EpochChangeProof> {
-        unimplemented!()
+        let ledger_infos = self
+            .epoch_ending_ledger_infos
+            .iter()
+            .filter(|li| (start_epoch..end_epoch).contains(&li.ledger_info().epoch()))
+            .cloned()
+            .collect();
+        Ok(EpochChangeProof::new(ledger_infos, false))
     }
 
     fn get_epoch_ending_ledger_info(&self, _: u64) -> Result<LedgerInfoWithSignatures> {
@@ -0,0 +1,132 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! TLS termination of client connections, with certificates reloaded in place
+//!
+//! Each connection runs its handshake in a task of its own, so a slow client can't hold up the
+//! handshakes of others. The certificate and key are served to handshakes by a resolver holding
+//! the latest pair loaded: reloading them never restarts the listener, so established
+//! connections and in-flight requests are unaffected, and a pair failing to load leaves the
+//! previous one in use.
+use anyhow::{ensure, format_err, Result};
+use arc_swap::ArcSwap;
+use futures::{channel::mpsc, Stream, StreamExt};
+use libra_logger::prelude::*;
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::{
+    rustls::{
+        internal::pemfile,
+        sign::{self, CertifiedKey},
+        ClientHello, NoClientAuth, PrivateKey, ResolvesServerCert, ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+
+/// Certificate chain and private key served to new handshakes, swapped in place when reloaded
+pub(crate) struct CertificateResolver {
+    certified_key: ArcSwap<CertifiedKey>,
+}
+
+impl CertificateResolver {
+    /// Loads the PEM encoded certificate chain and private key from the given files
+    pub fn new(cert_path: &Path, key_path: &Path) -> Result<Self> {
+        Ok(Self {
+            certified_key: ArcSwap::from_pointee(load_certified_key(cert_path, key_path)?),
+        })
+    }
+
+    /// Serves the certificate chain and private key of the given files to new handshakes.
+    /// The current ones are kept if the files fail to load
+    pub fn reload(&self, cert_path: &Path, key_path: &Path) -> Result<()> {
+        let certified_key = load_certified_key(cert_path, key_path)?;
+        self.certified_key.store(Arc::new(certified_key));
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<CertifiedKey> {
+        Some(CertifiedKey::clone(&self.certified_key.load()))
+    }
+}
+
+/// Reads the certificate chain and the private key, PKCS#8 or else PKCS#1 RSA, of the given files
+fn load_certified_key(cert_path: &Path, key_path: &Path) -> Result<CertifiedKey> {
+    let certs = pemfile::certs(&mut BufReader::new(File::open(cert_path)?))
+        .map_err(|()| format_err!("invalid certificate in {}", cert_path.display()))?;
+    ensure!(
+        !certs.is_empty(),
+        "no certificate in {}",
+        cert_path.display()
+    );
+
+    let read_keys = |read: fn(&mut dyn BufRead) -> Result<Vec<PrivateKey>, ()>| -> Result<_> {
+        read(&mut BufReader::new(File::open(key_path)?))
+            .map_err(|()| format_err!("invalid private key in {}", key_path.display()))
+    };
+    let mut keys = read_keys(pemfile::pkcs8_private_keys)?;
+    if keys.is_empty() {
+        keys = read_keys(pemfile::rsa_private_keys)?;
+    }
+    let key = keys
+        .first()
+        .ok_or_else(|| format_err!("no private key in {}", key_path.display()))?;
+    let signing_key = sign::any_supported_type(key)
+        .map_err(|()| format_err!("unsupported private key in {}", key_path.display()))?;
+    Ok(CertifiedKey::new(certs, Arc::new(signing_key)))
+}
+
+/// Returns the acceptor of TLS connections, serving the certificate of the given resolver
+pub(crate) fn tls_acceptor(resolver: Arc<CertificateResolver>) -> TlsAcceptor {
+    let mut config = ServerConfig::new(NoClientAuth::new());
+    config.cert_resolver = resolver;
+    config.set_protocols(&["h2".into(), "http/1.1".into()]);
+    TlsAcceptor::from(Arc::new(config))
+}
+
+/// Returns the TLS connections established over the given connections, as their handshakes
+/// complete. Connections failing their handshake are closed.
+/// Must be called within a tokio runtime
+pub(crate) fn tls_incoming<S, C>(
+    mut incoming: S,
+    acceptor: TlsAcceptor,
+) -> impl Stream<Item = io::Result<TlsStream<C>>>
+where
+    S: Stream<Item = io::Result<C>> + Send + Unpin + 'static,
+    C: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+{
+    let (sender, receiver) = mpsc::unbounded();
+    tokio::spawn(async move {
+        while let Some(connection) = incoming.next().await {
+            // connections stop being accepted once the server is gone
+            if sender.is_closed() {
+                break;
+            }
+            let connection = match connection {
+                Ok(connection) => connection,
+                Err(err) => {
+                    warn!("[rpc] failed to accept connection: {}", err);
+                    continue;
+                }
+            };
+            let acceptor = acceptor.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(connection).await {
+                    Ok(stream) => {
+                        let _ = sender.unbounded_send(Ok(stream));
+                    }
+                    Err(err) => debug!("[rpc] TLS handshake failed: {}", err),
+                }
+            });
+        }
+    });
+    receiver
+}
@@ -11,23 +11,26 @@ macro_rules! register_rpc_method {
     ($registry:expr, $name: expr, $method: expr, $required_num_args: expr, $opt_num_args: expr) => {
         $registry.insert(
             $name.to_string(),
-            Box::new(move |service, request| {
-                Box::pin(async move {
-                    if request.params.len() < $required_num_args
-                        || request.params.len() > $required_num_args + $opt_num_args
-                    {
-                        anyhow::bail!(JsonRpcError::invalid_params(Some(
-                            ErrorData::InvalidArguments(InvalidArguments {
-                                required: $required_num_args,
-                                optional: $opt_num_args,
-                                given: request.params.len(),
-                            })
-                        )));
-                    }
+            RpcMethod {
+                handler: Box::new(move |service, request| {
+                    Box::pin(async move {
+                        if request.params.len() < $required_num_args
+                            || request.params.len() > $required_num_args + $opt_num_args
+                        {
+                            anyhow::bail!(JsonRpcError::invalid_params(Some(
+                                ErrorData::InvalidArguments(InvalidArguments {
+                                    required: $required_num_args,
+                                    optional: $opt_num_args,
+                                    given: request.params.len(),
+                                })
+                            )));
+                        }
 
-                    Ok(serde_json::to_value($method(service, request).await?)?)
-                })
-            }),
+                        Ok(serde_json::to_value($method(service, request).await?)?)
+                    })
+                }),
+                deprecation: None,
+            },
         );
     };
 }
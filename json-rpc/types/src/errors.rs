@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use libra_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     vm_status::{StatusCode, StatusType},
 };
@@ -29,12 +30,45 @@ pub enum ServerCode {
     MempoolInvalidUpdate = -32010,
     MempoolVmError = -32011,
     MempoolUnknownError = -32012,
+
+    // Storage layer is failing and reads are short-circuited
+    StorageUnavailable = -32013,
+
+    // Requested state was pruned from storage
+    StatePruned = -32014,
+
+    // Client isn't allowed to call the method
+    Unauthorized = -32015,
+
+    // Node's ledger is behind the minimum version required by the client
+    NodeTooStale = -32016,
+
+    // Response exceeds the maximum response size, a smaller limit must be requested
+    ResponseTooLarge = -32017,
+
+    // Total cost of the requests of a batch exceeds the batch cost budget
+    BatchTooCostly = -32018,
+
+    // Audit log is unwritable and submissions can't be audited
+    AuditLogUnavailable = -32019,
+
+    // Another transaction with the same sender and sequence number was recently accepted
+    SequenceInFlight = -32020,
+
+    // Mempool can't take submissions, as it is overloaded or shutting down
+    MempoolUnavailable = -32021,
+
+    // Mempool dropped the submission without answering, so the transaction may have been accepted
+    SubmissionStatusUnknown = -32022,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ErrorData {
     InvalidArguments(InvalidArguments),
     StatusCode(StatusCode),
+    ResponseTooLarge(ResponseTooLarge),
+    InvalidLimit(InvalidLimit),
+    InvalidParam(InvalidParam),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Copy)]
@@ -44,6 +78,27 @@ pub struct InvalidArguments {
     pub given: usize,
 }
 
+/// Serialized size of a response, in bytes, and the maximum size allowed
+#[derive(Clone, Debug, Deserialize, Serialize, Copy)]
+pub struct ResponseTooLarge {
+    pub size: usize,
+    pub max_size: usize,
+}
+
+/// Limit parameter given, and the maximum limit allowed
+#[derive(Clone, Debug, Deserialize, Serialize, Copy)]
+pub struct InvalidLimit {
+    pub limit: u64,
+    pub max_limit: u64,
+}
+
+/// Position and name of a parameter that failed to be parsed
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct InvalidParam {
+    pub index: usize,
+    pub name: String,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct JsonRpcError {
     pub code: i16,
@@ -84,6 +139,14 @@ impl JsonRpcError {
         }
     }
 
+    pub fn duplicate_request_id(id: &Value) -> Self {
+        Self {
+            code: -32600,
+            message: format!("Invalid Request: duplicate id {} in batch", id),
+            data: None,
+        }
+    }
+
     pub fn invalid_params(data: Option<ErrorData>) -> Self {
         Self {
             code: -32602,
@@ -92,6 +155,25 @@ impl JsonRpcError {
         }
     }
 
+    pub fn invalid_param(index: usize, name: &str, reason: String) -> Self {
+        Self {
+            code: -32602,
+            message: format!("Invalid params: param {} ({}) {}", index, name, reason),
+            data: Some(ErrorData::InvalidParam(InvalidParam {
+                index,
+                name: name.to_string(),
+            })),
+        }
+    }
+
+    pub fn invalid_limit(limit: u64, max_limit: u64, reason: String) -> Self {
+        Self {
+            code: -32602,
+            message: format!("Invalid params: {}, got {}", reason, limit),
+            data: Some(ErrorData::InvalidLimit(InvalidLimit { limit, max_limit })),
+        }
+    }
+
     pub fn method_not_found() -> Self {
         Self {
             code: -32601,
@@ -108,6 +190,105 @@ impl JsonRpcError {
         }
     }
 
+    pub fn server_overloaded() -> Self {
+        Self::internal_error("server overloaded".to_string())
+    }
+
+    pub fn storage_unavailable() -> Self {
+        Self {
+            code: ServerCode::StorageUnavailable as i16,
+            message: "Server error: storage unavailable, retry later".to_string(),
+            data: None,
+        }
+    }
+
+    pub fn state_pruned(version: u64) -> Self {
+        Self {
+            code: ServerCode::StatePruned as i16,
+            message: format!("Server error: state at version {} is pruned", version),
+            data: None,
+        }
+    }
+
+    pub fn unauthorized() -> Self {
+        Self {
+            code: ServerCode::Unauthorized as i16,
+            message: "Server error: API key not authorized for this method".to_string(),
+            data: None,
+        }
+    }
+
+    pub fn node_too_stale(version: u64, min_ledger_version: u64) -> Self {
+        Self {
+            code: ServerCode::NodeTooStale as i16,
+            message: format!(
+                "Server error: ledger version {} is behind the minimum ledger version {}",
+                version, min_ledger_version
+            ),
+            data: None,
+        }
+    }
+
+    pub fn response_too_large(size: usize, max_size: usize) -> Self {
+        Self {
+            code: ServerCode::ResponseTooLarge as i16,
+            message: "Server error: response too large, reduce limit".to_string(),
+            data: Some(ErrorData::ResponseTooLarge(ResponseTooLarge {
+                size,
+                max_size,
+            })),
+        }
+    }
+
+    pub fn batch_too_costly(cost: u64, budget: u64) -> Self {
+        Self {
+            code: ServerCode::BatchTooCostly as i16,
+            message: format!(
+                "Server error: batch cost {} exceeds budget {}, split the batch",
+                cost, budget
+            ),
+            data: None,
+        }
+    }
+
+    pub fn audit_log_unavailable() -> Self {
+        Self {
+            code: ServerCode::AuditLogUnavailable as i16,
+            message: "Server error: audit log unavailable, submission rejected".to_string(),
+            data: None,
+        }
+    }
+
+    pub fn sequence_in_flight(sender: AccountAddress, sequence_number: u64) -> Self {
+        Self {
+            code: ServerCode::SequenceInFlight as i16,
+            message: format!(
+                "Server error: sequence number {} of {} already in flight",
+                sequence_number, sender
+            ),
+            data: None,
+        }
+    }
+
+    pub fn mempool_unavailable() -> Self {
+        Self {
+            code: ServerCode::MempoolUnavailable as i16,
+            message: "Server error: mempool unavailable, retry later".to_string(),
+            data: None,
+        }
+    }
+
+    pub fn submission_status_unknown(hash: &str) -> Self {
+        Self {
+            code: ServerCode::SubmissionStatusUnknown as i16,
+            message: format!(
+                "Server error: submission status unknown, poll for transaction {} before retrying",
+                hash
+            ),
+            data: None,
+        }
+    }
+
     pub fn mempool_error(error: MempoolStatus) -> Result<Self> {
         let code = match error.code {
             MempoolStatusCode::InvalidSeqNumber => ServerCode::MempoolInvalidSeqNumber,
@@ -165,4 +346,25 @@ impl JsonRpcError {
         }
         None
     }
+
+    pub fn as_response_too_large(&self) -> Option<ResponseTooLarge> {
+        if let Some(ErrorData::ResponseTooLarge(data)) = &self.data {
+            return Some(*data);
+        }
+        None
+    }
+
+    pub fn as_invalid_limit(&self) -> Option<InvalidLimit> {
+        if let Some(ErrorData::InvalidLimit(data)) = &self.data {
+            return Some(*data);
+        }
+        None
+    }
+
+    pub fn as_invalid_param(&self) -> Option<InvalidParam> {
+        if let Some(ErrorData::InvalidParam(data)) = &self.data {
+            return Some(data.clone());
+        }
+        None
+    }
 }
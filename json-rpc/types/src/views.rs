@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::errors::JsonRpcError;
 use anyhow::{format_err, Error, Result};
 use libra_crypto::HashValue;
 use libra_types::{
@@ -14,6 +15,7 @@ use libra_types::{
     contract_event::ContractEvent,
     epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{ScriptPublishingOption, VMPublishingOption},
     proof::{AccountStateProof, AccumulatorConsistencyProof},
     transaction::{Transaction, TransactionArgument, TransactionPayload},
     vm_status::KeptVMStatus,
@@ -30,6 +32,8 @@ use transaction_builder::get_transaction_name;
 pub const JSONRPC_LIBRA_CHAIN_ID: &str = "libra_chain_id";
 pub const JSONRPC_LIBRA_LEDGER_VERSION: &str = "libra_ledger_version";
 pub const JSONRPC_LIBRA_LEDGER_TIMESTAMPUSECS: &str = "libra_ledger_timestampusec";
+pub const JSONRPC_LIBRA_REQUEST_ID: &str = "libra_request_id";
+pub const JSONRPC_LIBRA_DEPRECATION: &str = "libra_deprecation";
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct AmountView {
@@ -82,6 +86,18 @@ pub struct AccountView {
     pub delegated_withdrawal_capability: bool,
     pub is_frozen: bool,
     pub role: AccountRoleView,
+    /// Parent VASP of a child VASP account, copied from `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_vasp_address: Option<BytesView>,
+    /// Number of children of a parent VASP account, copied from `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_children: Option<u64>,
+    /// Compliance key of a parent VASP or designated dealer account, copied from `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compliance_key: Option<BytesView>,
+    /// Key of the received mint events of a designated dealer account, copied from `role`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received_mint_events_key: Option<BytesView>,
 }
 
 impl AccountView {
@@ -105,9 +121,50 @@ impl AccountView {
             delegated_key_rotation_capability: account.has_delegated_key_rotation_capability(),
             delegated_withdrawal_capability: account.has_delegated_withdrawal_capability(),
             is_frozen: freezing_bit.is_frozen(),
-            role: AccountRoleView::from(account_role),
+            role: AccountRoleView::Unknown,
+            parent_vasp_address: None,
+            num_children: None,
+            compliance_key: None,
+            received_mint_events_key: None,
         }
+        .with_role(AccountRoleView::from(account_role))
     }
+
+    /// Sets the role of the account, and the fields specific to that role
+    fn with_role(mut self, role: AccountRoleView) -> Self {
+        match &role {
+            AccountRoleView::Unknown => (),
+            AccountRoleView::ChildVASP {
+                parent_vasp_address,
+            } => self.parent_vasp_address = Some(parent_vasp_address.clone()),
+            AccountRoleView::ParentVASP {
+                compliance_key,
+                num_children,
+                ..
+            } => {
+                self.compliance_key = Some(compliance_key.clone());
+                self.num_children = Some(*num_children);
+            }
+            AccountRoleView::DesignatedDealer {
+                compliance_key,
+                received_mint_events_key,
+                ..
+            } => {
+                self.compliance_key = Some(compliance_key.clone());
+                self.received_mint_events_key = Some(received_mint_events_key.clone());
+            }
+        }
+        self.role = role;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TreasuryComplianceView {
+    pub address: String,
+    pub sequence_number: u64,
+    pub authentication_key: BytesView,
+    pub is_frozen: bool,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -115,6 +172,13 @@ pub struct EventView {
     pub key: BytesView,
     pub sequence_number: u64,
     pub transaction_version: u64,
+    /// Hex encoded hash of the transaction that emitted the event, None if unknown
+    pub transaction_hash: Option<String>,
+    /// Index of the event among the events emitted by its transaction, None when the event was
+    /// fetched from its event stream
+    pub event_index: Option<u64>,
+    /// Move type of the event, in the syntax of the Move type parser with full length addresses
+    pub type_tag: Option<String>,
     pub data: EventDataView,
 }
 
@@ -316,11 +380,43 @@ impl From<(u64, ContractEvent)> for EventView {
             key: BytesView::from(event.key().as_bytes()),
             sequence_number: event.sequence_number(),
             transaction_version: txn_version,
+            transaction_hash: None,
+            event_index: None,
+            type_tag: Some(canonical_type_tag(event.type_tag())),
             data: event_data.unwrap_or(EventDataView::Unknown {}),
         }
     }
 }
 
+/// Returns the type tag in the syntax of the Move type parser, with full length addresses
+fn canonical_type_tag(type_tag: &TypeTag) -> String {
+    match type_tag {
+        TypeTag::Bool => "bool".to_string(),
+        TypeTag::U8 => "u8".to_string(),
+        TypeTag::U64 => "u64".to_string(),
+        TypeTag::U128 => "u128".to_string(),
+        TypeTag::Address => "address".to_string(),
+        TypeTag::Signer => "signer".to_string(),
+        TypeTag::Vector(type_tag) => format!("vector<{}>", canonical_type_tag(type_tag)),
+        TypeTag::Struct(struct_tag) => {
+            let name = format!(
+                "{}::{}::{}",
+                struct_tag.address, struct_tag.module, struct_tag.name
+            );
+            if struct_tag.type_params.is_empty() {
+                name
+            } else {
+                let type_params: Vec<_> = struct_tag
+                    .type_params
+                    .iter()
+                    .map(canonical_type_tag)
+                    .collect();
+                format!("{}<{}>", name, type_params.join(", "))
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct BlockMetadata {
     pub version: u64,
@@ -368,6 +464,9 @@ pub enum VMStatusView {
     DeserializationError,
     #[serde(rename = "publishing_failure")]
     PublishingFailure,
+    /// Not executed yet, the transaction is still in mempool
+    #[serde(rename = "pending")]
+    Pending,
 }
 
 impl From<&KeptVMStatus> for VMStatusView {
@@ -402,6 +501,19 @@ pub struct TransactionView {
     pub events: Vec<EventView>,
     pub vm_status: VMStatusView,
     pub gas_used: u64,
+    /// Currency the gas was paid in, None for transactions not sent by users
+    pub gas_currency: Option<String>,
+    /// Size in bytes of the LCS serialized transaction, 0 from servers not reporting it
+    #[serde(default)]
+    pub bytes: u64,
+    /// LCS serialized `TransactionInfoWithProof` of the transaction, relative to the ledger version
+    /// of the response. None unless requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<BytesView>,
+    /// Whether events of the transaction were left out of `events` to bound the response size,
+    /// in which case they can be read with `get_account_transaction` or `get_events`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub events_truncated: bool,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -450,10 +562,53 @@ pub enum ScriptView {
         auth_key_prefix: BytesView,
         amount: u64,
     },
+    #[serde(rename = "script")]
+    Script {
+        name: String,
+        code: BytesView,
+        type_arguments: Vec<String>,
+        arguments: Vec<TransactionArgumentView>,
+    },
     #[serde(rename = "unknown_transaction")]
     Unknown {},
 }
 
+/// Argument of a script, alongside its type
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", content = "value")]
+pub enum TransactionArgumentView {
+    #[serde(rename = "u8")]
+    U8(u8),
+    #[serde(rename = "u64")]
+    U64(u64),
+    /// Decimal string, as JSON numbers can't represent every u128
+    #[serde(rename = "u128")]
+    U128(String),
+    #[serde(rename = "address")]
+    Address(String),
+    #[serde(rename = "u8_vector")]
+    U8Vector(BytesView),
+    #[serde(rename = "bool")]
+    Bool(bool),
+}
+
+impl From<&TransactionArgument> for TransactionArgumentView {
+    fn from(argument: &TransactionArgument) -> Self {
+        match argument {
+            TransactionArgument::U8(value) => TransactionArgumentView::U8(*value),
+            TransactionArgument::U64(value) => TransactionArgumentView::U64(*value),
+            TransactionArgument::U128(value) => TransactionArgumentView::U128(value.to_string()),
+            TransactionArgument::Address(address) => {
+                TransactionArgumentView::Address(address.to_string())
+            }
+            TransactionArgument::U8Vector(bytes) => {
+                TransactionArgumentView::U8Vector(BytesView::from(bytes))
+            }
+            TransactionArgument::Bool(value) => TransactionArgumentView::Bool(*value),
+        }
+    }
+}
+
 impl ScriptView {
     // TODO cover all script types
 }
@@ -534,33 +689,29 @@ impl From<AccountRole> for AccountRoleView {
 
 impl From<TransactionPayload> for ScriptView {
     fn from(value: TransactionPayload) -> Self {
-        let empty_vec: Vec<TransactionArgument> = vec![];
-        let empty_ty_vec: Vec<String> = vec![];
-        let unknown_currency = "unknown_currency".to_string();
-
-        let (code, args, ty_args) = match value {
-            TransactionPayload::WriteSet(_) => ("genesis".to_string(), empty_vec, empty_ty_vec),
-            TransactionPayload::Script(script) => (
-                get_transaction_name(script.code()),
-                script.args().to_vec(),
-                script
-                    .ty_args()
-                    .iter()
-                    .map(|type_tag| match type_tag {
-                        TypeTag::Struct(StructTag { module, .. }) => module.to_string(),
-                        tag => format!("{}", tag),
-                    })
-                    .collect(),
-            ),
-            TransactionPayload::Module(_) => {
-                ("module publishing".to_string(), empty_vec, empty_ty_vec)
+        let script = match value {
+            TransactionPayload::Script(script) => script,
+            TransactionPayload::WriteSet(_) | TransactionPayload::Module(_) => {
+                return ScriptView::Unknown {}
             }
         };
+        let unknown_currency = "unknown_currency".to_string();
+
+        let code = get_transaction_name(script.code());
+        let args = script.args();
+        let ty_args: Vec<String> = script
+            .ty_args()
+            .iter()
+            .map(|type_tag| match type_tag {
+                TypeTag::Struct(StructTag { module, .. }) => module.to_string(),
+                tag => format!("{}", tag),
+            })
+            .collect();
 
         let res = match code.as_str() {
             "peer_to_peer_with_metadata_transaction" => {
                 if let [TransactionArgument::Address(receiver), TransactionArgument::U64(amount), TransactionArgument::U8Vector(metadata), TransactionArgument::U8Vector(metadata_signature)] =
-                    &args[..]
+                    args
                 {
                     Ok(ScriptView::PeerToPeer {
                         receiver: receiver.to_string(),
@@ -575,7 +726,7 @@ impl From<TransactionPayload> for ScriptView {
             }
             "mint" => {
                 if let [TransactionArgument::Address(receiver), TransactionArgument::U8Vector(auth_key_prefix), TransactionArgument::U64(amount)] =
-                    &args[..]
+                    args
                 {
                     let currency = ty_args.get(0).unwrap_or(&unknown_currency).to_string();
                     Ok(ScriptView::Mint {
@@ -590,16 +741,31 @@ impl From<TransactionPayload> for ScriptView {
             }
             _ => Err(format_err!("Unknown scripts")),
         };
-        res.unwrap_or(ScriptView::Unknown {})
+        // Scripts without a dedicated view keep their raw code, so that clients can still
+        // verify the script hash, alongside their best-effort decoded arguments.
+        res.unwrap_or_else(|_| ScriptView::Script {
+            name: code,
+            code: BytesView::from(script.code()),
+            type_arguments: script.ty_args().iter().map(ToString::to_string).collect(),
+            arguments: args.iter().map(TransactionArgumentView::from).collect(),
+        })
     }
 }
 
+fn default_can_mint() -> bool {
+    true
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct CurrencyInfoView {
     pub code: String,
     pub scaling_factor: u64,
     pub fractional_part: u64,
     pub to_lbr_exchange_rate: f32,
+    /// False once the currency was retired by disabling its minting, true from servers not
+    /// reporting it
+    #[serde(default = "default_can_mint")]
+    pub can_mint: bool,
     pub mint_events_key: BytesView,
     pub burn_events_key: BytesView,
     pub preburn_events_key: BytesView,
@@ -614,6 +780,7 @@ impl From<CurrencyInfoResource> for CurrencyInfoView {
             scaling_factor: info.scaling_factor(),
             fractional_part: info.fractional_part(),
             to_lbr_exchange_rate: info.exchange_rate(),
+            can_mint: info.can_mint(),
             mint_events_key: BytesView::from(info.mint_events().key().as_bytes()),
             burn_events_key: BytesView::from(info.burn_events().key().as_bytes()),
             preburn_events_key: BytesView::from(info.preburn_events().key().as_bytes()),
@@ -625,11 +792,166 @@ impl From<CurrencyInfoResource> for CurrencyInfoView {
     }
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GasEstimateView {
+    pub currency: String,
+    pub sample_size: u64,
+    pub min_gas_unit_price: u64,
+    pub median_gas_unit_price: u64,
+    pub p90_gas_unit_price: u64,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct AccountDiffView {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct NetworkStatusView {
+    pub connected_peers: u64,
+    pub networks: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServerVersionView {
+    pub version: String,
+    pub git_commit: String,
+    /// Unix timestamp, in seconds, at which the server was built
+    pub build_timestamp: u64,
+}
+
+/// Features and limits of a server, for clients to adapt to its configuration
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CapabilitiesView {
+    /// Names of the methods served, excluding the version 2 names of unchanged methods
+    pub methods: Vec<String>,
+    /// Names of the deprecated methods, still served until their sunset date
+    pub deprecated_methods: Vec<String>,
+    /// Whether requests can be batched
+    pub batch: bool,
+    /// Maximum total cost of the requests of a batch, None if unlimited
+    pub max_batch_cost: Option<u64>,
+    /// Maximum limit of a `get_transactions` call
+    pub max_transactions_limit: u64,
+    /// Maximum limit of a `get_events` call
+    pub max_events_limit: u64,
+    /// Maximum size in bytes of the result of methods returning transactions or events, None if
+    /// unlimited
+    pub max_response_size: Option<u64>,
+    /// Encodings requests and responses can be sent in
+    pub encodings: Vec<String>,
+    /// Whether clients can subscribe to be notified of new transactions or events
+    pub subscriptions: bool,
+    /// Whether state can be read at any version, rather than only within the prune window
+    pub historical_reads: bool,
+    /// Number of versions of state kept by storage, None if storage isn't pruned
+    pub prune_window: Option<u64>,
+    /// Whether transactions are returned with their events when `include_events` isn't given
+    pub include_events_by_default: bool,
+}
+
+/// Configuration of a node affecting how its API behaves.
+/// Only holds settings safe to disclose: keys, addresses and other secrets are never part of it
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct NodeConfigView {
+    /// "validator" or "full_node"
+    pub role: String,
+    pub chain_id: u8,
+    /// Maximum limit of a `get_transactions` call
+    pub max_transactions_limit: u64,
+    /// Maximum limit of a `get_events` call
+    pub max_events_limit: u64,
+    /// Maximum size in bytes of the result of methods returning transactions or events, None if
+    /// unlimited
+    pub max_response_size: Option<u64>,
+    /// Maximum number of events returned with each transaction of a `get_transactions` call, None
+    /// if unlimited
+    pub max_events_per_transaction: Option<u64>,
+    /// Maximum total number of events returned by a `get_transactions` call, None if unlimited
+    pub max_transactions_events: Option<u64>,
+    /// Maximum total cost of the requests of a batch, None if unlimited
+    pub max_batch_cost: Option<u64>,
+    /// Whether `submit` can only be called with an API key allowing it
+    pub submit_restricted: bool,
+    /// Number of versions of state kept by storage, None if storage isn't pruned
+    pub prune_window: Option<u64>,
+    /// Encodings requests and responses can be sent in
+    pub encodings: Vec<String>,
+    /// Whether transactions are returned with their events when `include_events` isn't given
+    pub include_events_by_default: bool,
+    /// Whether unsigned integers of results are strings when the `x-u64-format` header isn't given
+    pub u64_as_string_by_default: bool,
+}
+
+/// Page of events returned by version 2 of `get_events`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EventsPageView {
+    pub events: Vec<EventView>,
+    /// Opaque cursor to pass as the `start` param to get the following events
+    pub next_cursor: String,
+}
+
+/// Entry of a `batch_fetch` result, in the order of the requested reads
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum FetchResultView {
+    /// Hex encoded LCS serialized resource, None if the account or the resource doesn't exist
+    Resource(Option<BytesView>),
+    /// None if the account doesn't hold the currency
+    Balance(Option<AmountView>),
+    Events(Vec<EventView>),
+}
+
+/// Module published on chain
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ModuleView {
+    /// Address of the account the module is published under
+    pub address: String,
+    pub name: String,
+    /// Serialized bytecode of the module
+    pub bytecode: BytesView,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PeerView {
+    pub peer_id: String,
+    pub network: String,
+    pub direction: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct VMPublishingOptionView {
+    pub is_open_script: bool,
+    pub script_allow_list: Vec<BytesView>,
+    pub is_open_module: bool,
+}
+
+impl From<VMPublishingOption> for VMPublishingOptionView {
+    fn from(option: VMPublishingOption) -> VMPublishingOptionView {
+        let script_allow_list = match &option.script_option {
+            ScriptPublishingOption::Locked(allow_list) => allow_list
+                .iter()
+                .map(|hash| BytesView::from(&hash[..]))
+                .collect(),
+            ScriptPublishingOption::CustomScripts => vec![],
+        };
+        VMPublishingOptionView {
+            is_open_script: option.script_option == ScriptPublishingOption::CustomScripts,
+            script_allow_list,
+            is_open_module: option.is_open_module(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct StateProofView {
     pub ledger_info_with_signatures: BytesView,
     pub epoch_change_proof: BytesView,
-    pub ledger_consistency_proof: BytesView,
+    /// None if only the epoch change proof was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ledger_consistency_proof: Option<BytesView>,
 }
 
 impl
@@ -653,7 +975,36 @@ impl
                 &ledger_info_with_signatures,
             )?),
             epoch_change_proof: BytesView::from(&lcs::to_bytes(&epoch_change_proof)?),
-            ledger_consistency_proof: BytesView::from(&lcs::to_bytes(&ledger_consistency_proof)?),
+            ledger_consistency_proof: Some(BytesView::from(&lcs::to_bytes(
+                &ledger_consistency_proof,
+            )?)),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochChangeProofView {
+    pub ledger_info_with_signatures: BytesView,
+    pub epoch_change_proof: BytesView,
+    /// Whether epoch changes beyond the ones in `epoch_change_proof` are left to fetch
+    pub more: bool,
+}
+
+impl TryFrom<(LedgerInfoWithSignatures, EpochChangeProof)> for EpochChangeProofView {
+    type Error = Error;
+
+    fn try_from(
+        (ledger_info_with_signatures, epoch_change_proof): (
+            LedgerInfoWithSignatures,
+            EpochChangeProof,
+        ),
+    ) -> Result<EpochChangeProofView, Self::Error> {
+        Ok(EpochChangeProofView {
+            ledger_info_with_signatures: BytesView::from(&lcs::to_bytes(
+                &ledger_info_with_signatures,
+            )?),
+            more: epoch_change_proof.more,
+            epoch_change_proof: BytesView::from(&lcs::to_bytes(&epoch_change_proof)?),
         })
     }
 }
@@ -684,6 +1035,27 @@ impl TryFrom<AccountStateWithProof> for AccountStateWithProofView {
     }
 }
 
+/// Account state at a version alongside its proof relative to the state root of that version, as
+/// returned by `get_account_state_with_proof_by_version`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountStateWithSparseProofView {
+    pub version: u64,
+    /// Hex encoded LCS serialized `AccountStateBlob`, None if the account doesn't exist at the
+    /// version
+    pub blob: Option<BytesView>,
+    /// Hex encoded LCS serialized `SparseMerkleProof` of the blob, or of its absence
+    pub proof: BytesView,
+}
+
+/// Entry of a `get_account_state_with_proofs` result: the account state proof at the requested
+/// version, or the error which prevented serving it
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AccountStateWithProofResultView {
+    Ok(AccountStateWithProofView),
+    Err { error: JsonRpcError },
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct AccountStateProofView {
     pub ledger_info_to_transaction_info_proof: BytesView,
@@ -712,3 +1084,48 @@ impl TryFrom<AccountStateProof> for AccountStateProofView {
         })
     }
 }
+
+/// Transaction info of a version, without its transaction, as returned by
+/// `get_transaction_infos`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionInfoView {
+    pub version: u64,
+    /// Hash of the transaction, as in `TransactionView`
+    pub transaction_hash: String,
+    /// Root hash of the state tree once the transaction is applied
+    pub state_root_hash: String,
+    /// Root hash of the accumulator of the events emitted by the transaction
+    pub event_root_hash: String,
+    pub gas_used: u64,
+    pub vm_status: VMStatusView,
+}
+
+/// Signed ledger info, as returned by `get_ledger_info_at_version`
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LedgerInfoView {
+    /// Version whose accumulator root hash the ledger info signs
+    pub version: u64,
+    pub epoch: u64,
+    /// Whether the ledger info ends its epoch, carrying the validator set of the next epoch
+    pub ends_epoch: bool,
+    /// LCS serialized `LedgerInfoWithSignatures`
+    pub ledger_info_with_signatures: BytesView,
+}
+
+impl TryFrom<&LedgerInfoWithSignatures> for LedgerInfoView {
+    type Error = Error;
+
+    fn try_from(
+        ledger_info_with_signatures: &LedgerInfoWithSignatures,
+    ) -> Result<LedgerInfoView, Self::Error> {
+        let ledger_info = ledger_info_with_signatures.ledger_info();
+        Ok(LedgerInfoView {
+            version: ledger_info.version(),
+            epoch: ledger_info.epoch(),
+            ends_epoch: ledger_info.ends_epoch(),
+            ledger_info_with_signatures: BytesView::from(&lcs::to_bytes(
+                ledger_info_with_signatures,
+            )?),
+        })
+    }
+}
@@ -21,7 +21,7 @@ use libradb::LibraDB;
 use network_builder::builder::NetworkBuilder;
 use state_synchronizer::StateSynchronizer;
 use std::{boxed::Box, net::ToSocketAddrs, sync::Arc, thread, time::Instant};
-use storage_interface::DbReaderWriter;
+use storage_interface::{DbReader, DbReaderWriter};
 use storage_service::start_storage_service_with_db;
 use tokio::runtime::{Builder, Runtime};
 
@@ -216,7 +216,16 @@ pub fn setup_environment(node_config: &NodeConfig) -> LibraHandle {
     );
     let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
-    let rpc_runtime = bootstrap_rpc(&node_config, libra_db.clone(), mp_client_sender);
+    let secondary_db = node_config.rpc.secondary_db_dir.as_ref().map(|dir| {
+        Arc::new(LibraDB::open(dir, true /* readonly */, None).expect("Secondary DB should open."))
+            as Arc<dyn DbReader>
+    });
+    let rpc_runtime = bootstrap_rpc(
+        &node_config,
+        libra_db.clone(),
+        secondary_db,
+        mp_client_sender,
+    );
 
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
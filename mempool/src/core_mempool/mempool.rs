@@ -252,6 +252,15 @@ impl Mempool {
             .gc_by_expiration_time(block_time, &self.metrics_cache);
     }
 
+    /// Returns up to `limit` transactions sent by the given account, ordered by sequence number
+    pub(crate) fn get_by_sender(
+        &self,
+        address: &AccountAddress,
+        limit: usize,
+    ) -> Vec<SignedTransaction> {
+        self.transactions.get_by_sender(address, limit)
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`
     /// Returns block of transactions and new last_timeline_id
     pub(crate) fn read_timeline(
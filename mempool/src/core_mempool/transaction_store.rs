@@ -85,6 +85,23 @@ impl TransactionStore {
         None
     }
 
+    /// fetch up to `limit` transactions of account address, ordered by sequence_number
+    pub(crate) fn get_by_sender(
+        &self,
+        address: &AccountAddress,
+        limit: usize,
+    ) -> Vec<SignedTransaction> {
+        self.transactions
+            .get(&address)
+            .map(|txns| {
+                txns.values()
+                    .take(limit)
+                    .map(|txn| txn.txn.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// insert transaction into TransactionStore
     /// performs validation checks and updates indexes
     pub(crate) fn insert(
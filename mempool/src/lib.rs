@@ -63,8 +63,8 @@ pub use shared_mempool::{
     bootstrap, network,
     types::{
         gen_mempool_reconfig_subscription, CommitNotification, CommitResponse,
-        CommittedTransaction, ConsensusRequest, ConsensusResponse, MempoolClientSender,
-        SubmissionStatus, TransactionExclusion,
+        CommittedTransaction, ConsensusRequest, ConsensusResponse, MempoolClientRequest,
+        MempoolClientSender, SubmissionStatus, TransactionExclusion,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
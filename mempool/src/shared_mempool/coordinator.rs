@@ -11,21 +11,20 @@ use crate::{
         tasks,
         types::{notify_subscribers, SharedMempool, SharedMempoolNotification},
     },
-    CommitNotification, ConsensusRequest, SubmissionStatus,
+    CommitNotification, ConsensusRequest, MempoolClientRequest,
 };
 use ::network::protocols::network::Event;
-use anyhow::Result;
 use bounded_executor::BoundedExecutor;
 use channel::libra_channel;
 use futures::{
-    channel::{mpsc, oneshot},
+    channel::mpsc,
     stream::{select_all, FuturesUnordered},
     StreamExt,
 };
 use libra_config::{config::PeerNetworkId, network_id::NodeNetworkId};
 use libra_logger::prelude::*;
 use libra_trace::prelude::*;
-use libra_types::{on_chain_config::OnChainConfigPayload, transaction::SignedTransaction};
+use libra_types::on_chain_config::OnChainConfigPayload;
 use std::{
     ops::Deref,
     sync::{Arc, Mutex},
@@ -39,10 +38,7 @@ pub(crate) async fn coordinator<V>(
     mut smp: SharedMempool<V>,
     executor: Handle,
     network_events: Vec<(NodeNetworkId, MempoolNetworkEvents)>,
-    mut client_events: mpsc::Receiver<(
-        SignedTransaction,
-        oneshot::Sender<Result<SubmissionStatus>>,
-    )>,
+    mut client_events: mpsc::Receiver<MempoolClientRequest>,
     mut consensus_requests: mpsc::Receiver<ConsensusRequest>,
     mut state_sync_requests: mpsc::Receiver<CommitNotification>,
     mut mempool_reconfig_events: libra_channel::Receiver<(), OnChainConfigPayload>,
@@ -66,18 +62,39 @@ pub(crate) async fn coordinator<V>(
 
     loop {
         ::futures::select! {
-            (mut msg, callback) = client_events.select_next_some() => {
-                trace_event!("mempool::client_event", {"txn", msg.sender(), msg.sequence_number()});
-                let _ = counters::TASK_SPAWN_LATENCY
-                .with_label_values(&[counters::CLIENT_EVENT_LABEL])
-                .start_timer();
-                bounded_executor
-                .spawn(tasks::process_client_transaction_submission(
-                    smp.clone(),
-                    msg,
-                    callback,
-                ))
-                .await;
+            request = client_events.select_next_some() => match request {
+                MempoolClientRequest::SubmitTransaction(mut msg, callback) => {
+                    trace_event!("mempool::client_event", {"txn", msg.sender(), msg.sequence_number()});
+                    let _ = counters::TASK_SPAWN_LATENCY
+                    .with_label_values(&[counters::CLIENT_EVENT_LABEL])
+                    .start_timer();
+                    bounded_executor
+                    .spawn(tasks::process_client_transaction_submission(
+                        smp.clone(),
+                        msg,
+                        callback,
+                    ))
+                    .await;
+                }
+                MempoolClientRequest::SubmitTransactions(submissions) => {
+                    let _ = counters::TASK_SPAWN_LATENCY
+                    .with_label_values(&[counters::CLIENT_EVENT_LABEL])
+                    .start_timer();
+                    bounded_executor
+                    .spawn(tasks::process_client_transactions_submission(
+                        smp.clone(),
+                        submissions,
+                    ))
+                    .await;
+                }
+                MempoolClientRequest::GetAccountTransactions(address, limit, callback) => {
+                    let txns = mempool
+                        .lock()
+                        .expect("[shared mempool] failed to acquire mempool lock")
+                        .get_by_sender(&address, limit);
+                    // the client may have given up waiting
+                    let _ = callback.send(txns);
+                }
             },
             msg = consensus_requests.select_next_some() => {
                 tasks::process_consensus_request(&mempool, msg).await;
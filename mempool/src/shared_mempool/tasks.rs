@@ -240,6 +240,32 @@ pub(crate) async fn process_client_transaction_submission<V>(
     }
 }
 
+/// processes transactions submitted together by clients, replying to each with its status
+pub(crate) async fn process_client_transactions_submission<V>(
+    smp: SharedMempool<V>,
+    submissions: Vec<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+) where
+    V: TransactionValidation,
+{
+    let _timer = counters::PROCESS_TXN_SUBMISSION_LATENCY
+        .with_label_values(&["client"])
+        .start_timer();
+    let (transactions, callbacks): (Vec<_>, Vec<_>) = submissions.into_iter().unzip();
+    let statuses = process_transactions_in_order(&smp, transactions, TimelineState::NotReady).await;
+    let results: Vec<_> = statuses.iter().flatten().cloned().collect();
+    log_txn_process_results(&results, None);
+
+    for (status, callback) in statuses.into_iter().zip(callbacks) {
+        match status {
+            // the client may have given up waiting
+            Some(status) => {
+                let _ = callback.send(Ok(status));
+            }
+            None => error!("[shared mempool] missing status for client transaction submission"),
+        }
+    }
+}
+
 /// processes transactions from other nodes
 pub(crate) async fn process_transaction_broadcast<V>(
     mut smp: SharedMempool<V>,
@@ -330,7 +356,25 @@ async fn process_incoming_transactions<V>(
 where
     V: TransactionValidation,
 {
-    let mut statuses = vec![];
+    process_transactions_in_order(smp, transactions, timeline_state)
+        .await
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Validates the given transactions and adds the valid ones to mempool, returning the status of
+/// each transaction in the order they were given. The status is None if the transaction couldn't
+/// be validated
+async fn process_transactions_in_order<V>(
+    smp: &SharedMempool<V>,
+    transactions: Vec<SignedTransaction>,
+    timeline_state: TimelineState,
+) -> Vec<Option<SubmissionStatus>>
+where
+    V: TransactionValidation,
+{
+    let mut statuses = vec![None; transactions.len()];
 
     let start_storage_read = Instant::now();
     // track latency: fetching seq number
@@ -350,16 +394,16 @@ where
         .filter_map(|(idx, t)| {
             if let Ok(sequence_number) = seq_numbers[idx] {
                 if t.sequence_number() >= sequence_number {
-                    return Some((t, sequence_number));
+                    return Some((idx, t, sequence_number));
                 } else {
-                    statuses.push((
+                    statuses[idx] = Some((
                         MempoolStatus::new(MempoolStatusCode::VmError),
                         Some(DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD),
                     ));
                 }
             } else {
                 // failed to get transaction
-                statuses.push((
+                statuses[idx] = Some((
                     MempoolStatus::new(MempoolStatusCode::VmError),
                     Some(DiscardedVMStatus::RESOURCE_DOES_NOT_EXIST),
                 ));
@@ -378,7 +422,7 @@ where
             smp.validator
                 .read()
                 .unwrap()
-                .validate_transaction(t.0.clone())
+                .validate_transaction(t.1.clone())
         })
         .collect::<Vec<_>>();
     vm_validation_timer.stop_and_record();
@@ -388,8 +432,9 @@ where
             .mempool
             .lock()
             .expect("[shared mempool] failed to acquire mempool lock");
-        for (idx, (transaction, sequence_number)) in transactions.into_iter().enumerate() {
-            if let Ok(validation_result) = &validation_results[idx] {
+        for (position, (idx, transaction, sequence_number)) in transactions.into_iter().enumerate()
+        {
+            if let Ok(validation_result) = &validation_results[position] {
                 match validation_result.status() {
                     None => {
                         let gas_amount = transaction.max_gas_amount();
@@ -403,10 +448,10 @@ where
                             timeline_state,
                             governance_role,
                         );
-                        statuses.push((mempool_status, None));
+                        statuses[idx] = Some((mempool_status, None));
                     }
                     Some(validation_status) => {
-                        statuses.push((
+                        statuses[idx] = Some((
                             MempoolStatus::new(MempoolStatusCode::VmError),
                             Some(validation_status),
                         ));
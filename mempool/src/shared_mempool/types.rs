@@ -190,9 +190,24 @@ pub struct TransactionExclusion {
 /// Submission Status is represented as combination of vm_validator internal status and core mempool insertion status
 pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 
-/// sender type: used to enqueue new transactions to shared mempool by client endpoints
-pub type MempoolClientSender =
-    mpsc::Sender<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>;
+/// Request from a client endpoint to shared mempool
+pub enum MempoolClientRequest {
+    /// Enqueues a new transaction, replying with its submission status
+    SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
+    /// Enqueues the given transactions together, replying to each with its submission status.
+    /// Cheaper than submitting them one at a time when many are submitted at once
+    SubmitTransactions(Vec<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>),
+    /// Replies with up to the given number of pending transactions sent by the account, ordered
+    /// by sequence number
+    GetAccountTransactions(
+        AccountAddress,
+        usize,
+        oneshot::Sender<Vec<SignedTransaction>>,
+    ),
+}
+
+/// sender type: used to send requests to shared mempool by client endpoints
+pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
 
 /// On-chain configs that mempool subscribes to for reconfiguration
 const MEMPOOL_SUBSCRIBED_CONFIGS: &[ConfigID] = &[LibraVersion::CONFIG_ID, VMConfig::CONFIG_ID];
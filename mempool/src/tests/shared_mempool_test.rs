@@ -7,7 +7,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
     shared_mempool::{start_shared_mempool, types::SharedMempoolNotification},
     tests::common::{batch_add_signed_txn, TestTransaction},
-    CommitNotification, CommittedTransaction, ConsensusRequest,
+    CommitNotification, CommittedTransaction, ConsensusRequest, MempoolClientRequest,
 };
 use channel::{self, libra_channel, message_queues::QueueStyle};
 use futures::{
@@ -24,9 +24,12 @@ use libra_config::{
     config::{NetworkConfig, NodeConfig, RoleType, UpstreamConfig},
     network_id::{NetworkContext, NetworkId, NodeNetworkId},
 };
+use libra_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
 use libra_network_address::NetworkAddress;
 use libra_types::{
+    mempool_status::MempoolStatusCode,
     transaction::{GovernanceRole, SignedTransaction},
+    vm_status::StatusCode,
     PeerId,
 };
 use netcore::transport::ConnectionOrigin;
@@ -38,6 +41,7 @@ use network::{
     protocols::network::{NewNetworkEvents, NewNetworkSender},
     DisconnectReason, ProtocolId,
 };
+use rand::{rngs::StdRng, SeedableRng};
 use std::{
     collections::{HashMap, HashSet},
     num::NonZeroUsize,
@@ -1028,3 +1032,58 @@ fn test_rebroadcast_too_many_txns() {
         .collect::<Vec<_>>();
     assert_eq!(vec![2, 3], seq_nums);
 }
+
+#[test]
+fn test_client_transactions_submission() {
+    let smp = MockSharedMempool::new(None);
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let (key, other_key) = (
+        Ed25519PrivateKey::generate(&mut rng),
+        Ed25519PrivateKey::generate(&mut rng),
+    );
+    // signed with a key other than the one it claims
+    let invalid_txn = TestTransaction::new(2, 0, 1)
+        .make_signed_transaction()
+        .into_raw_transaction()
+        .sign(&key, other_key.public_key())
+        .unwrap()
+        .into_inner();
+    let txns = vec![
+        TestTransaction::new(0, 0, 1).make_signed_transaction(),
+        invalid_txn,
+        TestTransaction::new(1, 0, 1).make_signed_transaction(),
+    ];
+
+    // each transaction of the batch gets its own status back
+    let (submissions, callbacks): (Vec<_>, Vec<_>) = txns
+        .into_iter()
+        .map(|txn| {
+            let (callback, callback_rcv) = oneshot::channel();
+            ((txn, callback), callback_rcv)
+        })
+        .unzip();
+    let mut ac_client = smp.ac_client.clone();
+    let statuses: Vec<_> = block_on(async {
+        assert!(ac_client
+            .send(MempoolClientRequest::SubmitTransactions(submissions))
+            .await
+            .is_ok());
+        futures::future::join_all(callbacks).await
+    })
+    .into_iter()
+    .map(|status| status.unwrap().unwrap())
+    .collect();
+    assert_eq!(statuses[0].0.code, MempoolStatusCode::Accepted);
+    assert_eq!(statuses[1].0.code, MempoolStatusCode::VmError);
+    assert_eq!(statuses[1].1, Some(StatusCode::INVALID_SIGNATURE));
+    assert_eq!(statuses[2].0.code, MempoolStatusCode::Accepted);
+
+    let pool = smp
+        .mempool
+        .lock()
+        .expect("[mempool test] failed to acquire mempool lock");
+    for (address, count) in [(0, 1), (1, 1), (2, 0)].iter() {
+        let sender = TestTransaction::get_address(*address);
+        assert_eq!(pool.get_by_sender(&sender, 10).len(), *count);
+    }
+}
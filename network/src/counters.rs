@@ -30,6 +30,18 @@ pub static LIBRA_NETWORK_PEERS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static LIBRA_NETWORK_PEER_CONNECTED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "libra_network_peer_connected",
+        // metric description
+        "Indicates if the network is connected to a peer, only set for peers currently connected",
+        // metric labels (dimensions)
+        &["role_type", "network_id", "peer_id", "origin"]
+    )
+    .unwrap()
+});
+
 pub static LIBRA_NETWORK_DISCOVERY_NOTES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         // metric name
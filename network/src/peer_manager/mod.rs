@@ -414,10 +414,14 @@ where
                     self.network_context, conn,
                 );
                 // Update libra_network_peer counter.
+                let peer_id = conn.metadata.peer_id();
                 self.add_peer(conn);
                 counters::LIBRA_NETWORK_PEERS
                     .with_label_values(&[self.network_context.role().as_str(), "connected"])
                     .set(self.active_peers.len() as i64);
+                if let Some((conn_metadata, _)) = self.active_peers.get(&peer_id) {
+                    self.set_peer_connected(peer_id, conn_metadata.origin(), true);
+                }
             }
             TransportNotification::Disconnected(lost_conn_metadata, reason) => {
                 // See: https://github.com/libra/libra/issues/3128#issuecomment-605351504 for
@@ -438,6 +442,17 @@ where
                 counters::LIBRA_NETWORK_PEERS
                     .with_label_values(&[self.network_context.role().as_str(), "connected"])
                     .set(self.active_peers.len() as i64);
+                // Unless the peer is still connected in the same direction through a newer
+                // connection, it is no longer connected in the direction of the lost connection.
+                let still_connected = self
+                    .active_peers
+                    .get(&peer_id)
+                    .map_or(false, |(conn_metadata, _)| {
+                        conn_metadata.origin() == lost_conn_metadata.origin()
+                    });
+                if !still_connected {
+                    self.set_peer_connected(peer_id, lost_conn_metadata.origin(), false);
+                }
 
                 // If the connection was explicitly closed by an upstream client, send an ACK.
                 if let Some(oneshot_tx) = self
@@ -591,6 +606,31 @@ where
         }
     }
 
+    /// Updates the gauge of the given peer being connected in the given direction. The gauge of a
+    /// disconnected peer is removed rather than zeroed, so that the labels only ever cover the
+    /// peers currently connected.
+    fn set_peer_connected(&self, peer_id: PeerId, origin: ConnectionOrigin, connected: bool) {
+        let network_id = self.network_context.network_id().to_string();
+        let peer_id = peer_id.to_string();
+        let origin = origin.to_string();
+        let labels = [
+            self.network_context.role().as_str(),
+            network_id.as_str(),
+            peer_id.as_str(),
+            origin.as_str(),
+        ];
+        if connected {
+            counters::LIBRA_NETWORK_PEER_CONNECTED
+                .with_label_values(&labels)
+                .set(1);
+        } else {
+            // the gauge was never set for a connection dropped in favor of an existing one
+            counters::LIBRA_NETWORK_PEER_CONNECTED
+                .remove_label_values(&labels)
+                .ok();
+        }
+    }
+
     fn add_peer(&mut self, connection: Connection<TSocket>) {
         let conn_meta = connection.metadata.clone();
         let peer_id = conn_meta.peer_id();
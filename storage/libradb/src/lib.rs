@@ -56,6 +56,7 @@ use itertools::{izip, zip_eq};
 use libra_crypto::hash::{CryptoHash, HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use libra_logger::prelude::*;
 use libra_metrics::OpMetrics;
+use libra_trace::{prelude::*, request_id::current_request_id};
 use libra_types::{
     account_address::AccountAddress,
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
@@ -77,6 +78,17 @@ use schemadb::{DB, DEFAULT_CF_NAME};
 use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
 use storage_interface::{DbReader, DbWriter, StartupInfo, TreeState};
 
+/// Traces the enclosing read under the id of the client request it serves, if any, so storage
+/// traces can be correlated with the request
+macro_rules! trace_request {
+    ($stage:expr, $guards:ident) => {
+        let mut $guards = vec![];
+        if let Some(request_id) = current_request_id() {
+            trace_code_block!($stage, {"request", request_id}, $guards);
+        }
+    };
+}
+
 static OP_COUNTER: Lazy<OpMetrics> = Lazy::new(|| OpMetrics::new_and_registered("storage"));
 
 const MAX_LIMIT: u64 = 1000;
@@ -520,6 +532,7 @@ impl DbReader for LibraDB {
         let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
             .with_label_values(&["get_transactions"])
             .start_timer();
+        trace_request!("libradb::get_transactions", _trace_guards);
 
         error_if_too_many_requested(limit, MAX_LIMIT)?;
 
@@ -571,6 +584,7 @@ impl DbReader for LibraDB {
         let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
             .with_label_values(&["get_events"])
             .start_timer();
+        trace_request!("libradb::get_events", _trace_guards);
 
         let version = self
             .ledger_store
@@ -650,6 +664,7 @@ impl DbReader for LibraDB {
         let _timer = LIBRA_STORAGE_API_LATENCY_SECONDS
             .with_label_values(&["get_account_state_with_proof"])
             .start_timer();
+        trace_request!("libradb::get_account_state_with_proof", _trace_guards);
 
         ensure!(
             version <= ledger_version,
@@ -0,0 +1,17 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines error types returned by [`DbReader`](crate::DbReader) implementations.
+
+use thiserror::Error;
+
+/// This enum defines errors caused by the read itself rather than by a failure of storage.
+#[derive(Debug, Error)]
+pub enum LibraDbError {
+    /// A requested item is not found.
+    #[error("{0} not found.")]
+    NotFound(String),
+    /// Requested too many items.
+    #[error("Too many items requested: at least {0} requested, max is {1}")]
+    TooManyRequested(u64, u64),
+}
@@ -26,6 +26,7 @@ use std::{
 };
 use thiserror::Error;
 
+pub mod errors;
 #[cfg(any(feature = "testing", feature = "fuzzing"))]
 pub mod mock;
 pub mod state_view;
@@ -61,6 +61,15 @@ impl CurrencyInfoResource {
         (unrounded * 100.0).round() / 100.0
     }
 
+    /// Exchange rate to LBR as its on-chain 32|32 fixed-point representation, without rounding
+    pub fn to_lbr_exchange_rate(&self) -> u64 {
+        self.to_lbr_exchange_rate
+    }
+
+    pub fn can_mint(&self) -> bool {
+        self.can_mint
+    }
+
     pub fn convert_to_lbr(&self, amount: u64) -> u64 {
         (self.exchange_rate() * (amount as f32)) as u64
     }